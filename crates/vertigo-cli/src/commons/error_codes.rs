@@ -29,4 +29,5 @@ pub enum ErrorCode {
     ServeWasmReadFailed = 25,
     ServeWasmCompileFailed = 26,
     ServeWasmInstanceFailed = 27,
+    ServeWasmEngineFailed = 28,
 }
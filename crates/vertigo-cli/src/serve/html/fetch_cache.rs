@@ -4,10 +4,13 @@ use std::{
 };
 
 use parking_lot::RwLock;
+use tokio::task::JoinHandle;
 use vertigo::dev::{CallbackId, SsrFetchRequest, SsrFetchResponse};
 
 pub struct FetchCache {
     pub fetch_waiting: HashMap<SsrFetchRequest, Vec<CallbackId>>,
+    /// Requests in flight, aborted when all waiting callbacks are aborted
+    pub fetch_tasks: HashMap<SsrFetchRequest, JoinHandle<()>>,
     pub fetch_cache: BTreeMap<SsrFetchRequest, SsrFetchResponse>,
 }
 
@@ -15,6 +18,7 @@ impl FetchCache {
     pub fn new() -> Arc<RwLock<FetchCache>> {
         Arc::new(RwLock::new(FetchCache {
             fetch_waiting: HashMap::new(),
+            fetch_tasks: HashMap::new(),
             fetch_cache: BTreeMap::new(),
         }))
     }
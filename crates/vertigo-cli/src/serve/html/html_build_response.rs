@@ -1,7 +1,10 @@
 use std::{collections::HashMap, sync::Arc};
 
 use crate::serve::{
-    html::{HtmlNode, element::AllElements, fetch_cache::FetchCache, html_element::HtmlElement},
+    html::{
+        HtmlNode, element::AllElements, fetch_cache::FetchCache, html_element::HtmlElement,
+        html_node_convert_to_string::convert_fragment_to_string, stream_patch::body_patches,
+    },
     mount_path::MountConfig,
     response_state::ResponseState,
 };
@@ -9,16 +12,78 @@ use actix_web::http::StatusCode;
 use parking_lot::RwLock;
 use vertigo::{
     JsJsonSerialize,
-    dev::{SsrFetchCache, VERTIGO_MOUNT_POINT_PLACEHOLDER, VERTIGO_PUBLIC_BUILD_PATH_PLACEHOLDER},
+    dev::{
+        SsrContext, SsrFetchCache, VERTIGO_MOUNT_POINT_PLACEHOLDER,
+        VERTIGO_PUBLIC_BUILD_PATH_PLACEHOLDER,
+    },
 };
 
+const BODY_END: &str = "</body>";
+
+/// Part of the document to render
+pub enum DocumentPart<'a> {
+    /// Whole document
+    Whole,
+    /// Beginning of the document, up to the current content of the `<body>` (sent before the data is ready)
+    Start,
+    /// The rest of the document after its [start](DocumentPart::Start) was sent,
+    /// with patches of the `<body>` changed since `sent_body` was rendered
+    Rest { sent_body: &'a HtmlElement },
+}
+
+/// Parameters of a single SSR document
+pub struct RenderParams<'a> {
+    pub status: StatusCode,
+    /// Values to be replayed by the browser during hydration
+    pub ssr_context: &'a SsrContext,
+    pub part: DocumentPart<'a>,
+    /// The content of the `<body>` is skipped and hydration is disabled,
+    /// so the app is rendered from scratch in the browser.
+    pub shell: bool,
+}
+
+/// Current content of the `<body>` of the app
+pub fn current_body(all_elements: &AllElements) -> Option<HtmlElement> {
+    let (mut root_html, _) = all_elements.get_response(false);
+
+    let mut body = None;
+    root_html.modify(&[("body", 0)], |element| body = Some(element.clone()));
+    body
+}
+
+/// Render patches of the `<body>` to be streamed after the beginning of the document
+pub fn render_patches(patches: Vec<HtmlNode>, mount_path: &MountConfig) -> String {
+    replace_placeholders(convert_fragment_to_string(patches, true), mount_path)
+}
+
+fn replace_placeholders(body: String, mount_path: &MountConfig) -> String {
+    let body = body.replace(
+        VERTIGO_PUBLIC_BUILD_PATH_PLACEHOLDER,
+        &mount_path.dest_http_root(),
+    );
+
+    if mount_path.mount_point() != "/" {
+        body.replace(VERTIGO_MOUNT_POINT_PLACEHOLDER, mount_path.mount_point())
+    } else {
+        body.replace(VERTIGO_MOUNT_POINT_PLACEHOLDER, "")
+    }
+}
+
+/// Build SSR document
 pub fn build_response(
     all_elements: &AllElements,
     env: &HashMap<String, String>,
     mount_path: &MountConfig,
-    status: StatusCode,
     fetch: &Arc<RwLock<FetchCache>>,
+    params: RenderParams,
 ) -> ResponseState {
+    let RenderParams {
+        status,
+        ssr_context,
+        part,
+        shell,
+    } = params;
+
     let (mut root_html, css) = all_elements.get_response(false);
 
     if let HtmlNode::Element(html) = &mut root_html {
@@ -33,6 +98,34 @@ pub fn build_response(
         return ResponseState::internal_error("Missing <html> element");
     }
 
+    let end_nodes = || body_end(env, mount_path, fetch, ssr_context, shell);
+
+    if let DocumentPart::Rest { sent_body } = part {
+        let mut rest = None;
+
+        root_html.modify(&[("body", 0)], |body| {
+            if shell {
+                body.children.clear();
+            }
+
+            let mut nodes = body_patches(sent_body, body);
+            // Styles could have been added after the head was sent
+            nodes.push(css);
+            nodes.extend(end_nodes());
+
+            rest = Some(convert_fragment_to_string(nodes, true));
+        });
+
+        let Some(rest) = rest else {
+            return ResponseState::internal_error("Missing <body> element");
+        };
+
+        let rest = replace_placeholders(rest, mount_path);
+        return ResponseState::html(status, [rest.as_str(), "</body>\n</html>\n"].concat());
+    }
+
+    let whole = matches!(part, DocumentPart::Whole);
+
     let head_exists = root_html.modify(&[("head", 0)], |head| {
         if mount_path.wasm_preload {
             let script_preconnect = HtmlElement::new("link")
@@ -49,66 +142,81 @@ pub fn build_response(
         log::info!("Missing <head> element");
     }
 
-    let body_exists = root_html.modify(&[("body", 0)], move |body| {
-        // Generate SSR cache
-        let fetch_cache = {
-            let fetch_cache_guard = fetch.read();
-            SsrFetchCache::new(&fetch_cache_guard.fetch_cache)
-                .to_json()
-                .convert_to_string()
-        };
-
-        // Create hidden div
-        let mut data_div = HtmlElement::new("div")
-            .attr("id", "v-metadata")
-            .attr("hidden", "hidden")
-            .attr("style", "display: none")
-            .attr("data-fetch-cache", fetch_cache);
+    let body_exists = root_html.modify(&[("body", 0)], |body| {
+        if shell {
+            body.children.clear();
+        }
 
-        // Add custom env parameters
-        for (env_name, env_value) in env {
-            data_div.add_attr(format!("data-env-{env_name}"), env_value);
+        if whole {
+            body.children.extend(end_nodes());
         }
+    });
 
-        // Add dynamic values for public path
-        data_div.add_attr("data-env-vertigo-mount-point", mount_path.mount_point());
-        data_div.add_attr("data-env-vertigo-public-path", mount_path.dest_http_root());
-
-        // Add disable hydration flag
-        data_div.add_attr(
-            "data-env-disable-hydration",
-            if mount_path.disable_hydration {
-                "true"
-            } else {
-                "false"
-            },
-        );
+    if !body_exists {
+        return ResponseState::internal_error("Missing <body> element");
+    }
 
-        // WASM script starter
-        let script = HtmlElement::new("script")
-            .attr("type", "module")
-            .attr("data-vertigo-run-wasm", mount_path.get_wasm_http_path())
-            .attr("src", mount_path.get_run_js_http_path());
+    let mut document = replace_placeholders(root_html.convert_to_string(true), mount_path);
 
-        body.add_child(script);
+    if !whole && let Some(position) = document.rfind(BODY_END) {
+        // The rest of the document is sent later
+        document.truncate(position);
+    }
 
-        body.add_child(data_div);
-    });
+    ResponseState::html(status, document)
+}
 
-    if body_exists {
-        let mut body = root_html.convert_to_string(true).replace(
-            VERTIGO_PUBLIC_BUILD_PATH_PLACEHOLDER,
-            &mount_path.dest_http_root(),
+/// Script starting the app and its metadata, placed at the end of the `<body>`
+fn body_end(
+    env: &HashMap<String, String>,
+    mount_path: &MountConfig,
+    fetch: &Arc<RwLock<FetchCache>>,
+    ssr_context: &SsrContext,
+    shell: bool,
+) -> [HtmlNode; 2] {
+    // Generate SSR cache
+    let fetch_cache = {
+        let fetch_cache_guard = fetch.read();
+        SsrFetchCache::new(&fetch_cache_guard.fetch_cache)
+            .to_json()
+            .convert_to_string()
+    };
+
+    // Create hidden div
+    let mut data_div = HtmlElement::new("div")
+        .attr("id", "v-metadata")
+        .attr("hidden", "hidden")
+        .attr("style", "display: none")
+        .attr("data-fetch-cache", fetch_cache)
+        .attr(
+            "data-ssr-context",
+            ssr_context.clone().to_json().convert_to_string(),
         );
 
-        if mount_path.mount_point() != "/" {
-            body = body.replace(VERTIGO_MOUNT_POINT_PLACEHOLDER, mount_path.mount_point());
+    // Add custom env parameters
+    for (env_name, env_value) in env {
+        data_div.add_attr(format!("data-env-{env_name}"), env_value);
+    }
+
+    // Add dynamic values for public path
+    data_div.add_attr("data-env-vertigo-mount-point", mount_path.mount_point());
+    data_div.add_attr("data-env-vertigo-public-path", mount_path.dest_http_root());
+
+    // Add disable hydration flag
+    data_div.add_attr(
+        "data-env-disable-hydration",
+        if mount_path.disable_hydration || shell {
+            "true"
         } else {
-            body = body.replace(VERTIGO_MOUNT_POINT_PLACEHOLDER, "");
-        }
+            "false"
+        },
+    );
 
-        ResponseState::html(status, body)
-    } else {
-        ResponseState::internal_error("Missing <body> element")
-    }
+    // WASM script starter
+    let script = HtmlElement::new("script")
+        .attr("type", "module")
+        .attr("data-vertigo-run-wasm", mount_path.get_wasm_http_path())
+        .attr("src", mount_path.get_run_js_http_path());
+
+    [script.into(), data_div.into()]
 }
@@ -28,6 +28,22 @@ pub fn convert_to_string(root: HtmlNode, pretty: bool) -> String {
     result.concat()
 }
 
+/// Render nodes placed inside an already sent document (without `<!DOCTYPE>`)
+pub fn convert_fragment_to_string(nodes: Vec<HtmlNode>, pretty: bool) -> String {
+    let mut result = Vec::new();
+
+    let ident = match pretty {
+        true => Format::some(),
+        false => Format::none(),
+    };
+
+    for node in nodes {
+        html_node_to_string(&mut result, ident, node);
+    }
+
+    result.concat()
+}
+
 fn html_node_to_string(result: &mut Vec<String>, ident: Format, node: HtmlNode) {
     let mut ident_str = ident.get();
 
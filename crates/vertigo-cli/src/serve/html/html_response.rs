@@ -1,18 +1,41 @@
 use actix_web::http::StatusCode;
 use parking_lot::RwLock;
-use std::{collections::HashMap, sync::Arc};
-use tokio::sync::mpsc::UnboundedSender;
-use vertigo::dev::command::{CommandForWasm, DriverDomCommand};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::{sync::mpsc::UnboundedSender, time::Instant};
+use vertigo::{
+    FetchStreamEvent, JsJsonSerialize,
+    dev::{
+        CallbackId, SsrContext, VERTIGO_MOUNT_POINT_PLACEHOLDER,
+        command::{CommandForWasm, DriverDomCommand, TimerKind},
+    },
+};
 
+use crate::commons::spawn::SpawnOwner;
 use crate::serve::{
-    html::{fetch_cache::FetchCache, html_build_response::build_response},
-    mount_path::MountConfig,
+    html::{
+        fetch_cache::FetchCache,
+        html_build_response::{
+            DocumentPart, RenderParams, build_response, current_body, render_patches,
+        },
+        html_element::HtmlElement,
+        ssr_deadline::SsrDeadline,
+        stream_patch::body_patches,
+    },
+    mount_path::{MountConfig, SsrTimeoutPolicy},
     response_state::ResponseState,
     wasm::{Message, WasmInstance},
 };
 
 use super::{element::AllElements, send_request::send_request};
 
+/// Header telling whether SSR finished (`complete`) or gave up (`timeout-<policy>`)
+pub const SSR_OUTCOME_HEADER: &str = "x-vertigo-ssr";
+
+struct Timer {
+    kind: TimerKind,
+    _task: SpawnOwner,
+}
+
 pub struct HtmlResponse {
     sender: UnboundedSender<Message>,
     mount_path: MountConfig,
@@ -21,6 +44,16 @@ pub struct HtmlResponse {
     fetch: Arc<RwLock<FetchCache>>,
     env: Arc<HashMap<String, String>>,
     status: StatusCode,
+    headers: HashMap<String, String>,
+    cookies: Vec<String>,
+    handler_pending: bool,
+    /// Content of the `<body>` already sent to the client (if the beginning of the document was sent)
+    sent_body: Option<HtmlElement>,
+    deadline: Arc<SsrDeadline>,
+    timers: HashMap<CallbackId, Timer>,
+    /// Streamed requests waiting for the error event (streaming is not available during SSR)
+    streams: HashMap<CallbackId, String>,
+    ssr_context: SsrContext,
 }
 
 impl HtmlResponse {
@@ -30,6 +63,8 @@ impl HtmlResponse {
         inst: WasmInstance,
         env: Arc<HashMap<String, String>>,
         fetch: Arc<RwLock<FetchCache>>,
+        deadline: Arc<SsrDeadline>,
+        ssr_context: SsrContext,
     ) -> Self {
         Self {
             sender,
@@ -39,34 +74,215 @@ impl HtmlResponse {
             fetch,
             env,
             status: StatusCode::default(),
+            headers: HashMap::new(),
+            cookies: Vec::new(),
+            handler_pending: false,
+            sent_body: None,
+            deadline,
+            timers: HashMap::new(),
+            streams: HashMap::new(),
+            ssr_context,
         }
     }
 
+    /// Mark that the response is going to be provided by async server handler instead of HTML
+    pub fn set_handler_pending(&mut self) {
+        self.handler_pending = true;
+    }
+
     pub fn feed(&mut self, commands: Vec<DriverDomCommand>) {
         self.all_elements.feed(commands);
     }
 
+    /// Whether there are pending fetches or timeouts (intervals don't hold the response)
     pub fn awaiting_response(&self) -> bool {
-        let guard = self.fetch.read();
-        !guard.fetch_waiting.is_empty()
+        let fetch_waiting = !self.fetch.read().fetch_waiting.is_empty();
+
+        fetch_waiting
+            || self
+                .timers
+                .values()
+                .any(|timer| matches!(timer.kind, TimerKind::Timeout))
+    }
+
+    /// Point in time after which rendering should be given up
+    pub fn deadline(&self) -> Instant {
+        self.deadline.get()
     }
 
     pub fn build_response(&self) -> ResponseState {
-        build_response(
+        if self.handler_pending {
+            return ResponseState::plain(
+                StatusCode::GATEWAY_TIMEOUT,
+                "Server handler did not respond",
+            );
+        }
+
+        with_outcome(self.render(false), "complete")
+    }
+
+    /// Build response according to the timeout policy
+    pub fn timeout_response(&self) -> ResponseState {
+        if self.handler_pending {
+            return ResponseState::plain(
+                StatusCode::GATEWAY_TIMEOUT,
+                "Server handler did not respond",
+            );
+        }
+
+        let policy = self.mount_path.ssr_timeout_policy;
+
+        log::warn!(
+            "SSR timeout after {}ms, responding with {policy}",
+            self.deadline.timeout().as_millis()
+        );
+
+        let response = match policy {
+            SsrTimeoutPolicy::Partial => self.render(false),
+            SsrTimeoutPolicy::Shell => self.render(true),
+            SsrTimeoutPolicy::Error if self.sent_body.is_some() => {
+                log::error!("Can't respond with error, beginning of the page was already sent");
+                self.render(false)
+            }
+            SsrTimeoutPolicy::Error => {
+                ResponseState::plain(StatusCode::GATEWAY_TIMEOUT, "SSR timeout")
+            }
+        };
+
+        with_outcome(response, format!("timeout-{policy}"))
+    }
+
+    fn render(&self, shell: bool) -> ResponseState {
+        let part = match &self.sent_body {
+            Some(sent_body) => DocumentPart::Rest { sent_body },
+            None => DocumentPart::Whole,
+        };
+
+        self.render_part(part, shell)
+    }
+
+    fn render_part(&self, part: DocumentPart, shell: bool) -> ResponseState {
+        let response = build_response(
             &self.all_elements,
             &self.env,
             &self.mount_path,
-            self.status,
             &self.fetch,
-        )
+            RenderParams {
+                status: self.status,
+                ssr_context: &self.ssr_context,
+                part,
+                shell,
+            },
+        );
+
+        self.with_headers(response)
+    }
+
+    /// Build response containing the beginning of the document, up to the current content of the `<body>`
+    ///
+    /// After flushing, status, headers and redirects can't be changed anymore. Changes of the `<body>`
+    /// are sent as patches by [flush_patches](Self::flush_patches) and [build_response](Self::build_response)
+    /// returns only the rest of the document.
+    pub fn flush_start(&mut self) -> Option<ResponseState> {
+        if self.handler_pending || self.sent_body.is_some() {
+            return None;
+        }
+
+        let sent_body = current_body(&self.all_elements)?;
+        let response = self.render_part(DocumentPart::Start, false);
+        self.sent_body = Some(sent_body);
+
+        Some(response)
+    }
+
+    /// Patches of the `<body>` changed since it was last sent
+    pub fn flush_patches(&mut self) -> Option<Vec<u8>> {
+        let sent_body = self.sent_body.as_ref()?;
+        let current = current_body(&self.all_elements)?;
+
+        let patches = body_patches(sent_body, &current);
+
+        if patches.is_empty() {
+            return None;
+        }
+
+        self.sent_body = Some(current);
+
+        Some(render_patches(patches, &self.mount_path).into_bytes())
+    }
+
+    fn redirect_response(&self, url: String, status: u16) -> ResponseState {
+        let status = match StatusCode::from_u16(status) {
+            Ok(status) if status.is_redirection() => status,
+            _ => {
+                log::error!("Invalid redirection status code requested: {status}");
+                StatusCode::FOUND
+            }
+        };
+
+        let mount_point = match self.mount_path.mount_point() {
+            "/" => "",
+            mount_point => mount_point,
+        };
+
+        let location = url.replace(VERTIGO_MOUNT_POINT_PLACEHOLDER, mount_point);
+
+        self.with_headers(ResponseState::redirect(status, location))
+    }
+
+    fn set_timer(&mut self, callback: CallbackId, duration: u32, kind: TimerKind) {
+        let duration = Duration::from_millis(duration.into());
+
+        if duration.is_zero() && matches!(kind, TimerKind::Timeout) {
+            self.inst
+                .wasm_command(CommandForWasm::TimerCall { callback });
+            return;
+        }
+
+        if duration > self.deadline.time_left() {
+            log::debug!(
+                "Timer of {}ms exceeds SSR time budget, skipping",
+                duration.as_millis()
+            );
+            return;
+        }
+
+        let sender = self.sender.clone();
+
+        let task = match kind {
+            TimerKind::Timeout => SpawnOwner::new(async move {
+                tokio::time::sleep(duration).await;
+                sender
+                    .send(Message::TimerFired { callback })
+                    .inspect_err(|err| log::error!("Error sending TimerFired: {err}"))
+                    .unwrap_or_default();
+            }),
+            TimerKind::Interval => SpawnOwner::new(async move {
+                // Interval can't have zero period
+                let period = duration.max(Duration::from_millis(1));
+                let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+
+                loop {
+                    interval.tick().await;
+                    if sender.send(Message::TimerFired { callback }).is_err() {
+                        break;
+                    }
+                }
+            }),
+        };
+
+        self.timers.insert(callback, Timer { kind, _task: task });
+    }
+
+    fn with_headers(&self, mut response: ResponseState) -> ResponseState {
+        response.headers.extend(self.headers.clone());
+        response.cookies.extend(self.cookies.iter().cloned());
+
+        response
     }
 
     pub fn process_message(&mut self, message: Message) -> Option<ResponseState> {
         match message {
-            Message::TimeoutAndSendResponse => {
-                log::info!("timeout");
-                Some(self.build_response())
-            }
             Message::DomUpdate(update) => {
                 self.feed(update);
 
@@ -77,7 +293,28 @@ impl HtmlResponse {
                     message.unwrap_or_else(|| "panic message decoding problem".to_string());
                 Some(ResponseState::internal_error(message))
             }
-            Message::SetTimeoutZero { callback } => {
+            Message::TimerSet {
+                callback,
+                duration,
+                kind,
+            } => {
+                self.set_timer(callback, duration, kind);
+                None
+            }
+            Message::TimerClear { callback } => {
+                self.timers.remove(&callback);
+                None
+            }
+            Message::TimerFired { callback } => {
+                let Some(timer) = self.timers.get(&callback) else {
+                    // Already cleared
+                    return None;
+                };
+
+                if matches!(timer.kind, TimerKind::Timeout) {
+                    self.timers.remove(&callback);
+                }
+
                 self.inst
                     .wasm_command(CommandForWasm::TimerCall { callback });
                 None
@@ -86,14 +323,19 @@ impl HtmlResponse {
                 let mut guard = self.fetch.write();
 
                 if let Some(response) = guard.fetch_cache.get(&request) {
-                    self.inst.send_fetch_response(callback, response.clone());
-                    return None;
+                    if !response.is_retriable() {
+                        self.inst.send_fetch_response(callback, response.clone());
+                        return None;
+                    }
+
+                    // Repeated after failure (i.e. by RetryPolicy), so fetch it again
+                    guard.fetch_cache.remove(&request);
                 }
 
                 if let Some(callbacks) = guard.fetch_waiting.get_mut(&request) {
                     callbacks.push(callback);
                 } else {
-                    actix_web::rt::spawn({
+                    let task = actix_web::rt::spawn({
                         let request = request.clone();
                         let sender = self.sender.clone();
 
@@ -109,23 +351,50 @@ impl HtmlResponse {
                         }
                     });
 
-                    guard.fetch_waiting.insert(request, vec![callback]);
+                    guard.fetch_waiting.insert(request.clone(), vec![callback]);
+                    guard.fetch_tasks.insert(request, task);
                 }
                 None
             }
 
-            Message::FetchResponse { request, response } => {
+            Message::FetchAbort { callback } => {
                 let mut guard = self.fetch.write();
 
-                let exist = guard.fetch_cache.insert(request.clone(), response.clone());
-                assert!(exist.is_none());
+                let unused_request =
+                    guard
+                        .fetch_waiting
+                        .iter_mut()
+                        .find_map(|(request, callbacks)| {
+                            let position = callbacks.iter().position(|item| *item == callback)?;
+                            callbacks.remove(position);
+                            callbacks.is_empty().then(|| request.clone())
+                        });
+
+                // Nobody waits for the response anymore
+                if let Some(request) = unused_request {
+                    guard.fetch_waiting.remove(&request);
+
+                    if let Some(task) = guard.fetch_tasks.remove(&request) {
+                        task.abort();
+                    }
+                }
+
+                None
+            }
+
+            Message::FetchResponse { request, response } => {
+                let mut guard = self.fetch.write();
 
-                let callback_list = guard.fetch_waiting.remove(&request);
+                guard.fetch_tasks.remove(&request);
 
-                let Some(callback_list) = callback_list else {
-                    unreachable!();
+                let Some(callback_list) = guard.fetch_waiting.remove(&request) else {
+                    // Aborted before the response arrived
+                    return None;
                 };
 
+                let exist = guard.fetch_cache.insert(request.clone(), response.clone());
+                assert!(exist.is_none());
+
                 for callback_id in callback_list {
                     self.inst.send_fetch_response(callback_id, response.clone());
                 }
@@ -133,6 +402,41 @@ impl HtmlResponse {
                 None
             }
 
+            Message::FetchStream { callback, request } => {
+                self.streams.insert(
+                    callback,
+                    format!(
+                        "Streamed fetch is not available during SSR: {}",
+                        request.url
+                    ),
+                );
+
+                // Deliver the error later, the app may be still inside the call which started the stream
+                self.sender
+                    .send(Message::FetchStreamFailed { callback })
+                    .inspect_err(|err| log::error!("Error sending FetchStreamFailed: {err}"))
+                    .unwrap_or_default();
+                None
+            }
+
+            Message::FetchStreamAbort { callback } => {
+                self.streams.remove(&callback);
+                None
+            }
+
+            Message::FetchStreamFailed { callback } => {
+                let Some(message) = self.streams.remove(&callback) else {
+                    // Already aborted
+                    return None;
+                };
+
+                self.inst.wasm_command(CommandForWasm::CallbackCall {
+                    callback_id: callback,
+                    value: FetchStreamEvent::Error { message }.to_json(),
+                });
+                None
+            }
+
             Message::SetStatus(status) => {
                 match StatusCode::from_u16(status) {
                     Ok(status) => self.status = status,
@@ -140,6 +444,34 @@ impl HtmlResponse {
                 }
                 None
             }
+
+            Message::SetHeader { name, value } => {
+                self.headers.insert(name.to_lowercase(), value);
+                None
+            }
+
+            Message::SetCookie(cookie) => {
+                self.cookies.push(cookie);
+                None
+            }
+
+            Message::Redirect { url, status } => {
+                if self.sent_body.is_some() {
+                    log::error!("Can't redirect to {url}, beginning of the page was already sent");
+                    return None;
+                }
+
+                Some(self.redirect_response(url, status))
+            }
+
+            Message::HandlerResponse(response) => Some(self.with_headers(response)),
         }
     }
 }
+
+fn with_outcome(mut response: ResponseState, outcome: impl Into<String>) -> ResponseState {
+    response
+        .headers
+        .insert(SSR_OUTCOME_HEADER.into(), outcome.into());
+    response
+}
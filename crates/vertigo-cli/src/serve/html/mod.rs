@@ -5,11 +5,16 @@ mod html_node;
 mod html_node_convert_to_string;
 mod html_response;
 mod send_request;
+mod stream_patch;
 
 pub use html_node::HtmlNode;
 pub use html_response::HtmlResponse;
+pub use send_request::{convert_to_jsjson, convert_to_serde_value};
 
 mod html_build_response;
 
 mod fetch_cache;
 pub use fetch_cache::FetchCache;
+
+mod ssr_deadline;
+pub use ssr_deadline::SsrDeadline;
@@ -3,10 +3,13 @@ use serde_json::{Map, Number, Value};
 use std::collections::BTreeMap;
 use vertigo::{
     JsJson, JsJsonNumber,
-    dev::{SsrFetchRequest, SsrFetchRequestBody, SsrFetchResponse, SsrFetchResponseContent},
+    dev::{
+        SsrFetchRequest, SsrFetchRequestBody, SsrFetchResponse, SsrFetchResponseContent,
+        SsrFetchResponseKind, SsrFormField, SsrMultipartPart, SsrMultipartValue,
+    },
 };
 
-fn convert_to_jsjson(value: Value) -> JsJson {
+pub fn convert_to_jsjson(value: Value) -> JsJson {
     match value {
         Value::Bool(true) => JsJson::True,
         Value::Bool(false) => JsJson::False,
@@ -43,7 +46,7 @@ fn convert_to_jsjson(value: Value) -> JsJson {
     }
 }
 
-fn convert_to_serde_value(value: JsJson) -> Value {
+pub fn convert_to_serde_value(value: JsJson) -> Value {
     match value {
         JsJson::True => Value::Bool(true),
         JsJson::False => Value::Bool(false),
@@ -90,14 +93,108 @@ pub async fn send_request(request_params: SsrFetchRequest) -> SsrFetchResponse {
 enum BodyToSend {
     None,
     String(String),
+    Bytes(Vec<u8>),
+}
+
+fn set_default_content_type(headers: &mut BTreeMap<String, String>, content_type: &str) {
+    if !headers.contains_key("content-type") {
+        headers.insert("content-type".into(), content_type.into());
+    }
+}
+
+fn url_encode(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'*' => {
+                result.push(byte as char)
+            }
+            b' ' => result.push('+'),
+            _ => result.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    result
+}
+
+fn encode_form(fields: &[SsrFormField]) -> String {
+    fields
+        .iter()
+        .map(|field| format!("{}={}", url_encode(&field.name), url_encode(&field.value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn escape_quoted(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(['\r', '\n'], " ")
+}
+
+fn encode_multipart(boundary: &str, parts: &[SsrMultipartPart]) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+
+    for part in parts {
+        body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+
+        let name = escape_quoted(&part.name);
+
+        match &part.value {
+            SsrMultipartValue::Text { text } => {
+                body.extend_from_slice(
+                    format!("Content-Disposition: form-data; name=\"{name}\"\r\n\r\n").as_bytes(),
+                );
+                body.extend_from_slice(text.as_bytes());
+            }
+            SsrMultipartValue::Bytes {
+                data,
+                file_name,
+                content_type,
+            } => {
+                let file_name = escape_quoted(file_name.as_deref().unwrap_or("blob"));
+                let content_type = content_type
+                    .as_deref()
+                    .unwrap_or("application/octet-stream");
+                body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{name}\"; filename=\"{file_name}\"\r\nContent-Type: {content_type}\r\n\r\n"
+                    )
+                    .as_bytes(),
+                );
+                body.extend_from_slice(data);
+            }
+            SsrMultipartValue::File { .. } => {
+                return Err(format!(
+                    "File part \"{}\" is not available during SSR",
+                    part.name
+                ));
+            }
+        }
+
+        body.extend_from_slice(b"\r\n");
+    }
+
+    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+    Ok(body)
+}
+
+fn multipart_boundary() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|time| time.as_nanos())
+        .unwrap_or_default();
+
+    format!("----VertigoFormBoundary{nanos:x}")
 }
 
 fn get_headers_and_body(
     mut headers: BTreeMap<String, String>,
     body: &SsrFetchRequestBody,
-) -> (BTreeMap<String, String>, BodyToSend) {
-    match body.clone() {
-        SsrFetchRequestBody::None => (headers, BodyToSend::None),
+) -> Result<(BTreeMap<String, String>, BodyToSend), String> {
+    let body = match body.clone() {
+        SsrFetchRequestBody::None => BodyToSend::None,
         SsrFetchRequestBody::Data { data } => {
             if !headers.contains_key("content-type") {
                 headers.insert(
@@ -111,9 +208,37 @@ fn get_headers_and_body(
                 .inspect_err(|err| log::error!("Error serializing body: {err}"))
                 .unwrap_or_default();
 
-            (headers, BodyToSend::String(json_str))
+            BodyToSend::String(json_str)
         }
-    }
+        SsrFetchRequestBody::File { .. } => {
+            return Err("File body is not available during SSR".into());
+        }
+        SsrFetchRequestBody::Text { text } => {
+            set_default_content_type(&mut headers, "text/plain; charset=utf-8");
+            BodyToSend::String(text)
+        }
+        SsrFetchRequestBody::Bytes { data } => {
+            set_default_content_type(&mut headers, "application/octet-stream");
+            BodyToSend::Bytes(data)
+        }
+        SsrFetchRequestBody::FormUrlEncoded { fields } => {
+            set_default_content_type(
+                &mut headers,
+                "application/x-www-form-urlencoded; charset=utf-8",
+            );
+            BodyToSend::String(encode_form(&fields))
+        }
+        SsrFetchRequestBody::Multipart { parts } => {
+            let boundary = multipart_boundary();
+            headers.insert(
+                "content-type".into(),
+                format!("multipart/form-data; boundary={boundary}"),
+            );
+            BodyToSend::Bytes(encode_multipart(&boundary, &parts)?)
+        }
+    };
+
+    Ok((headers, body))
 }
 
 fn clear_headers(headers: &BTreeMap<String, String>) -> BTreeMap<String, String> {
@@ -141,7 +266,10 @@ async fn send_request_inner(request_params: SsrFetchRequest) -> SsrFetchResponse
     };
 
     let headers = clear_headers(&request_params.headers);
-    let (headers, body) = get_headers_and_body(headers, &request_params.body);
+    let (headers, body) = match get_headers_and_body(headers, &request_params.body) {
+        Ok(headers_and_body) => headers_and_body,
+        Err(message) => return SsrFetchResponse::Err { message },
+    };
 
     for (key, value) in headers {
         request = request.append_header((key, value));
@@ -150,6 +278,7 @@ async fn send_request_inner(request_params: SsrFetchRequest) -> SsrFetchResponse
     let response = match body {
         BodyToSend::None => request.send(),
         BodyToSend::String(body) => request.send_body(body),
+        BodyToSend::Bytes(body) => request.send_body(body),
     };
 
     let mut response = match response.await {
@@ -163,11 +292,14 @@ async fn send_request_inner(request_params: SsrFetchRequest) -> SsrFetchResponse
 
     let status = response.status().as_u16() as u32;
 
-    let content_type = response
+    let headers = response
         .headers()
-        .get("Content-Type")
-        .cloned()
-        .and_then(|v| v.to_str().ok().map(ToString::to_string));
+        .iter()
+        .filter_map(|(name, value)| {
+            let value = value.to_str().ok()?;
+            Some((name.as_str().to_lowercase(), value.to_string()))
+        })
+        .collect::<BTreeMap<_, _>>();
 
     let buffer = match response.body().await {
         Ok(response) => response.to_vec(),
@@ -178,26 +310,119 @@ async fn send_request_inner(request_params: SsrFetchRequest) -> SsrFetchResponse
         }
     };
 
-    match content_type {
-        Some(v) if v.starts_with("text/plain;") => {
+    let content_type = headers.get("content-type").map(String::as_str);
+
+    let response = match SsrFetchResponseContent::kind_for(content_type) {
+        SsrFetchResponseKind::Text => {
             let text_response = String::from_utf8_lossy(&buffer);
-            SsrFetchResponse::Ok {
-                status,
-                response: SsrFetchResponseContent::Text(text_response.into()),
-            }
+            SsrFetchResponseContent::Text(text_response.into())
         }
-        _ => match serde_json::from_slice::<Value>(buffer.as_slice()) {
-            Ok(json) => {
-                let json = convert_to_jsjson(json);
-
-                SsrFetchResponse::Ok {
-                    status,
-                    response: SsrFetchResponseContent::Json(json),
-                }
+        SsrFetchResponseKind::Bytes => SsrFetchResponseContent::Bytes(buffer),
+        SsrFetchResponseKind::Json => match serde_json::from_slice::<Value>(buffer.as_slice()) {
+            Ok(json) => SsrFetchResponseContent::Json(convert_to_jsjson(json)),
+            Err(error) => {
+                return SsrFetchResponse::Err {
+                    message: format!("response decoding json problem error={error}"),
+                };
             }
-            Err(error) => SsrFetchResponse::Err {
-                message: format!("response decoding json problem error={error}"),
-            },
         },
+    };
+
+    SsrFetchResponse::Ok {
+        status,
+        headers,
+        response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use vertigo::{
+        FetchMethod,
+        dev::{
+            SsrFetchRequest, SsrFetchRequestBody, SsrFetchResponse, SsrFormField, SsrMultipartPart,
+            SsrMultipartValue,
+        },
+    };
+
+    use super::{encode_form, encode_multipart, send_request};
+
+    #[test]
+    fn test_encode_form() {
+        let fields = vec![
+            SsrFormField {
+                name: "name".into(),
+                value: "John Smith".into(),
+            },
+            SsrFormField {
+                name: "q&a".into(),
+                value: "zażółć=1".into(),
+            },
+        ];
+
+        assert_eq!(
+            encode_form(&fields),
+            "name=John+Smith&q%26a=za%C5%BC%C3%B3%C5%82%C4%87%3D1"
+        );
+    }
+
+    #[test]
+    fn test_encode_multipart() {
+        let parts = vec![
+            SsrMultipartPart {
+                name: "title".into(),
+                value: SsrMultipartValue::Text {
+                    text: "Holidays".into(),
+                },
+            },
+            SsrMultipartPart {
+                name: "thumb".into(),
+                value: SsrMultipartValue::Bytes {
+                    data: vec![1, 2, 3],
+                    file_name: Some("thumb.png".into()),
+                    content_type: Some("image/png".into()),
+                },
+            },
+        ];
+
+        let mut expected =
+            b"--XYZ\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nHolidays\r\n\
+            --XYZ\r\nContent-Disposition: form-data; name=\"thumb\"; filename=\"thumb.png\"\r\n\
+            Content-Type: image/png\r\n\r\n"
+                .to_vec();
+        expected.extend_from_slice(&[1, 2, 3]);
+        expected.extend_from_slice(b"\r\n--XYZ--\r\n");
+
+        assert_eq!(encode_multipart("XYZ", &parts), Ok(expected));
+    }
+
+    #[test]
+    fn test_encode_multipart_file() {
+        let parts = vec![SsrMultipartPart {
+            name: "photo".into(),
+            value: SsrMultipartValue::File { file: 1 },
+        }];
+
+        assert_eq!(
+            encode_multipart("XYZ", &parts),
+            Err("File part \"photo\" is not available during SSR".to_string())
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_send_file_body() {
+        let request = SsrFetchRequest {
+            method: FetchMethod::POST,
+            url: "http://127.0.0.1:1/upload".into(),
+            headers: BTreeMap::new(),
+            body: SsrFetchRequestBody::File { file: 1 },
+        };
+
+        let SsrFetchResponse::Err { message } = send_request(request).await else {
+            panic!("Expected error response");
+        };
+        assert_eq!(message, "File body is not available during SSR");
     }
 }
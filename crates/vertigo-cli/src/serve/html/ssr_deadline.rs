@@ -0,0 +1,36 @@
+use parking_lot::RwLock;
+use std::{sync::Arc, time::Duration};
+use tokio::time::Instant;
+
+/// Time budget for rendering a page in SSR
+pub struct SsrDeadline {
+    started: Instant,
+    timeout: RwLock<Duration>,
+}
+
+impl SsrDeadline {
+    pub fn new(timeout: Duration) -> Arc<SsrDeadline> {
+        Arc::new(SsrDeadline {
+            started: Instant::now(),
+            timeout: RwLock::new(timeout),
+        })
+    }
+
+    /// Change the timeout (counted from the start of rendering)
+    pub fn set_timeout(&self, timeout: Duration) {
+        *self.timeout.write() = timeout;
+    }
+
+    pub fn timeout(&self) -> Duration {
+        *self.timeout.read()
+    }
+
+    /// Point in time after which rendering should be given up
+    pub fn get(&self) -> Instant {
+        self.started + self.timeout()
+    }
+
+    pub fn time_left(&self) -> Duration {
+        self.get().saturating_duration_since(Instant::now())
+    }
+}
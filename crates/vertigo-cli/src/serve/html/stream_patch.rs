@@ -0,0 +1,178 @@
+use super::{HtmlNode, html_element::HtmlElement};
+
+/// Attribute of `<template>` with new content of a part of the `<body>` streamed after its beginning.
+///
+/// The value is a path of indexes of child elements starting from `<body>` (i.e. `0/2`),
+/// empty path replaces the whole content of the `<body>`. The JS driver applies the patches
+/// in order before hydration, so the path refers to the document with previous patches applied.
+pub const PATCH_ATTR: &str = "data-vertigo-patch";
+
+/// Content of an element as rendered, comments are skipped and adjacent texts are merged
+#[derive(PartialEq)]
+enum Content<'a> {
+    Text(String),
+    Element(&'a str),
+}
+
+fn content(element: &HtmlElement) -> Vec<Content<'_>> {
+    let mut result = Vec::new();
+
+    for child in &element.children {
+        match child {
+            HtmlNode::Text(text) => match result.last_mut() {
+                Some(Content::Text(last)) => last.push_str(text),
+                _ => result.push(Content::Text(text.clone())),
+            },
+            HtmlNode::Element(element) => result.push(Content::Element(&element.name)),
+            HtmlNode::Comment(_) => {}
+        }
+    }
+
+    result
+}
+
+fn child_elements(element: &HtmlElement) -> impl Iterator<Item = &HtmlElement> {
+    element.children.iter().filter_map(|child| match child {
+        HtmlNode::Element(element) => Some(element),
+        _ => None,
+    })
+}
+
+fn patch(path: &[usize], children: impl IntoIterator<Item = HtmlNode>) -> HtmlNode {
+    let path = path
+        .iter()
+        .map(|index| index.to_string())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    let mut template = HtmlElement::new("template").attr(PATCH_ATTR, path);
+    template.children.extend(children);
+    template.into()
+}
+
+fn diff_children(
+    sent: &HtmlElement,
+    current: &HtmlElement,
+    path: &mut Vec<usize>,
+    patches: &mut Vec<HtmlNode>,
+) {
+    for (index, (sent, current)) in child_elements(sent)
+        .zip(child_elements(current))
+        .enumerate()
+    {
+        path.push(index);
+
+        if sent.attr == current.attr && content(sent) == content(current) {
+            diff_children(sent, current, path, patches);
+        } else {
+            patches.push(patch(path, [current.clone().into()]));
+        }
+
+        path.pop();
+    }
+}
+
+/// Templates replacing parts of `sent` body so it becomes the `current` one
+pub fn body_patches(sent: &HtmlElement, current: &HtmlElement) -> Vec<HtmlNode> {
+    let mut patches = Vec::new();
+
+    if content(sent) == content(current) {
+        diff_children(sent, current, &mut Vec::new(), &mut patches);
+    } else {
+        patches.push(patch(&[], current.children.iter().cloned()));
+    }
+
+    patches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HtmlElement, HtmlNode, body_patches};
+    use crate::serve::html::html_node_convert_to_string::convert_fragment_to_string;
+
+    fn text(text: &str) -> HtmlNode {
+        HtmlNode::Text(text.into())
+    }
+
+    fn render(patches: Vec<HtmlNode>) -> String {
+        convert_fragment_to_string(patches, false)
+    }
+
+    fn body(list: HtmlElement) -> HtmlElement {
+        HtmlElement::new("body")
+            .child(HtmlElement::new("header").child(text("Title")).into())
+            .child(HtmlNode::Comment("list".into()))
+            .child(list.into())
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let list = HtmlElement::new("ul").child(HtmlElement::new("li").into());
+
+        assert!(body_patches(&body(list.clone()), &body(list)).is_empty());
+    }
+
+    #[test]
+    fn test_nested_change() {
+        let sent = HtmlElement::new("ul")
+            .child(HtmlElement::new("li").child(text("Loading")).into())
+            .child(HtmlElement::new("li").child(text("Loading")).into());
+
+        let current = HtmlElement::new("ul")
+            .child(HtmlElement::new("li").child(text("Loading")).into())
+            .child(HtmlElement::new("li").child(text("Ready")).into());
+
+        assert_eq!(
+            render(body_patches(&body(sent), &body(current))),
+            r#"<template data-vertigo-patch="1/1"><li>Ready</li></template>"#
+        );
+    }
+
+    #[test]
+    fn test_changed_attributes_and_children() {
+        let sent = HtmlElement::new("ul")
+            .child(HtmlElement::new("li").child(text("A")).into())
+            .child(HtmlElement::new("li").child(text("B")).into());
+
+        let current = HtmlElement::new("ul")
+            .child(
+                HtmlElement::new("li")
+                    .attr("class", "active")
+                    .child(text("A"))
+                    .into(),
+            )
+            .child(HtmlElement::new("li").child(text("B")).into())
+            .child(HtmlElement::new("li").child(text("C")).into());
+
+        // New child replaces the whole list
+        assert_eq!(
+            render(body_patches(&body(sent.clone()), &body(current))),
+            r#"<template data-vertigo-patch="1"><ul><li class="active">A</li><li>B</li><li>C</li></ul></template>"#
+        );
+
+        let current = HtmlElement::new("ul")
+            .child(
+                HtmlElement::new("li")
+                    .attr("class", "active")
+                    .child(text("A"))
+                    .into(),
+            )
+            .child(HtmlElement::new("li").child(text("B")).into());
+
+        assert_eq!(
+            render(body_patches(&body(sent), &body(current))),
+            r#"<template data-vertigo-patch="1/0"><li class="active">A</li></template>"#
+        );
+    }
+
+    #[test]
+    fn test_body_change() {
+        let sent = HtmlElement::new("body").child(text("Loading"));
+        let current = HtmlElement::new("body").child(HtmlElement::new("main").into());
+
+        assert_eq!(
+            render(body_patches(&sent, &current)),
+            r#"<template data-vertigo-patch=""><main></main></template>"#
+        );
+    }
+}
@@ -5,13 +5,14 @@ mod response_state;
 mod serve_opts;
 mod serve_run;
 mod server_state;
+mod ssr_commands;
 mod vertigo_handler;
 mod vertigo_install;
 mod wasm;
 
-pub use mount_path::{MountConfig, MountConfigBuilder};
+pub use mount_path::{DEFAULT_SSR_BODY_LIMIT, MountConfig, MountConfigBuilder, SsrTimeoutPolicy};
 pub use serve_opts::{ServeOpts, ServeOptsInner};
 pub use serve_run::run;
-pub use server_state::ServerState;
+pub use server_state::{ResponseStream, ServerState};
 pub use vertigo_handler::vertigo_handler;
 pub use vertigo_install::vertigo_install;
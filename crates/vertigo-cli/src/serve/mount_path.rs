@@ -1,15 +1,42 @@
 #![allow(clippy::question_mark)]
-use std::{collections::HashMap, path::Path, sync::Arc};
+use derive_more::Display;
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
 use vertigo::dev::VERTIGO_PUBLIC_BUILD_PATH_PLACEHOLDER;
 
 use crate::commons::{ErrorCode, models::IndexModel};
 
+/// Default time for rendering a page in SSR
+pub const DEFAULT_SSR_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default max size of the body of request passed to the app during SSR
+pub const DEFAULT_SSR_BODY_LIMIT: usize = 256 * 1024;
+
+/// What to respond with when SSR doesn't finish in time
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, Display, PartialEq)]
+pub enum SsrTimeoutPolicy {
+    /// Send HTML rendered so far
+    #[display("partial")]
+    #[default]
+    Partial,
+    /// Send `504 Gateway Timeout`
+    #[display("error")]
+    Error,
+    /// Send document with empty `<body>` so the app is rendered in the browser
+    #[display("shell")]
+    Shell,
+}
+
 pub struct MountConfigBuilder {
     pub mount_point: String,
     pub dest_dir: String,
     pub env: Vec<(String, String)>,
     pub wasm_preload: bool,
     pub disable_hydration: bool,
+    pub ssr_streaming: bool,
+    pub ssr_timeout: Duration,
+    pub ssr_timeout_policy: SsrTimeoutPolicy,
+    pub ssr_body_limit: usize,
+    pub wasm_pool_size: Option<u32>,
 }
 
 impl MountConfigBuilder {
@@ -20,6 +47,11 @@ impl MountConfigBuilder {
             env: vec![],
             wasm_preload: false,
             disable_hydration: false,
+            ssr_streaming: false,
+            ssr_timeout: DEFAULT_SSR_TIMEOUT,
+            ssr_timeout_policy: SsrTimeoutPolicy::default(),
+            ssr_body_limit: DEFAULT_SSR_BODY_LIMIT,
+            wasm_pool_size: None,
         }
     }
 
@@ -43,14 +75,66 @@ impl MountConfigBuilder {
         self
     }
 
+    pub fn ssr_streaming(mut self, ssr_streaming: bool) -> Self {
+        self.ssr_streaming = ssr_streaming;
+        self
+    }
+
+    pub fn ssr_timeout(mut self, ssr_timeout: Duration) -> Self {
+        self.ssr_timeout = ssr_timeout;
+        self
+    }
+
+    pub fn ssr_timeout_policy(mut self, ssr_timeout_policy: SsrTimeoutPolicy) -> Self {
+        self.ssr_timeout_policy = ssr_timeout_policy;
+        self
+    }
+
+    pub fn ssr_body_limit(mut self, ssr_body_limit: usize) -> Self {
+        self.ssr_body_limit = ssr_body_limit;
+        self
+    }
+
+    pub fn wasm_pool_size(mut self, wasm_pool_size: Option<u32>) -> Self {
+        self.wasm_pool_size = wasm_pool_size;
+        self
+    }
+
     pub fn build(self) -> Result<MountConfig, ErrorCode> {
-        MountConfig::new(
+        let mut mount_config = MountConfig::new(
             self.mount_point,
             self.dest_dir,
             self.env,
             self.wasm_preload,
             self.disable_hydration,
-        )
+            self.ssr_streaming,
+        )?;
+
+        mount_config.ssr_timeout = self.ssr_timeout;
+        mount_config.ssr_timeout_policy = self.ssr_timeout_policy;
+        mount_config.ssr_body_limit = self.ssr_body_limit;
+        mount_config.wasm_pool_size = self.wasm_pool_size;
+
+        Ok(mount_config)
+    }
+
+    /// Build config without reading `index.json` of the app
+    #[cfg(test)]
+    pub fn build_mock(self) -> MountConfig {
+        MountConfig {
+            mount_point: self.mount_point,
+            dest_dir: self.dest_dir,
+            run_js: format!("{VERTIGO_PUBLIC_BUILD_PATH_PLACEHOLDER}/wasm_run.js"),
+            wasm_path: format!("{VERTIGO_PUBLIC_BUILD_PATH_PLACEHOLDER}/app.wasm"),
+            env: Arc::new(self.env.into_iter().collect()),
+            wasm_preload: self.wasm_preload,
+            disable_hydration: self.disable_hydration,
+            ssr_streaming: self.ssr_streaming,
+            ssr_timeout: self.ssr_timeout,
+            ssr_timeout_policy: self.ssr_timeout_policy,
+            ssr_body_limit: self.ssr_body_limit,
+            wasm_pool_size: self.wasm_pool_size,
+        }
     }
 }
 
@@ -70,6 +154,16 @@ pub struct MountConfig {
     pub wasm_preload: bool,
     /// Whether to disable hydration
     pub disable_hydration: bool,
+    /// Whether to flush the beginning of SSR page and stream parts of the body as their data arrives
+    pub ssr_streaming: bool,
+    /// Time after which SSR gives up waiting for data
+    pub ssr_timeout: Duration,
+    /// What to respond with after the timeout
+    pub ssr_timeout_policy: SsrTimeoutPolicy,
+    /// Max size of the body of request passed to the app
+    pub ssr_body_limit: usize,
+    /// Number of preallocated WASM instance slots (pooling disabled if not set)
+    pub wasm_pool_size: Option<u32>,
 }
 
 impl MountConfig {
@@ -79,6 +173,7 @@ impl MountConfig {
         env: Vec<(String, String)>,
         wasm_preload: bool,
         disable_hydration: bool,
+        ssr_streaming: bool,
     ) -> Result<MountConfig, ErrorCode> {
         let dest_dir = dest_dir.into();
         let index_model = read_index(&dest_dir)?;
@@ -91,6 +186,11 @@ impl MountConfig {
             env: Arc::new(env.into_iter().collect()),
             wasm_preload,
             disable_hydration,
+            ssr_streaming,
+            ssr_timeout: DEFAULT_SSR_TIMEOUT,
+            ssr_timeout_policy: SsrTimeoutPolicy::default(),
+            ssr_body_limit: DEFAULT_SSR_BODY_LIMIT,
+            wasm_pool_size: None,
         })
     }
 
@@ -1,9 +1,17 @@
+use actix_web::web::Bytes;
 use std::{collections::HashMap, sync::Arc};
 
 #[derive(Clone)]
 pub struct RequestState {
+    pub method: String,
     pub url: String,
     pub env: Arc<HashMap<String, String>>,
+    /// Headers of the incoming request (names lowercased)
+    pub headers: Arc<HashMap<String, String>>,
+    /// Cookies of the incoming request (values already decoded)
+    pub cookies: Arc<HashMap<String, String>>,
+    /// Body of the incoming request
+    pub body: Bytes,
 }
 
 impl RequestState {
@@ -13,4 +21,32 @@ impl RequestState {
 
         Some(value.clone())
     }
+
+    pub fn header(&self, name: impl Into<String>) -> Option<String> {
+        let name = name.into().to_lowercase();
+        let value = self.headers.get(&name)?;
+
+        Some(value.clone())
+    }
+
+    pub fn cookie(&self, name: impl Into<String>) -> Option<String> {
+        let name = name.into();
+        let value = self.cookies.get(&name)?;
+
+        Some(value.clone())
+    }
+}
+
+#[cfg(test)]
+impl RequestState {
+    pub fn mock(method: &str, url: &str) -> Self {
+        Self {
+            method: method.into(),
+            url: url.into(),
+            env: Arc::new(HashMap::new()),
+            headers: Arc::new(HashMap::new()),
+            cookies: Arc::new(HashMap::new()),
+            body: Bytes::new(),
+        }
+    }
 }
@@ -1,5 +1,16 @@
-use actix_web::http::StatusCode;
-use std::collections::HashMap;
+use actix_web::{
+    HttpResponseBuilder,
+    cookie::{Cookie, SameSite},
+    http::StatusCode,
+    web::Bytes,
+};
+use futures::{
+    Stream, StreamExt,
+    future::{Future, ready},
+    stream,
+};
+use std::{collections::HashMap, convert::Infallible};
+use tokio::sync::mpsc::UnboundedReceiver;
 
 use vertigo::AutoJsJson;
 
@@ -17,11 +28,25 @@ fn content_type_plain() -> HashMap<String, String> {
     content_type("text/plain")
 }
 
+/// Build `Set-Cookie` header value compatible with cookies set by the browser driver
+pub fn cookie_header(name: &str, value: &str, expires_in: u64) -> String {
+    Cookie::build(name, value)
+        .path("/")
+        .same_site(SameSite::Strict)
+        .max_age(time::Duration::seconds(expires_in as i64))
+        .finish()
+        .encoded()
+        .to_string()
+}
+
 #[derive(AutoJsJson, Debug)]
 pub struct ResponseState {
     pub status: u16,
     pub headers: HashMap<String, String>,
     pub body: Vec<u8>,
+    /// Values of `Set-Cookie` headers (kept apart as there can be many of them)
+    #[js_json(default)]
+    pub cookies: Vec<String>,
 }
 
 impl ResponseState {
@@ -33,6 +58,7 @@ impl ResponseState {
             status: status.as_u16(),
             headers: content_type_html(),
             body: body.into().into_bytes(),
+            cookies: Vec::new(),
         }
     }
 
@@ -41,6 +67,19 @@ impl ResponseState {
             status: status.as_u16(),
             headers: content_type_plain(),
             body: body.into().into_bytes(),
+            cookies: Vec::new(),
+        }
+    }
+
+    pub fn redirect(status: StatusCode, location: impl Into<String>) -> Self {
+        let mut headers = content_type_plain();
+        headers.insert("location".into(), location.into());
+
+        Self {
+            status: status.as_u16(),
+            headers,
+            body: Vec::new(),
+            cookies: Vec::new(),
         }
     }
 
@@ -49,6 +88,7 @@ impl ResponseState {
             status: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
             headers: content_type_plain(),
             body: body.into().into_bytes(),
+            cookies: Vec::new(),
         }
     }
 
@@ -62,18 +102,103 @@ impl ResponseState {
         let script = chunks.join("\n").into_bytes();
         self.body.extend(script);
     }
+
+    fn response_builder(&self) -> HttpResponseBuilder {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut builder = actix_web::HttpResponse::build(status);
+
+        for (name, value) in &self.headers {
+            builder.insert_header((name.as_str(), value.as_str()));
+        }
+
+        for cookie in &self.cookies {
+            builder.append_header(("set-cookie", cookie.as_str()));
+        }
+
+        builder
+    }
+
+    /// Create streamed response starting with the body of this response,
+    /// followed by `chunks` (until the channel is closed) and `rest`
+    pub fn into_streaming(
+        self,
+        chunks: UnboundedReceiver<Vec<u8>>,
+        rest: impl Future<Output = Vec<u8>> + 'static,
+    ) -> actix_web::HttpResponse {
+        let mut builder = self.response_builder();
+        builder.streaming(self.into_chunks(chunks, rest))
+    }
+
+    fn into_chunks(
+        self,
+        chunks: UnboundedReceiver<Vec<u8>>,
+        rest: impl Future<Output = Vec<u8>> + 'static,
+    ) -> impl Stream<Item = Result<Bytes, Infallible>> {
+        let head = stream::once(ready(self.body));
+        let chunks = stream::unfold(chunks, |mut chunks| async move {
+            let chunk = chunks.recv().await?;
+            Some((chunk, chunks))
+        });
+        let rest = stream::once(rest);
+
+        head.chain(chunks)
+            .chain(rest)
+            .map(|chunk| Ok(Bytes::from(chunk)))
+    }
 }
 
 impl From<ResponseState> for actix_web::HttpResponse {
     fn from(value: ResponseState) -> Self {
-        let status =
-            StatusCode::from_u16(value.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
-        let mut builder = actix_web::HttpResponse::build(status);
+        value.response_builder().body(value.body)
+    }
+}
 
-        for (name, value) in value.headers {
-            builder.insert_header((name, value));
-        }
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+
+    use futures::{StreamExt, future::ready};
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::{ResponseState, cookie_header};
+
+    #[test]
+    fn test_response_cookies() {
+        let mut response = ResponseState::html(StatusCode::OK, "<html></html>");
+        response.cookies = vec![cookie_header("a", "1", 60), cookie_header("b", "2", 60)];
+
+        let response = actix_web::HttpResponse::from(response);
+
+        let cookies = response
+            .headers()
+            .get_all("set-cookie")
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            cookies,
+            vec![
+                "a=1; SameSite=Strict; Path=/; Max-Age=60",
+                "b=2; SameSite=Strict; Path=/; Max-Age=60"
+            ]
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_streaming_chunks() {
+        let head = ResponseState::html(StatusCode::OK, "<head>");
+
+        let (sender, receiver) = unbounded_channel();
+        sender.send(b"<patch 1>".to_vec()).unwrap_or_default();
+        sender.send(b"<patch 2>".to_vec()).unwrap_or_default();
+        drop(sender);
+
+        let chunks = head
+            .into_chunks(receiver, ready(b"<rest>".to_vec()))
+            .filter_map(|chunk| ready(chunk.ok()))
+            .collect::<Vec<_>>()
+            .await;
 
-        builder.body(value.body)
+        assert_eq!(chunks, vec!["<head>", "<patch 1>", "<patch 2>", "<rest>"]);
     }
 }
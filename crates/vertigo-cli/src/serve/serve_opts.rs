@@ -2,6 +2,7 @@ use clap::Args;
 
 use crate::commons::models::CommonOpts;
 use crate::commons::parse_key_val;
+use crate::serve::{DEFAULT_SSR_BODY_LIMIT, SsrTimeoutPolicy};
 
 #[derive(Args, Clone, Debug)]
 pub struct ServeOpts {
@@ -40,6 +41,32 @@ pub struct ServeOptsInner {
     #[arg(long, default_value_t = {false})]
     pub disable_hydration: bool,
 
+    /// Send the beginning of SSR page immediately (so the browser can start loading CSS and WASM)
+    /// and stream changed parts of the body as soon as their data is fetched
+    #[arg(long, default_value_t = {false})]
+    pub ssr_streaming: bool,
+
+    /// Time (in milliseconds) after which SSR stops waiting for data
+    /// (can be overridden per request using `Driver::set_ssr_timeout`)
+    #[arg(long, default_value_t = {10_000})]
+    pub ssr_timeout: u64,
+
+    /// What to respond with when SSR times out: HTML rendered so far (`partial`),
+    /// `504 Gateway Timeout` (`error`) or an empty page rendered by the browser (`shell`)
+    #[arg(long, default_value_t = {SsrTimeoutPolicy::default()})]
+    pub ssr_timeout_policy: SsrTimeoutPolicy,
+
+    /// Max size (in bytes) of the body of request passed to the app, larger requests get `413 Payload Too Large`
+    #[arg(long, default_value_t = {DEFAULT_SSR_BODY_LIMIT})]
+    pub ssr_body_limit: usize,
+
+    /// Use pooling allocator with provided number of WASM instance slots
+    ///
+    /// Memory of instances is preallocated and reused between requests (after being cleared),
+    /// which makes rendering cheaper. Requests exceeding the pool size wait for a free slot.
+    #[arg(long)]
+    pub wasm_pool_size: Option<u32>,
+
     /// Number of threads to use for processing requests
     ///
     /// (default: number of CPU cores, 2 for watch mode)
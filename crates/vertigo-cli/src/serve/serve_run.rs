@@ -11,7 +11,7 @@ use crate::commons::{
     ErrorCode,
     spawn::{ServerOwner, term_signal},
 };
-use crate::serve::mount_path::MountConfig;
+use crate::serve::mount_path::MountConfigBuilder;
 
 use super::{
     ServeOpts, ServeOptsInner, server_state::ServerState, vertigo_install::vertigo_install,
@@ -48,16 +48,24 @@ pub async fn run(opts: ServeOpts, port_watch: Option<u16>) -> Result<(), ErrorCo
         env,
         wasm_preload,
         disable_hydration,
+        ssr_streaming,
+        ssr_timeout,
+        ssr_timeout_policy,
+        ssr_body_limit,
+        wasm_pool_size,
         threads,
     } = opts.inner;
 
-    let mount_config = MountConfig::new(
-        mount_point,
-        opts.common.dest_dir,
-        env,
-        wasm_preload,
-        disable_hydration,
-    )?;
+    let mount_config = MountConfigBuilder::new(mount_point, opts.common.dest_dir)
+        .envs(env)
+        .wasm_preload(wasm_preload)
+        .disable_hydration(disable_hydration)
+        .ssr_streaming(ssr_streaming)
+        .ssr_timeout(Duration::from_millis(ssr_timeout))
+        .ssr_timeout_policy(ssr_timeout_policy)
+        .ssr_body_limit(ssr_body_limit)
+        .wasm_pool_size(wasm_pool_size)
+        .build()?;
 
     ServerState::init_with_watch(&mount_config, port_watch)?;
 
@@ -1,19 +1,21 @@
 use parking_lot::RwLock;
 use std::{
     collections::HashMap,
+    hash::{BuildHasher, RandomState},
     sync::{Arc, OnceLock},
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
-use tokio::sync::mpsc::{error::TryRecvError, unbounded_channel};
-use vertigo::{
-    JsJson, JsJsonSerialize,
-    dev::command::{CommandForBrowser, ConsoleLogLevel, browser_response},
+use tokio::sync::{
+    Semaphore,
+    mpsc::{UnboundedSender, error::TryRecvError, unbounded_channel},
+    oneshot,
 };
-use wasmtime::{Engine, Module};
+use vertigo::dev::{SSR_TIMEZONE_OFFSET_COOKIE, SsrContext};
+use wasmtime::{Config, Engine, InstanceAllocationStrategy, Module, PoolingAllocationConfig};
 
 use crate::{
-    commons::{ErrorCode, spawn::SpawnOwner},
-    serve::html::FetchCache,
+    commons::ErrorCode,
+    serve::html::{FetchCache, SsrDeadline},
 };
 
 use super::{
@@ -21,7 +23,8 @@ use super::{
     mount_path::MountConfig,
     request_state::RequestState,
     response_state::ResponseState,
-    wasm::{Message, WasmInstance},
+    ssr_commands::SsrCommands,
+    wasm::{HandleUrlResult, Message, WasmInstance},
 };
 
 pub fn get_now() -> Duration {
@@ -35,6 +38,9 @@ pub fn get_now() -> Duration {
     }
 }
 
+/// Max size of linear memory of pooled WASM instance
+const POOL_MAX_MEMORY_SIZE: usize = 1 << 30;
+
 pub type ServerStateMap = HashMap<String, Arc<ServerState>>;
 
 static STATE: OnceLock<Arc<RwLock<ServerStateMap>>> = OnceLock::new();
@@ -45,6 +51,8 @@ pub struct ServerState {
     module: Module,
     pub mount_config: MountConfig,
     pub port_watch: Option<u16>,
+    /// Free slots of instance pool (if pooling is enabled)
+    pool_slots: Option<Arc<Semaphore>>,
 }
 
 impl ServerState {
@@ -56,7 +64,7 @@ impl ServerState {
         mount_config: &MountConfig,
         port_watch: Option<u16>,
     ) -> Result<(), ErrorCode> {
-        let engine = Engine::default();
+        let engine = build_engine(mount_config)?;
 
         let module = build_module_wasm(&engine, mount_config)?;
 
@@ -70,12 +78,38 @@ impl ServerState {
                 module,
                 mount_config: mount_config.clone(),
                 port_watch,
+                pool_slots: mount_config
+                    .wasm_pool_size
+                    .map(|size| Arc::new(Semaphore::new(size as usize))),
             }),
         );
 
         Ok(())
     }
 
+    /// Create state of the app given in WebAssembly text format
+    #[cfg(test)]
+    pub fn mock(mount_config: &MountConfig, wat: &str) -> ServerState {
+        let Ok(engine) = build_engine(mount_config) else {
+            panic!("Can't create engine");
+        };
+
+        let module = match Module::new(&engine, wat) {
+            Ok(module) => module,
+            Err(err) => panic!("Can't compile test app: {err}"),
+        };
+
+        Self {
+            engine,
+            module,
+            mount_config: mount_config.clone(),
+            port_watch: None,
+            pool_slots: mount_config
+                .wasm_pool_size
+                .map(|size| Arc::new(Semaphore::new(size as usize))),
+        }
+    }
+
     pub fn global(mount_point: &str) -> Arc<ServerState> {
         let mutex = STATE.get_or_init(|| Arc::new(RwLock::new(ServerStateMap::new())));
 
@@ -88,152 +122,67 @@ impl ServerState {
         unreachable!();
     }
 
-    pub async fn request(&self, url: &str) -> ResponseState {
-        let (sender, mut receiver) = unbounded_channel::<Message>();
+    pub async fn request(&self, request: RequestState) -> ResponseState {
+        self.render(request, None).await
+    }
+
+    /// Render the page sending its beginning through `stream` as soon as the app starts waiting for data.
+    ///
+    /// If the beginning was sent, returned response contains only the rest of the document.
+    /// Otherwise (i.e. for non-HTML responses) the head sender is dropped and returned response is complete.
+    pub async fn request_streaming(
+        &self,
+        request: RequestState,
+        stream: ResponseStream,
+    ) -> ResponseState {
+        self.render(request, Some(stream)).await
+    }
 
-        let request = RequestState {
-            url: url.to_string(),
-            env: self.mount_config.env.clone(),
+    async fn render(
+        &self,
+        request: RequestState,
+        mut stream: Option<ResponseStream>,
+    ) -> ResponseState {
+        // Keep the slot until the instance is dropped
+        let _pool_slot = match &self.pool_slots {
+            Some(pool_slots) => match pool_slots.acquire().await {
+                Ok(permit) => Some(permit),
+                Err(err) => {
+                    log::error!("Can't acquire instance pool slot: {err}");
+                    return ResponseState::internal_error("Instance pool closed");
+                }
+            },
+            None => None,
         };
 
+        let (sender, mut receiver) = unbounded_channel::<Message>();
+
         let fetch = FetchCache::new();
 
+        let deadline = SsrDeadline::new(self.mount_config.ssr_timeout);
+
+        let ssr_context = SsrContext {
+            now: get_now().as_millis() as u64,
+            seed: RandomState::new().hash_one(request.url.as_str()) as u32,
+            timezone_offset: request
+                .cookie(SSR_TIMEZONE_OFFSET_COOKIE)
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default(),
+        };
+
         let mut inst = WasmInstance::new(
             sender.clone(),
             &self.engine,
             &self.module,
-            request,
+            request.clone(),
             Arc::new({
-                let sender = sender.clone();
-
-                move |request: RequestState, command| match command {
-                    CommandForBrowser::FetchCacheGet => {
-                        browser_response::FetchCacheGet { data: None }.to_json()
-                    }
-                    CommandForBrowser::FetchExec { request, callback } => {
-                        sender
-                            .send(Message::FetchRequest { callback, request })
-                            .inspect_err(|err| log::error!("Error sending FetchRequest: {err}"))
-                            .unwrap_or_default();
-
-                        JsJson::Null
-                    }
-                    CommandForBrowser::SetStatus { status } => {
-                        sender
-                            .send(Message::SetStatus(status))
-                            .inspect_err(|err| log::error!("Error sending FetchRequest: {err}"))
-                            .unwrap_or_default();
-
-                        JsJson::Null
-                    }
-                    CommandForBrowser::IsBrowser => {
-                        let response = browser_response::IsBrowser { value: false };
-
-                        response.to_json()
-                    }
-                    CommandForBrowser::GetDateNow => {
-                        let time = get_now().as_millis();
-
-                        let response = browser_response::GetDateNow { value: time as u64 };
-
-                        response.to_json()
-                    }
-                    CommandForBrowser::WebsocketRegister {
-                        host: _,
-                        callback: _,
-                    } => JsJson::Null,
-                    CommandForBrowser::WebsocketUnregister { callback: _ } => JsJson::Null,
-                    CommandForBrowser::WebsocketSendMessage {
-                        callback: _,
-                        message: _,
-                    } => JsJson::Null,
-                    CommandForBrowser::TimerSet {
-                        callback,
-                        duration,
-                        kind: _,
-                    } => {
-                        if duration == 0 {
-                            sender
-                                .send(Message::SetTimeoutZero { callback })
-                                .inspect_err(|err| {
-                                    log::error!("Error sending SetTimeoutZero: {err}")
-                                })
-                                .unwrap_or_default();
-                        }
-
-                        JsJson::Null
-                    }
-                    CommandForBrowser::TimerClear { callback: _ } => JsJson::Null,
-                    CommandForBrowser::LocationCallback {
-                        target: _,
-                        mode: _,
-                        callback: _,
-                    } => JsJson::Null,
-                    CommandForBrowser::LocationSet {
-                        target: _,
-                        mode: _,
-                        value: _,
-                    } => JsJson::Null,
-                    CommandForBrowser::LocationGet { target: _ } => {
-                        let url = request.url.clone();
-                        browser_response::LocationGet { value: url }.to_json()
-                    }
-                    CommandForBrowser::CookieGet { name: _ } => {
-                        browser_response::CookieGet { value: "".into() }.to_json()
-                    }
-                    CommandForBrowser::CookieSet {
-                        name: _,
-                        value: _,
-                        expires_in: _,
-                    } => JsJson::Null,
-                    CommandForBrowser::CookieJsonGet { name: _ } => {
-                        browser_response::CookieJsonGet {
-                            value: JsJson::Null,
-                        }
-                        .to_json()
-                    }
-                    CommandForBrowser::CookieJsonSet {
-                        name: _,
-                        value: _,
-                        expires_in: _,
-                    } => JsJson::Null,
-                    CommandForBrowser::GetEnv { name } => {
-                        let env_value = request.env(name);
-
-                        browser_response::GetEnv { value: env_value }.to_json()
-                    }
-                    CommandForBrowser::Log {
-                        kind,
-                        message,
-                        arg2: _,
-                        arg3: _,
-                        arg4: _,
-                    } => {
-                        if kind == ConsoleLogLevel::Error {
-                            log::warn!("{message}");
-                        } else {
-                            log::info!("{message}");
-                        }
-
-                        JsJson::Null
-                    }
-                    CommandForBrowser::TimezoneOffset => {
-                        browser_response::TimezoneOffset { value: 0 }.to_json()
-                    }
-                    CommandForBrowser::HistoryBack => JsJson::Null,
-                    CommandForBrowser::GetRandom { min, max: _ } => {
-                        browser_response::GetRandom { value: min }.to_json()
-                    }
-                    CommandForBrowser::JsApiCall { commands: _ } => JsJson::Null,
-                    CommandForBrowser::DomBulkUpdate { list } => {
-                        sender
-                            .send(Message::DomUpdate(list))
-                            .inspect_err(|err| log::error!("Error sending DomUpdate: {err}"))
-                            .unwrap_or_default();
-
-                        JsJson::Null
-                    }
-                }
+                let commands = SsrCommands {
+                    sender: sender.clone(),
+                    deadline: deadline.clone(),
+                    ssr_context: ssr_context.clone(),
+                };
+
+                move |request: RequestState, command| commands.handle(&request, command)
             }),
         );
 
@@ -243,18 +192,18 @@ impl ServerState {
 
         inst.call_vertigo_entry_function();
 
-        if let Some(result) = inst.handle_url(url) {
-            return result;
-        }
-
-        let spawn_resource = SpawnOwner::new({
-            let sender = sender.clone();
-
-            async move {
-                tokio::time::sleep(std::time::Duration::from_secs(10)).await;
-                let _ = sender.send(Message::TimeoutAndSendResponse);
+        let handler_pending = match inst.handle_url(&request) {
+            HandleUrlResult::Html => false,
+            HandleUrlResult::Response(response) => {
+                // Queued after cookies and headers set by the app, so they are included
+                sender
+                    .send(Message::HandlerResponse(response))
+                    .inspect_err(|err| log::error!("Error sending HandlerResponse: {err}"))
+                    .unwrap_or_default();
+                false
             }
-        });
+            HandleUrlResult::Pending => true,
+        };
 
         let mut html_response = HtmlResponse::new(
             sender.clone(),
@@ -262,8 +211,14 @@ impl ServerState {
             inst,
             self.mount_config.env.clone(),
             fetch,
+            deadline,
+            ssr_context,
         );
 
+        if handler_pending {
+            html_response.set_handler_pending();
+        }
+
         loop {
             let message = receiver.try_recv();
 
@@ -281,7 +236,16 @@ impl ServerState {
             }
 
             if html_response.awaiting_response() {
-                let message = receiver.recv().await;
+                if let Some(stream) = &mut stream {
+                    stream.flush(&mut html_response);
+                }
+
+                let Ok(message) =
+                    tokio::time::timeout_at(html_response.deadline(), receiver.recv()).await
+                else {
+                    return html_response.timeout_response();
+                };
+
                 if let Some(message) = message
                     && let Some(response) = html_response.process_message(message)
                 {
@@ -292,11 +256,60 @@ impl ServerState {
             }
         }
 
-        spawn_resource.off();
         html_response.build_response()
     }
 }
 
+/// Channels for sending the page in parts while it's being rendered
+pub struct ResponseStream {
+    /// Beginning of the document together with status and headers
+    pub head: Option<oneshot::Sender<ResponseState>>,
+    /// Patches of the `<body>` changed after its beginning was sent
+    pub chunks: UnboundedSender<Vec<u8>>,
+}
+
+impl ResponseStream {
+    fn flush(&mut self, html_response: &mut HtmlResponse) {
+        if let Some(head) = self.head.take() {
+            if let Some(start) = html_response.flush_start()
+                && head.send(start).is_err()
+            {
+                log::warn!("Client disconnected before receiving the head");
+            }
+            return;
+        }
+
+        if let Some(chunk) = html_response.flush_patches()
+            && self.chunks.send(chunk).is_err()
+        {
+            log::warn!("Client disconnected before receiving the whole page");
+        }
+    }
+}
+
+fn build_engine(mount_config: &MountConfig) -> Result<Engine, ErrorCode> {
+    let Some(pool_size) = mount_config.wasm_pool_size else {
+        return Ok(Engine::default());
+    };
+
+    log::info!("Using WASM instance pool of size {pool_size}");
+
+    let mut pooling = PoolingAllocationConfig::default();
+    pooling
+        .total_core_instances(pool_size)
+        .total_memories(pool_size)
+        .total_tables(pool_size)
+        .max_memory_size(POOL_MAX_MEMORY_SIZE);
+
+    let mut config = Config::new();
+    config.allocation_strategy(InstanceAllocationStrategy::Pooling(pooling));
+
+    Engine::new(&config).map_err(|err| {
+        log::error!("Wasm engine creation error: error={err}");
+        ErrorCode::ServeWasmEngineFailed
+    })
+}
+
 fn build_module_wasm(engine: &Engine, mount_path: &MountConfig) -> Result<Module, ErrorCode> {
     let full_wasm_path = mount_path.get_wasm_fs_path();
 
@@ -323,3 +336,543 @@ fn build_module_wasm(engine: &Engine, mount_path: &MountConfig) -> Result<Module
     log::info!("WASM module compiled in {} ms.", now.elapsed().as_millis());
     Ok(module)
 }
+
+#[cfg(test)]
+mod tests {
+    use vertigo::{
+        FetchMethod, JsJson, JsJsonSerialize, ServerResponse,
+        dev::{
+            CallbackId, HANDLER_RESPONSE_PENDING, SsrFetchRequest, SsrFetchRequestBody,
+            VERTIGO_MOUNT_POINT_PLACEHOLDER,
+            command::{CommandForBrowser, TimerKind},
+        },
+    };
+
+    use std::{
+        collections::BTreeMap,
+        time::{Duration, Instant},
+    };
+    use tokio::sync::{mpsc::unbounded_channel, oneshot};
+
+    use super::{ResponseStream, ServerState};
+    use crate::serve::{
+        mount_path::{MountConfigBuilder, SsrTimeoutPolicy},
+        request_state::RequestState,
+        response_state::{ResponseState, cookie_header},
+        wasm::TestApp,
+    };
+
+    fn server(app: TestApp, config: MountConfigBuilder) -> ServerState {
+        ServerState::mock(&config.build_mock(), &app.into_wat())
+    }
+
+    fn body(body: &[u8]) -> &str {
+        std::str::from_utf8(body).unwrap_or_default()
+    }
+
+    fn timer(callback: u64, duration: u32) -> CommandForBrowser {
+        CommandForBrowser::TimerSet {
+            callback: CallbackId::from_u64(callback),
+            duration,
+            kind: TimerKind::Timeout,
+        }
+    }
+
+    fn outcome(response: &ResponseState) -> Option<&str> {
+        response.headers.get("x-vertigo-ssr").map(String::as_str)
+    }
+
+    /// Backend which accepts connections and never responds
+    async fn backend_hanging() -> (tokio::task::JoinHandle<()>, String) {
+        let Ok(listener) = tokio::net::TcpListener::bind("127.0.0.1:0").await else {
+            panic!("Can't bind backend");
+        };
+        let Ok(address) = listener.local_addr() else {
+            panic!("Missing backend address");
+        };
+
+        let backend = tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((connection, _)) = listener.accept().await {
+                connections.push(connection);
+            }
+        });
+
+        (backend, format!("http://{address}/data"))
+    }
+
+    fn fetch(url: String) -> CommandForBrowser {
+        CommandForBrowser::FetchExec {
+            request: SsrFetchRequest {
+                method: FetchMethod::GET,
+                url,
+                headers: BTreeMap::new(),
+                body: SsrFetchRequestBody::None,
+            },
+            callback: CallbackId::from_u64(1),
+            progress: false,
+        }
+    }
+
+    /// App waiting for data from a backend which doesn't respond
+    fn app_slow(url: String) -> TestApp {
+        TestApp::new()
+            .entry(TestApp::page("Loading"))
+            .entry(fetch(url))
+    }
+
+    async fn request_slow(policy: SsrTimeoutPolicy) -> ResponseState {
+        let (backend, url) = backend_hanging().await;
+
+        let config = MountConfigBuilder::new("/", "build")
+            .ssr_timeout(Duration::from_millis(50))
+            .ssr_timeout_policy(policy);
+
+        let response = server(app_slow(url), config)
+            .request(RequestState::mock("GET", "/"))
+            .await;
+
+        backend.abort();
+        response
+    }
+
+    #[actix_web::test]
+    async fn test_timeout_complete() {
+        let app = TestApp::new()
+            .entry(TestApp::page("Loading"))
+            .entry(timer(1, 10))
+            .on_command(vec![TestApp::update_page("Ready")]);
+
+        let server = server(app, MountConfigBuilder::new("/", "build"));
+        let response = server.request(RequestState::mock("GET", "/")).await;
+
+        assert_eq!(response.status, 200);
+        assert_eq!(outcome(&response), Some("complete"));
+        assert!(body(&response.body).contains("Ready"));
+    }
+
+    #[actix_web::test]
+    async fn test_timeout_partial() {
+        let response = request_slow(SsrTimeoutPolicy::Partial).await;
+
+        assert_eq!(response.status, 200);
+        assert_eq!(outcome(&response), Some("timeout-partial"));
+        assert!(body(&response.body).contains("Loading"));
+        assert!(body(&response.body).contains("v-metadata"));
+    }
+
+    #[actix_web::test]
+    async fn test_timeout_error() {
+        let response = request_slow(SsrTimeoutPolicy::Error).await;
+
+        assert_eq!(response.status, 504);
+        assert_eq!(outcome(&response), Some("timeout-error"));
+    }
+
+    #[actix_web::test]
+    async fn test_timeout_shell() {
+        let response = request_slow(SsrTimeoutPolicy::Shell).await;
+        let body = body(&response.body);
+
+        assert_eq!(response.status, 200);
+        assert_eq!(outcome(&response), Some("timeout-shell"));
+        assert!(!body.contains("Loading"));
+        assert!(body.contains("wasm_run.js"));
+    }
+
+    #[actix_web::test]
+    async fn test_timeout_set_by_app() {
+        let (backend, url) = backend_hanging().await;
+        let app = app_slow(url).entry(CommandForBrowser::SetSsrTimeout { time: 50 });
+
+        // Default timeout of the server is much longer
+        let server = server(app, MountConfigBuilder::new("/", "build"));
+        let response = server.request(RequestState::mock("GET", "/")).await;
+        backend.abort();
+
+        assert_eq!(outcome(&response), Some("timeout-partial"));
+        assert!(body(&response.body).contains("Loading"));
+    }
+
+    #[actix_web::test]
+    async fn test_timeout_error_after_flush() {
+        let (backend, url) = backend_hanging().await;
+
+        let config = MountConfigBuilder::new("/", "build")
+            .ssr_streaming(true)
+            .ssr_timeout(Duration::from_millis(50))
+            .ssr_timeout_policy(SsrTimeoutPolicy::Error);
+
+        let (head_sender, head_receiver) = oneshot::channel();
+        let (chunks_sender, _chunks_receiver) = unbounded_channel();
+        let stream = ResponseStream {
+            head: Some(head_sender),
+            chunks: chunks_sender,
+        };
+
+        let rest = server(app_slow(url), config)
+            .request_streaming(RequestState::mock("GET", "/"), stream)
+            .await;
+        backend.abort();
+
+        // Status was already sent, so the rest of the page is sent as rendered so far
+        let Ok(head) = head_receiver.await else {
+            panic!("Head not sent");
+        };
+        assert_eq!(head.status, 200);
+        assert_eq!(outcome(&rest), Some("timeout-error"));
+        assert!(body(&rest.body).ends_with("</body>\n</html>\n"));
+    }
+
+    #[actix_web::test]
+    async fn test_timer_exceeding_budget() {
+        let app = TestApp::new()
+            .entry(TestApp::page("Loading"))
+            .entry(timer(1, 5_000))
+            .on_command(vec![TestApp::update_page("Ready")]);
+
+        let config = MountConfigBuilder::new("/", "build").ssr_timeout(Duration::from_millis(500));
+
+        let start = Instant::now();
+        let response = server(app, config)
+            .request(RequestState::mock("GET", "/"))
+            .await;
+
+        // Skipped, so rendering doesn't wait for the deadline
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert_eq!(outcome(&response), Some("complete"));
+        assert!(body(&response.body).contains("Loading"));
+    }
+
+    #[actix_web::test]
+    async fn test_timer_cleared() {
+        let app = TestApp::new()
+            .entry(TestApp::page("Loading"))
+            .entry(timer(1, 20))
+            .entry(CommandForBrowser::TimerClear {
+                callback: CallbackId::from_u64(1),
+            })
+            .on_command(vec![TestApp::update_page("Ready")]);
+
+        let server = server(app, MountConfigBuilder::new("/", "build"));
+        let response = server.request(RequestState::mock("GET", "/")).await;
+
+        assert_eq!(outcome(&response), Some("complete"));
+        assert!(body(&response.body).contains("Loading"));
+    }
+
+    fn fetch_stream() -> CommandForBrowser {
+        CommandForBrowser::FetchStream {
+            request: SsrFetchRequest {
+                method: FetchMethod::GET,
+                url: "http://127.0.0.1:1/events".into(),
+                headers: BTreeMap::new(),
+                body: SsrFetchRequestBody::None,
+            },
+            callback: CallbackId::from_u64(1),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_fetch_stream_error() {
+        let app = TestApp::new()
+            .entry(TestApp::page("Loading"))
+            .entry(fetch_stream())
+            .on_command(vec![TestApp::update_page("Failed")]);
+
+        let server = server(app, MountConfigBuilder::new("/", "build"));
+        let response = server.request(RequestState::mock("GET", "/")).await;
+
+        assert_eq!(outcome(&response), Some("complete"));
+        assert!(body(&response.body).contains("Failed"));
+    }
+
+    #[actix_web::test]
+    async fn test_fetch_stream_aborted() {
+        let app = TestApp::new()
+            .entry(TestApp::page("Loading"))
+            .entry(fetch_stream())
+            .entry(CommandForBrowser::FetchStreamAbort {
+                callback: CallbackId::from_u64(1),
+            })
+            .on_command(vec![TestApp::update_page("Failed")]);
+
+        let server = server(app, MountConfigBuilder::new("/", "build"));
+        let response = server.request(RequestState::mock("GET", "/")).await;
+
+        assert_eq!(outcome(&response), Some("complete"));
+        assert!(body(&response.body).contains("Loading"));
+    }
+
+    #[actix_web::test]
+    async fn test_interval() {
+        let interval = CommandForBrowser::TimerSet {
+            callback: CallbackId::from_u64(1),
+            duration: 10,
+            kind: TimerKind::Interval,
+        };
+
+        // Pending timeout keeps rendering alive, interval is cleared after the second tick
+        let app = TestApp::new()
+            .entry(TestApp::page("Loading"))
+            .entry(interval)
+            .entry(timer(2, 100))
+            .on_command(vec![TestApp::update_page("Tick 1")])
+            .on_command(vec![
+                TestApp::update_page("Tick 2"),
+                CommandForBrowser::TimerClear {
+                    callback: CallbackId::from_u64(1),
+                },
+            ]);
+
+        let server = server(app, MountConfigBuilder::new("/", "build"));
+        let response = server.request(RequestState::mock("GET", "/")).await;
+
+        assert_eq!(outcome(&response), Some("complete"));
+        assert!(body(&response.body).contains("Tick 2"));
+    }
+
+    #[actix_web::test]
+    async fn test_pool_isolation() {
+        // Counter of `handle_url` calls is kept in the memory of the instance,
+        // so the response changes if the memory of a previous request leaks
+        let app = TestApp::new()
+            .entry(TestApp::page("Hello"))
+            .handle_url(JsJson::Null)
+            .handle_url_repeated(ServerResponse::text("leaked").to_json());
+
+        let config = MountConfigBuilder::new("/", "build").wasm_pool_size(Some(1));
+        let server = server(app, config);
+
+        for _ in 0..3 {
+            let response = server.request(RequestState::mock("GET", "/")).await;
+
+            assert_eq!(response.status, 200);
+            assert!(body(&response.body).contains("Hello"));
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_response_cookies_and_headers() {
+        let app = TestApp::new()
+            .entry(TestApp::page("Hello"))
+            .entry(CommandForBrowser::CookieSet {
+                name: "session".into(),
+                value: "abc".into(),
+                expires_in: 3600,
+            })
+            .entry(CommandForBrowser::SetResponseHeader {
+                name: "Cache-Control".into(),
+                value: "no-store".into(),
+            });
+
+        let server = server(app, MountConfigBuilder::new("/", "build"));
+        let response = server.request(RequestState::mock("GET", "/")).await;
+
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            response.headers.get("cache-control").map(String::as_str),
+            Some("no-store")
+        );
+        assert_eq!(
+            response.cookies,
+            vec![cookie_header("session", "abc", 3600)]
+        );
+        assert!(body(&response.body).contains("Hello"));
+    }
+
+    #[actix_web::test]
+    async fn test_redirect() {
+        let app = TestApp::new()
+            .entry(TestApp::page("Hello"))
+            .entry(CommandForBrowser::CookieSet {
+                name: "return_to".into(),
+                value: "/account".into(),
+                expires_in: 60,
+            })
+            .entry(CommandForBrowser::Redirect {
+                url: format!("{VERTIGO_MOUNT_POINT_PLACEHOLDER}/login"),
+                status: 303,
+            });
+
+        let server = server(app, MountConfigBuilder::new("/app", "build"));
+        let response = server.request(RequestState::mock("GET", "/account")).await;
+
+        assert_eq!(response.status, 303);
+        assert_eq!(
+            response.headers.get("location").map(String::as_str),
+            Some("/app/login")
+        );
+        assert_eq!(
+            response.cookies,
+            vec![cookie_header("return_to", "/account", 60)]
+        );
+        assert!(response.body.is_empty());
+    }
+
+    #[actix_web::test]
+    async fn test_redirect_invalid_status() {
+        let app = TestApp::new()
+            .entry(TestApp::page("Hello"))
+            .entry(CommandForBrowser::Redirect {
+                url: format!("{VERTIGO_MOUNT_POINT_PLACEHOLDER}/login"),
+                status: 200,
+            });
+
+        let server = server(app, MountConfigBuilder::new("/", "build"));
+        let response = server.request(RequestState::mock("GET", "/")).await;
+
+        assert_eq!(response.status, 302);
+        assert_eq!(
+            response.headers.get("location").map(String::as_str),
+            Some("/login")
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_handler_response() {
+        let response = ServerResponse::bytes("image/png", vec![0, 159, 255])
+            .status(201)
+            .header("cache-control", "max-age=60");
+
+        let app = TestApp::new()
+            .entry(TestApp::page("Hello"))
+            .entry(CommandForBrowser::CookieSet {
+                name: "seen".into(),
+                value: "1".into(),
+                expires_in: 60,
+            })
+            .handle_url(response.to_json());
+
+        let server = server(app, MountConfigBuilder::new("/", "build"));
+        let response = server.request(RequestState::mock("GET", "/logo.png")).await;
+
+        assert_eq!(response.status, 201);
+        assert_eq!(response.body, vec![0, 159, 255]);
+        assert_eq!(
+            response.headers.get("content-type").map(String::as_str),
+            Some("image/png")
+        );
+        assert_eq!(
+            response.headers.get("cache-control").map(String::as_str),
+            Some("max-age=60")
+        );
+        assert_eq!(response.cookies, vec![cookie_header("seen", "1", 60)]);
+    }
+
+    #[actix_web::test]
+    async fn test_handler_response_pending() {
+        let response = ServerResponse::text("done").status(202);
+
+        // Response is delivered when the timer fires
+        let app = TestApp::new()
+            .entry(TestApp::page("Hello"))
+            .entry(CommandForBrowser::TimerSet {
+                callback: CallbackId::from_u64(1),
+                duration: 10,
+                kind: TimerKind::Timeout,
+            })
+            .on_command(vec![CommandForBrowser::ServerHandlerResponse { response }])
+            .handle_url(JsJson::String(HANDLER_RESPONSE_PENDING.into()));
+
+        let server = server(app, MountConfigBuilder::new("/", "build"));
+        let response = server.request(RequestState::mock("GET", "/job")).await;
+
+        assert_eq!(response.status, 202);
+        assert_eq!(body(&response.body), "done");
+    }
+
+    #[actix_web::test]
+    async fn test_handler_response_missing() {
+        let app = TestApp::new()
+            .entry(TestApp::page("Hello"))
+            .handle_url(JsJson::String(HANDLER_RESPONSE_PENDING.into()));
+
+        let server = server(app, MountConfigBuilder::new("/", "build"));
+        let response = server.request(RequestState::mock("GET", "/job")).await;
+
+        assert_eq!(response.status, 504);
+    }
+
+    #[actix_web::test]
+    async fn test_streaming_chunks_order() {
+        // Data arriving in two steps
+        let app = TestApp::new()
+            .entry(TestApp::page("Loading"))
+            .entry(timer(1, 10))
+            .entry(timer(2, 50))
+            .on_command(vec![TestApp::update_page("Step 1")])
+            .on_command(vec![TestApp::update_page("Step 2")]);
+
+        let server = server(
+            app,
+            MountConfigBuilder::new("/", "build").ssr_streaming(true),
+        );
+
+        let (head_sender, head_receiver) = oneshot::channel();
+        let (chunks_sender, mut chunks_receiver) = unbounded_channel();
+        let stream = ResponseStream {
+            head: Some(head_sender),
+            chunks: chunks_sender,
+        };
+
+        let rest = server
+            .request_streaming(RequestState::mock("GET", "/"), stream)
+            .await;
+
+        let Ok(head) = head_receiver.await else {
+            panic!("Head not sent");
+        };
+        let head = body(&head.body);
+        assert!(head.starts_with("<!DOCTYPE html>"));
+        assert!(head.contains("<body>"));
+        assert!(head.contains("Loading"));
+        assert!(!head.contains("</body>"));
+
+        let Some(chunk) = chunks_receiver.recv().await else {
+            panic!("Chunk expected");
+        };
+        let chunk = String::from_utf8(chunk).unwrap_or_default();
+        assert!(chunk.starts_with(r#"<template data-vertigo-patch="0">"#));
+        assert!(chunk.contains("Step 1"));
+        assert!(chunks_receiver.recv().await.is_none());
+
+        let rest = body(&rest.body);
+        let Some(patch) = rest.find(r#"<template data-vertigo-patch="0">"#) else {
+            panic!("Patch expected in the rest: {rest}");
+        };
+        let Some(metadata) = rest.find("v-metadata") else {
+            panic!("Metadata expected in the rest: {rest}");
+        };
+        assert!(rest.contains("Step 2"));
+        assert!(patch < metadata);
+        assert!(rest.ends_with("</body>\n</html>\n"));
+    }
+
+    #[actix_web::test]
+    async fn test_streaming_not_html() {
+        let app = TestApp::new()
+            .entry(TestApp::page("Loading"))
+            .handle_url(ServerResponse::text("plain").to_json());
+
+        let server = server(
+            app,
+            MountConfigBuilder::new("/", "build").ssr_streaming(true),
+        );
+
+        let (head_sender, head_receiver) = oneshot::channel();
+        let (chunks_sender, _chunks_receiver) = unbounded_channel();
+        let stream = ResponseStream {
+            head: Some(head_sender),
+            chunks: chunks_sender,
+        };
+
+        let response = server
+            .request_streaming(RequestState::mock("GET", "/robots.txt"), stream)
+            .await;
+
+        // Head is never sent, so the response is complete
+        assert!(head_receiver.await.is_err());
+        assert_eq!(body(&response.body), "plain");
+    }
+}
@@ -0,0 +1,537 @@
+use std::{sync::Arc, time::Duration};
+use tokio::sync::mpsc::UnboundedSender;
+use vertigo::{
+    JsJson, JsJsonSerialize,
+    dev::{
+        SsrContext,
+        command::{CommandForBrowser, ConsoleLogLevel, browser_response},
+    },
+};
+
+use crate::serve::html::{SsrDeadline, convert_to_jsjson, convert_to_serde_value};
+
+use super::{
+    request_state::RequestState,
+    response_state::{ResponseState, cookie_header},
+    server_state::get_now,
+    wasm::Message,
+};
+
+/// Answers commands sent by the app during SSR
+pub struct SsrCommands {
+    pub sender: UnboundedSender<Message>,
+    pub deadline: Arc<SsrDeadline>,
+    pub ssr_context: SsrContext,
+}
+
+impl SsrCommands {
+    pub fn handle(&self, request: &RequestState, command: CommandForBrowser) -> JsJson {
+        match command {
+            CommandForBrowser::FetchCacheGet => {
+                browser_response::FetchCacheGet { data: None }.to_json()
+            }
+            CommandForBrowser::SsrContextGet => browser_response::SsrContextGet {
+                value: Some(self.ssr_context.clone()),
+            }
+            .to_json(),
+            CommandForBrowser::FetchExec {
+                request, callback, ..
+            } => {
+                self.sender
+                    .send(Message::FetchRequest { callback, request })
+                    .inspect_err(|err| log::error!("Error sending FetchRequest: {err}"))
+                    .unwrap_or_default();
+
+                JsJson::Null
+            }
+            CommandForBrowser::FetchAbort { callback } => {
+                self.sender
+                    .send(Message::FetchAbort { callback })
+                    .inspect_err(|err| log::error!("Error sending FetchAbort: {err}"))
+                    .unwrap_or_default();
+
+                JsJson::Null
+            }
+            CommandForBrowser::FetchStream { request, callback } => {
+                self.sender
+                    .send(Message::FetchStream { callback, request })
+                    .inspect_err(|err| log::error!("Error sending FetchStream: {err}"))
+                    .unwrap_or_default();
+
+                JsJson::Null
+            }
+            CommandForBrowser::FetchStreamAbort { callback } => {
+                self.sender
+                    .send(Message::FetchStreamAbort { callback })
+                    .inspect_err(|err| log::error!("Error sending FetchStreamAbort: {err}"))
+                    .unwrap_or_default();
+
+                JsJson::Null
+            }
+            CommandForBrowser::SetStatus { status } => {
+                self.sender
+                    .send(Message::SetStatus(status))
+                    .inspect_err(|err| log::error!("Error sending FetchRequest: {err}"))
+                    .unwrap_or_default();
+
+                JsJson::Null
+            }
+            CommandForBrowser::SetResponseHeader { name, value } => {
+                self.sender
+                    .send(Message::SetHeader { name, value })
+                    .inspect_err(|err| log::error!("Error sending SetHeader: {err}"))
+                    .unwrap_or_default();
+
+                JsJson::Null
+            }
+            CommandForBrowser::ServerHandlerResponse { response } => {
+                let response = ResponseState {
+                    status: response.status,
+                    headers: response.headers,
+                    body: response.body,
+                    cookies: Vec::new(),
+                };
+
+                self.sender
+                    .send(Message::HandlerResponse(response))
+                    .inspect_err(|err| log::error!("Error sending HandlerResponse: {err}"))
+                    .unwrap_or_default();
+
+                JsJson::Null
+            }
+            CommandForBrowser::Redirect { url, status } => {
+                self.sender
+                    .send(Message::Redirect { url, status })
+                    .inspect_err(|err| log::error!("Error sending Redirect: {err}"))
+                    .unwrap_or_default();
+
+                JsJson::Null
+            }
+            CommandForBrowser::SetSsrTimeout { time } => {
+                self.deadline
+                    .set_timeout(Duration::from_millis(time.into()));
+
+                JsJson::Null
+            }
+            CommandForBrowser::SsrTimeLeftGet => {
+                let value = Some(self.deadline.time_left().as_millis() as u32);
+
+                browser_response::SsrTimeLeftGet { value }.to_json()
+            }
+            CommandForBrowser::IsBrowser => {
+                let response = browser_response::IsBrowser { value: false };
+
+                response.to_json()
+            }
+            CommandForBrowser::GetDateNow => {
+                let time = get_now().as_millis();
+
+                let response = browser_response::GetDateNow { value: time as u64 };
+
+                response.to_json()
+            }
+            CommandForBrowser::WebsocketRegister {
+                host: _,
+                callback: _,
+            } => JsJson::Null,
+            CommandForBrowser::JsCallbackRelease { callback: _ } => JsJson::Null,
+            CommandForBrowser::FileRead { .. } | CommandForBrowser::FileRelease { .. } => {
+                // There are no user files during SSR
+                JsJson::Null
+            }
+            CommandForBrowser::WebsocketUnregister { callback: _ } => JsJson::Null,
+            CommandForBrowser::WebsocketSendMessage {
+                callback: _,
+                message: _,
+            } => JsJson::Null,
+            CommandForBrowser::TimerSet {
+                callback,
+                duration,
+                kind,
+            } => {
+                self.sender
+                    .send(Message::TimerSet {
+                        callback,
+                        duration,
+                        kind,
+                    })
+                    .inspect_err(|err| log::error!("Error sending TimerSet: {err}"))
+                    .unwrap_or_default();
+
+                JsJson::Null
+            }
+            CommandForBrowser::TimerClear { callback } => {
+                self.sender
+                    .send(Message::TimerClear { callback })
+                    .inspect_err(|err| log::error!("Error sending TimerClear: {err}"))
+                    .unwrap_or_default();
+
+                JsJson::Null
+            }
+            CommandForBrowser::LocationCallback {
+                target: _,
+                mode: _,
+                callback: _,
+            } => JsJson::Null,
+            CommandForBrowser::LocationSet {
+                target: _,
+                mode: _,
+                value: _,
+            } => JsJson::Null,
+            CommandForBrowser::LocationGet { target: _ } => {
+                let url = request.url.clone();
+                browser_response::LocationGet { value: url }.to_json()
+            }
+            CommandForBrowser::CookieGet { name } => {
+                let value = request.cookie(name).unwrap_or_default();
+
+                browser_response::CookieGet { value }.to_json()
+            }
+            CommandForBrowser::CookieSet {
+                name,
+                value,
+                expires_in,
+            } => {
+                self.sender
+                    .send(Message::SetCookie(cookie_header(&name, &value, expires_in)))
+                    .inspect_err(|err| log::error!("Error sending SetCookie: {err}"))
+                    .unwrap_or_default();
+
+                JsJson::Null
+            }
+            CommandForBrowser::CookieJsonGet { name } => {
+                let value = request
+                    .cookie(&name)
+                    .and_then(|value| {
+                        serde_json::from_str(&value)
+                            .inspect_err(|err| {
+                                log::error!("Error deserializing cookie {name}: {err}")
+                            })
+                            .ok()
+                    })
+                    .map(convert_to_jsjson)
+                    .unwrap_or(JsJson::Null);
+
+                browser_response::CookieJsonGet { value }.to_json()
+            }
+            CommandForBrowser::CookieJsonSet {
+                name,
+                value,
+                expires_in,
+            } => {
+                match serde_json::to_string(&convert_to_serde_value(value)) {
+                    Ok(value) => self
+                        .sender
+                        .send(Message::SetCookie(cookie_header(&name, &value, expires_in)))
+                        .inspect_err(|err| log::error!("Error sending SetCookie: {err}"))
+                        .unwrap_or_default(),
+                    Err(err) => log::error!("Error serializing cookie {name}: {err}"),
+                }
+
+                JsJson::Null
+            }
+            CommandForBrowser::StorageGet { .. } => browser_response::StorageGet {
+                stored: false,
+                value: JsJson::Null,
+            }
+            .to_json(),
+            CommandForBrowser::StorageSet { .. }
+            | CommandForBrowser::StorageRemove { .. }
+            | CommandForBrowser::StorageWatch { .. }
+            | CommandForBrowser::StorageUnwatch { .. }
+            | CommandForBrowser::OfflineCacheGet { .. }
+            | CommandForBrowser::OfflineCacheSet { .. }
+            | CommandForBrowser::OfflineCacheClear => JsJson::Null,
+            CommandForBrowser::GetEnv { name } => {
+                let env_value = request.env(name);
+
+                browser_response::GetEnv { value: env_value }.to_json()
+            }
+            CommandForBrowser::RequestHeaderGet { name } => {
+                let value = request.header(name);
+
+                browser_response::RequestHeaderGet { value }.to_json()
+            }
+            CommandForBrowser::RequestMethodGet => {
+                let value = Some(request.method.clone());
+
+                browser_response::RequestMethodGet { value }.to_json()
+            }
+            CommandForBrowser::RequestBodyGet => {
+                let value = Some(request.body.to_vec());
+
+                browser_response::RequestBodyGet { value }.to_json()
+            }
+            CommandForBrowser::Log {
+                kind,
+                message,
+                arg2: _,
+                arg3: _,
+                arg4: _,
+            } => {
+                if kind == ConsoleLogLevel::Error {
+                    log::warn!("{message}");
+                } else {
+                    log::info!("{message}");
+                }
+
+                JsJson::Null
+            }
+            CommandForBrowser::TimezoneOffset => {
+                browser_response::TimezoneOffset { value: 0 }.to_json()
+            }
+            CommandForBrowser::HistoryBack => JsJson::Null,
+            CommandForBrowser::GetRandom { min, max: _ } => {
+                browser_response::GetRandom { value: min }.to_json()
+            }
+            CommandForBrowser::JsApiCall { commands: _ } => JsJson::Null,
+            CommandForBrowser::JsApiCallAsync {
+                commands: _,
+                callback: _,
+            } => JsJson::Null,
+            CommandForBrowser::DomBulkUpdate { list } => {
+                self.sender
+                    .send(Message::DomUpdate(list))
+                    .inspect_err(|err| log::error!("Error sending DomUpdate: {err}"))
+                    .unwrap_or_default();
+
+                JsJson::Null
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        sync::Arc,
+        time::Duration,
+    };
+    use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+    use vertigo::{
+        JsJson, JsJsonSerialize,
+        dev::{
+            SsrContext,
+            command::{CommandForBrowser, browser_response},
+        },
+        from_json,
+    };
+
+    use super::SsrCommands;
+    use crate::serve::{html::SsrDeadline, request_state::RequestState, wasm::Message};
+
+    fn commands() -> (SsrCommands, UnboundedReceiver<Message>) {
+        let (sender, receiver) = unbounded_channel();
+
+        let commands = SsrCommands {
+            sender,
+            deadline: SsrDeadline::new(Duration::from_secs(10)),
+            ssr_context: SsrContext {
+                now: 0,
+                seed: 0,
+                timezone_offset: 0,
+            },
+        };
+
+        (commands, receiver)
+    }
+
+    fn request() -> RequestState {
+        RequestState {
+            headers: Arc::new(HashMap::from([(
+                "accept-language".to_string(),
+                "pl-PL".to_string(),
+            )])),
+            cookies: Arc::new(HashMap::from([
+                ("session".to_string(), "abc 123".to_string()),
+                ("settings".to_string(), r#"{"dark":true}"#.to_string()),
+            ])),
+            ..RequestState::mock("GET", "/")
+        }
+    }
+
+    #[test]
+    fn test_request_headers() {
+        let (commands, _receiver) = commands();
+        let request = request();
+
+        let header = |name: &str| {
+            commands.handle(
+                &request,
+                CommandForBrowser::RequestHeaderGet { name: name.into() },
+            )
+        };
+
+        // Header names are case-insensitive
+        assert_eq!(
+            header("Accept-Language"),
+            browser_response::RequestHeaderGet {
+                value: Some("pl-PL".into())
+            }
+            .to_json()
+        );
+        assert_eq!(
+            header("authorization"),
+            browser_response::RequestHeaderGet { value: None }.to_json()
+        );
+    }
+
+    #[test]
+    fn test_request_method_and_body() {
+        let (commands, _receiver) = commands();
+        let request = RequestState {
+            body: "name=Ala&age=7".into(),
+            ..RequestState::mock("POST", "/form")
+        };
+
+        assert_eq!(
+            commands.handle(&request, CommandForBrowser::RequestMethodGet),
+            browser_response::RequestMethodGet {
+                value: Some("POST".into())
+            }
+            .to_json()
+        );
+        assert_eq!(
+            commands.handle(&request, CommandForBrowser::RequestBodyGet),
+            browser_response::RequestBodyGet {
+                value: Some(b"name=Ala&age=7".to_vec())
+            }
+            .to_json()
+        );
+    }
+
+    #[test]
+    fn test_time_left() {
+        let (commands, _receiver) = commands();
+        let request = RequestState::mock("GET", "/");
+
+        let time_left = || {
+            let response = commands.handle(&request, CommandForBrowser::SsrTimeLeftGet);
+            let Ok(browser_response::SsrTimeLeftGet { value: Some(value) }) =
+                from_json::<browser_response::SsrTimeLeftGet>(response)
+            else {
+                panic!("Time left expected");
+            };
+            value
+        };
+
+        let value = time_left();
+        assert!(value > 9_000 && value <= 10_000, "{value}");
+
+        // Timeout set by the app is counted from the start of rendering
+        commands.handle(&request, CommandForBrowser::SetSsrTimeout { time: 100 });
+        assert!(time_left() <= 100);
+    }
+
+    #[test]
+    fn test_request_cookies() {
+        let (commands, _receiver) = commands();
+        let request = request();
+
+        let cookie = |name: &str| {
+            commands.handle(&request, CommandForBrowser::CookieGet { name: name.into() })
+        };
+
+        assert_eq!(
+            cookie("session"),
+            browser_response::CookieGet {
+                value: "abc 123".into()
+            }
+            .to_json()
+        );
+        assert_eq!(
+            cookie("missing"),
+            browser_response::CookieGet {
+                value: String::new()
+            }
+            .to_json()
+        );
+
+        assert_eq!(
+            commands.handle(
+                &request,
+                CommandForBrowser::CookieJsonGet {
+                    name: "settings".into()
+                }
+            ),
+            browser_response::CookieJsonGet {
+                value: JsJson::Object(BTreeMap::from([("dark".to_string(), JsJson::True)]))
+            }
+            .to_json()
+        );
+
+        // Not a JSON
+        assert_eq!(
+            commands.handle(
+                &request,
+                CommandForBrowser::CookieJsonGet {
+                    name: "session".into()
+                }
+            ),
+            browser_response::CookieJsonGet {
+                value: JsJson::Null
+            }
+            .to_json()
+        );
+    }
+
+    #[test]
+    fn test_response_cookies_and_headers() {
+        let (commands, mut receiver) = commands();
+        let request = request();
+
+        commands.handle(
+            &request,
+            CommandForBrowser::CookieSet {
+                name: "session".into(),
+                value: "abc 123".into(),
+                expires_in: 3600,
+            },
+        );
+        commands.handle(
+            &request,
+            CommandForBrowser::CookieJsonSet {
+                name: "settings".into(),
+                value: JsJson::Object(BTreeMap::from([("dark".to_string(), JsJson::False)])),
+                expires_in: 60,
+            },
+        );
+        commands.handle(
+            &request,
+            CommandForBrowser::SetResponseHeader {
+                name: "Cache-Control".into(),
+                value: "no-store".into(),
+            },
+        );
+        commands.handle(&request, CommandForBrowser::SetStatus { status: 404 });
+
+        let Ok(Message::SetCookie(cookie)) = receiver.try_recv() else {
+            panic!("SetCookie expected");
+        };
+        assert_eq!(
+            cookie,
+            "session=abc%20123; SameSite=Strict; Path=/; Max-Age=3600"
+        );
+
+        let Ok(Message::SetCookie(cookie)) = receiver.try_recv() else {
+            panic!("SetCookie expected");
+        };
+        assert_eq!(
+            cookie,
+            "settings=%7B%22dark%22%3Afalse%7D; SameSite=Strict; Path=/; Max-Age=60"
+        );
+
+        let Ok(Message::SetHeader { name, value }) = receiver.try_recv() else {
+            panic!("SetHeader expected");
+        };
+        assert_eq!(
+            (name.as_str(), value.as_str()),
+            ("Cache-Control", "no-store")
+        );
+
+        let Ok(Message::SetStatus(404)) = receiver.try_recv() else {
+            panic!("SetStatus expected");
+        };
+    }
+}
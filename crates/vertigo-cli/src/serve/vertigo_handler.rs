@@ -1,9 +1,10 @@
-use actix_web::{HttpRequest, http::StatusCode, web};
-use std::time::Instant;
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode, web};
+use std::{collections::HashMap, sync::Arc, time::Instant};
+use tokio::sync::{mpsc::unbounded_channel, oneshot};
 
-use crate::serve::{MountConfig, response_state::ResponseState};
+use crate::serve::{MountConfig, request_state::RequestState, response_state::ResponseState};
 
-use super::server_state::ServerState;
+use super::server_state::{ResponseStream, ServerState};
 
 /// Directly attach SSR mechanism to the actix web server (no static files mounting)
 ///
@@ -32,7 +33,7 @@ use super::server_state::ServerState;
 pub fn vertigo_handler(mount_config: &MountConfig) -> actix_web::Route {
     let mount_point = mount_config.mount_point().to_string();
 
-    web::route().to(move |req: HttpRequest| {
+    web::route().to(move |req: HttpRequest, payload: web::Payload| {
         let mount_point = mount_point.clone();
         async move {
             let state = ServerState::global(&mount_point);
@@ -55,45 +56,255 @@ pub fn vertigo_handler(mount_config: &MountConfig) -> actix_web::Route {
             };
 
             log::debug!("Incoming request: {uri}");
-            let mut response_state = state.request(&uri).await;
 
-            let time = now.elapsed().as_millis();
-            let log_level = if time > 1000 {
-                log::Level::Warn
-            } else {
-                log::Level::Info
+            let body = match read_body(payload, state.mount_config.ssr_body_limit).await {
+                Ok(body) => body,
+                Err(response) => return finish_response(&state, response, now, &uri),
             };
-            log::log!(
-                log_level,
-                "Response for request: {} {time}ms {uri}",
-                response_state.status,
-            );
-
-            if let Some(port_watch) = state.port_watch
-                && response_state.headers.get("content-type")
-                    == Some(&ResponseState::HTML.to_string())
-            {
-                response_state.add_watch_script(port_watch);
+
+            let request = RequestState {
+                method: req.method().to_string(),
+                url: uri.clone(),
+                env: state.mount_config.env.clone(),
+                headers: Arc::new(get_headers(&req)),
+                cookies: Arc::new(get_cookies(&req)),
+                body,
+            };
+
+            if state.mount_config.ssr_streaming {
+                return streaming_response(state, request, now, uri).await;
             }
 
-            // Checking for error status to log
-            if StatusCode::from_u16(response_state.status)
-                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
-                .is_server_error()
-            {
-                log::error!("WASM status: {}", response_state.status);
-
-                match String::from_utf8(response_state.body.clone()) {
-                    Ok(messagee) => {
-                        log::error!("WASM response: text={}", messagee);
-                    }
-                    Err(_) => {
-                        log::error!("WASM response: bytes={:#?}", response_state.body);
-                    }
-                }
+            let response_state = state.request(request).await;
+
+            finish_response(&state, response_state, now, &uri)
+        }
+    })
+}
+
+async fn streaming_response(
+    state: Arc<ServerState>,
+    request: RequestState,
+    now: Instant,
+    uri: String,
+) -> HttpResponse {
+    let (head_sender, head_receiver) = oneshot::channel();
+    let (chunks_sender, chunks_receiver) = unbounded_channel();
+    let (rest_sender, rest_receiver) = oneshot::channel();
+
+    let stream = ResponseStream {
+        head: Some(head_sender),
+        chunks: chunks_sender,
+    };
+
+    actix_web::rt::spawn({
+        let state = state.clone();
+        async move {
+            let rest = state.request_streaming(request, stream).await;
+            if rest_sender.send(rest).is_err() {
+                log::warn!("Client disconnected before receiving the response");
             }
+        }
+    });
+
+    let Ok(head) = head_receiver.await else {
+        // Head not flushed, so the response is complete
+        return match rest_receiver.await {
+            Ok(response_state) => finish_response(&state, response_state, now, &uri),
+            Err(_) => ResponseState::internal_error("Rendering interrupted").into(),
+        };
+    };
+
+    log::info!(
+        "Head sent for request: {} {}ms {uri}",
+        head.status,
+        now.elapsed().as_millis()
+    );
 
-            actix_web::HttpResponse::from(response_state)
+    let port_watch = state.port_watch;
+
+    head.into_streaming(chunks_receiver, async move {
+        match rest_receiver.await {
+            Ok(mut rest) => {
+                log_response_time(rest.status, now, &uri);
+
+                if let Some(port_watch) = port_watch {
+                    rest.add_watch_script(port_watch);
+                }
+
+                rest.body
+            }
+            Err(_) => {
+                log::error!("Rendering interrupted: {uri}");
+                Vec::new()
+            }
         }
     })
 }
+
+/// Read body of the request for the app, refusing the ones exceeding the limit
+async fn read_body(payload: web::Payload, limit: usize) -> Result<web::Bytes, ResponseState> {
+    match payload.to_bytes_limited(limit).await {
+        Ok(Ok(body)) => Ok(body),
+        Ok(Err(err)) => Err(ResponseState::plain(
+            StatusCode::BAD_REQUEST,
+            format!("Can't read request body: {err}"),
+        )),
+        Err(_) => Err(ResponseState::plain(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Request body exceeds {limit} bytes"),
+        )),
+    }
+}
+
+fn log_response_time(status: u16, now: Instant, uri: &str) {
+    let time = now.elapsed().as_millis();
+    let log_level = if time > 1000 {
+        log::Level::Warn
+    } else {
+        log::Level::Info
+    };
+    log::log!(log_level, "Response for request: {status} {time}ms {uri}");
+}
+
+fn finish_response(
+    state: &ServerState,
+    mut response_state: ResponseState,
+    now: Instant,
+    uri: &str,
+) -> HttpResponse {
+    log_response_time(response_state.status, now, uri);
+
+    if let Some(port_watch) = state.port_watch
+        && response_state.headers.get("content-type") == Some(&ResponseState::HTML.to_string())
+    {
+        response_state.add_watch_script(port_watch);
+    }
+
+    // Checking for error status to log
+    if StatusCode::from_u16(response_state.status)
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+        .is_server_error()
+    {
+        log::error!("WASM status: {}", response_state.status);
+
+        match String::from_utf8(response_state.body.clone()) {
+            Ok(messagee) => {
+                log::error!("WASM response: text={}", messagee);
+            }
+            Err(_) => {
+                log::error!("WASM response: bytes={:#?}", response_state.body);
+            }
+        }
+    }
+
+    HttpResponse::from(response_state)
+}
+
+fn get_headers(req: &HttpRequest) -> HashMap<String, String> {
+    let mut headers = HashMap::new();
+
+    for (name, value) in req.headers() {
+        let Ok(value) = value.to_str() else {
+            log::warn!("Skipping non-ASCII value of header {name}");
+            continue;
+        };
+
+        // Join repeated headers as per RFC 9110
+        headers
+            .entry(name.as_str().to_lowercase())
+            .and_modify(|prev: &mut String| {
+                prev.push_str(", ");
+                prev.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+
+    headers
+}
+
+fn get_cookies(req: &HttpRequest) -> HashMap<String, String> {
+    match req.cookies() {
+        Ok(cookies) => cookies
+            .iter()
+            .map(|cookie| (cookie.name().to_string(), cookie.value().to_string()))
+            .collect(),
+        Err(err) => {
+            log::warn!("Error parsing cookies: {err}");
+            HashMap::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{FromRequest, cookie::Cookie, test::TestRequest, web};
+
+    use super::{get_cookies, get_headers, read_body};
+
+    #[test]
+    fn test_get_headers() {
+        let req = TestRequest::default()
+            .insert_header(("Accept-Language", "pl-PL"))
+            .append_header(("X-Forwarded-For", "10.0.0.1"))
+            .append_header(("X-Forwarded-For", "10.0.0.2"))
+            .to_http_request();
+
+        let headers = get_headers(&req);
+
+        assert_eq!(
+            headers.get("accept-language").map(String::as_str),
+            Some("pl-PL")
+        );
+        assert_eq!(
+            headers.get("x-forwarded-for").map(String::as_str),
+            Some("10.0.0.1, 10.0.0.2")
+        );
+    }
+
+    #[test]
+    fn test_get_cookies() {
+        let req = TestRequest::default()
+            .cookie(Cookie::new("session", "abc"))
+            .cookie(Cookie::new("theme", "dark"))
+            .to_http_request();
+
+        let cookies = get_cookies(&req);
+
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies.get("session").map(String::as_str), Some("abc"));
+        assert_eq!(cookies.get("theme").map(String::as_str), Some("dark"));
+    }
+
+    #[actix_web::test]
+    async fn test_read_body() {
+        let (req, mut payload) = TestRequest::post()
+            .set_payload("name=Ala&age=7")
+            .to_http_parts();
+
+        let Ok(payload) = web::Payload::from_request(&req, &mut payload).await else {
+            panic!("Missing payload");
+        };
+
+        let Ok(body) = read_body(payload, 1024).await else {
+            panic!("Body expected");
+        };
+        assert_eq!(body, "name=Ala&age=7");
+    }
+
+    #[actix_web::test]
+    async fn test_read_body_limit() {
+        let (req, mut payload) = TestRequest::post()
+            .set_payload(vec![0; 2048])
+            .to_http_parts();
+
+        let Ok(payload) = web::Payload::from_request(&req, &mut payload).await else {
+            panic!("Missing payload");
+        };
+
+        let Err(response) = read_body(payload, 1024).await else {
+            panic!("Body should exceed the limit");
+        };
+        assert_eq!(response.status, 413);
+    }
+}
@@ -1,20 +1,55 @@
-use vertigo::dev::{CallbackId, SsrFetchRequest, SsrFetchResponse, command::DriverDomCommand};
+use vertigo::dev::{
+    CallbackId, SsrFetchRequest, SsrFetchResponse,
+    command::{DriverDomCommand, TimerKind},
+};
+
+use crate::serve::response_state::ResponseState;
 
 #[derive(Debug)]
 pub enum Message {
-    TimeoutAndSendResponse,
     DomUpdate(Vec<DriverDomCommand>),
     Panic(Option<String>),
-    SetTimeoutZero {
+    TimerSet {
+        callback: CallbackId,
+        duration: u32,
+        kind: TimerKind,
+    },
+    TimerClear {
+        callback: CallbackId,
+    },
+    TimerFired {
         callback: CallbackId,
     },
     FetchRequest {
         callback: CallbackId,
         request: SsrFetchRequest,
     },
+    FetchAbort {
+        callback: CallbackId,
+    },
     FetchResponse {
         request: SsrFetchRequest,
         response: SsrFetchResponse,
     },
+    FetchStream {
+        callback: CallbackId,
+        request: SsrFetchRequest,
+    },
+    FetchStreamAbort {
+        callback: CallbackId,
+    },
+    FetchStreamFailed {
+        callback: CallbackId,
+    },
     SetStatus(u16),
+    SetHeader {
+        name: String,
+        value: String,
+    },
+    SetCookie(String),
+    Redirect {
+        url: String,
+        status: u16,
+    },
+    HandlerResponse(ResponseState),
 }
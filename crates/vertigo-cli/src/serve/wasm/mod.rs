@@ -1,9 +1,13 @@
 mod data_context;
 mod message;
+#[cfg(test)]
+mod test_app;
 mod wasm_instance;
 
 pub use message::Message;
-pub use wasm_instance::WasmInstance;
+#[cfg(test)]
+pub use test_app::TestApp;
+pub use wasm_instance::{HandleUrlResult, WasmInstance};
 
 const VERTIGO_VERSION_MAJOR: u32 = pkg_version::pkg_version_major!();
 const VERTIGO_VERSION_MINOR: u32 = pkg_version::pkg_version_minor!();
@@ -0,0 +1,224 @@
+//! Stub of a vertigo app for testing SSR without building a real WASM module.
+
+use std::fmt::Write;
+use vertigo::{
+    DomId, JsJson, JsJsonSerialize,
+    dev::command::{CommandForBrowser, DriverDomCommand},
+};
+
+/// Start of data segments (below there are counters of calls)
+const DATA_START: u32 = 1024;
+const HANDLE_URL_COUNTER: u32 = 16;
+const COMMAND_COUNTER: u32 = 20;
+const PAGE_SIZE: u32 = 65536;
+
+/// WASM app sending prepared commands to the server
+///
+/// Memory of the app is not freed, every block is allocated anew.
+#[derive(Default)]
+pub struct TestApp {
+    entry: Vec<CommandForBrowser>,
+    on_command: Vec<Vec<CommandForBrowser>>,
+    handle_url: Option<JsJson>,
+    handle_url_repeated: Option<JsJson>,
+}
+
+impl TestApp {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Command sent when the app starts
+    pub fn entry(mut self, command: CommandForBrowser) -> Self {
+        self.entry.push(command);
+        self
+    }
+
+    /// Commands sent when the server calls the app (i.e. fires a timer or delivers a response),
+    /// consecutive calls send consecutive lists
+    pub fn on_command(mut self, commands: Vec<CommandForBrowser>) -> Self {
+        self.on_command.push(commands);
+        self
+    }
+
+    /// Result of the first `handle_url` call in the instance (`null` renders HTML)
+    pub fn handle_url(mut self, result: JsJson) -> Self {
+        self.handle_url = Some(result);
+        self
+    }
+
+    /// Result of consecutive `handle_url` calls in the same instance
+    pub fn handle_url_repeated(mut self, result: JsJson) -> Self {
+        self.handle_url_repeated = Some(result);
+        self
+    }
+
+    /// Document with `text` in a `<div>` in the body
+    pub fn page(text: &str) -> CommandForBrowser {
+        let html = DomId::from_u64(1);
+        let head = DomId::from_u64(2);
+        let body = DomId::from_u64(3);
+        let div = DomId::from_u64(4);
+        let text_id = DomId::from_u64(5);
+
+        CommandForBrowser::DomBulkUpdate {
+            list: vec![
+                DriverDomCommand::CreateNode {
+                    id: html,
+                    name: "html".into(),
+                },
+                DriverDomCommand::CreateNode {
+                    id: head,
+                    name: "head".into(),
+                },
+                DriverDomCommand::CreateNode {
+                    id: body,
+                    name: "body".into(),
+                },
+                DriverDomCommand::CreateNode {
+                    id: div,
+                    name: "div".into(),
+                },
+                DriverDomCommand::CreateText {
+                    id: text_id,
+                    value: text.into(),
+                },
+                DriverDomCommand::InsertBefore {
+                    parent: html,
+                    child: head,
+                    ref_id: None,
+                },
+                DriverDomCommand::InsertBefore {
+                    parent: html,
+                    child: body,
+                    ref_id: None,
+                },
+                DriverDomCommand::InsertBefore {
+                    parent: body,
+                    child: div,
+                    ref_id: None,
+                },
+                DriverDomCommand::InsertBefore {
+                    parent: div,
+                    child: text_id,
+                    ref_id: None,
+                },
+            ],
+        }
+    }
+
+    /// Change text of the body of [page](Self::page)
+    pub fn update_page(text: &str) -> CommandForBrowser {
+        CommandForBrowser::DomBulkUpdate {
+            list: vec![DriverDomCommand::UpdateText {
+                id: DomId::from_u64(5),
+                value: text.into(),
+            }],
+        }
+    }
+
+    pub fn into_wat(self) -> String {
+        let mut data = Vec::new();
+
+        let mut save = |value: JsJson| -> u64 {
+            if value == JsJson::Null {
+                return 0;
+            }
+
+            let block = value.to_vec();
+            let ptr = DATA_START as usize + data.len();
+            let len = block.len();
+            data.extend(block);
+            // Keep blocks aligned
+            data.resize(data.len().next_multiple_of(8), 0);
+
+            ((ptr as u64) << 32) | len as u64
+        };
+
+        let entry: Vec<u64> = self
+            .entry
+            .into_iter()
+            .map(|command| save(command.to_json()))
+            .collect();
+
+        let on_command: Vec<Vec<u64>> = self
+            .on_command
+            .into_iter()
+            .map(|commands| {
+                commands
+                    .into_iter()
+                    .map(|command| save(command.to_json()))
+                    .collect()
+            })
+            .collect();
+
+        let handle_url = save(self.handle_url.unwrap_or(JsJson::Null));
+        let handle_url_repeated = save(self.handle_url_repeated.unwrap_or(JsJson::Null));
+
+        let heap_start = DATA_START + data.len() as u32;
+        let pages = heap_start / PAGE_SIZE + 1;
+
+        let send = |commands: &[u64]| {
+            commands
+                .iter()
+                .map(|ptr| format!("(drop (call $dom_access (i64.const {ptr})))\n"))
+                .collect::<String>()
+        };
+
+        let mut data_escaped = String::new();
+        for byte in data {
+            let _ = write!(data_escaped, "\\{byte:02x}");
+        }
+
+        format!(
+            r#"(module
+  (import "mod" "dom_access" (func $dom_access (param i64) (result i64)))
+  (import "mod" "panic_message" (func $panic_message (param i64)))
+  (memory (export "memory") {pages})
+  (global $heap (mut i32) (i32.const {heap_start}))
+  (data (i32.const {DATA_START}) "{data_escaped}")
+
+  (func (export "vertigo_export_alloc_block") (param $size i32) (result i64)
+    (local $ptr i32)
+    (local.set $ptr (global.get $heap))
+    (global.set $heap (i32.add (local.get $ptr) (local.get $size)))
+    (if (i32.gt_u (global.get $heap) (i32.mul (memory.size) (i32.const {PAGE_SIZE})))
+      (then
+        (drop (memory.grow (i32.sub
+          (i32.add (i32.div_u (global.get $heap) (i32.const {PAGE_SIZE})) (i32.const 1))
+          (memory.size))))))
+    (i64.or
+      (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+      (i64.extend_i32_u (local.get $size))))
+
+  (func (export "vertigo_export_free_block") (param i64))
+
+  (func (export "vertigo_entry_function") (param i32 i32)
+    {entry})
+
+  (func (export "vertigo_export_wasm_command") (param i64) (result i64)
+    (i32.store (i32.const {COMMAND_COUNTER})
+      (i32.add (i32.load (i32.const {COMMAND_COUNTER})) (i32.const 1)))
+    {on_command}
+    (i64.const 0))
+
+  (func (export "vertigo_export_handle_url") (param i64) (result i64)
+    (i32.store (i32.const {HANDLE_URL_COUNTER})
+      (i32.add (i32.load (i32.const {HANDLE_URL_COUNTER})) (i32.const 1)))
+    (if (result i64) (i32.eq (i32.load (i32.const {HANDLE_URL_COUNTER})) (i32.const 1))
+      (then (i64.const {handle_url}))
+      (else (i64.const {handle_url_repeated})))))
+"#,
+            entry = send(&entry),
+            on_command = on_command
+                .iter()
+                .enumerate()
+                .map(|(index, commands)| format!(
+                    "(if (i32.eq (i32.load (i32.const {COMMAND_COUNTER})) (i32.const {})) (then {}))\n",
+                    index + 1,
+                    send(commands)
+                ))
+                .collect::<String>(),
+        )
+    }
+}
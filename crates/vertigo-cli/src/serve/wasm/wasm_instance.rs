@@ -1,9 +1,9 @@
 use std::{process::exit, sync::Arc};
 use tokio::sync::mpsc::UnboundedSender;
 use vertigo::{
-    JsJson, JsJsonSerialize,
+    JsJson, JsJsonSerialize, ServerRequest,
     dev::{
-        CallbackId, LongPtr, SsrFetchResponse,
+        CallbackId, HANDLER_RESPONSE_PENDING, LongPtr, SsrFetchResponse,
         command::{CommandForBrowser, CommandForWasm, decode_json},
     },
 };
@@ -16,6 +16,15 @@ use crate::{
 
 use super::{data_context::DataContext, message::Message};
 
+pub enum HandleUrlResult {
+    /// Url not handled, regular HTML should be rendered
+    Html,
+    /// Url handled with response ready
+    Response(ResponseState),
+    /// Url handled by async handler, response will arrive as [Message::HandlerResponse]
+    Pending,
+}
+
 pub struct WasmInstance {
     instance: Instance,
     store: Store<RequestState>,
@@ -137,12 +146,17 @@ impl WasmInstance {
         JsJson::Null
     }
 
-    pub fn handle_url(&mut self, url: &str) -> Option<ResponseState> {
-        let url = JsJson::String(url.to_string());
+    pub fn handle_url(&mut self, request: &RequestState) -> HandleUrlResult {
+        let request = ServerRequest {
+            method: request.method.clone(),
+            url: request.url.clone(),
+            headers: request.headers.as_ref().clone(),
+            body: request.body.to_vec(),
+        };
 
         let params_ptr = {
             let mut data_context = DataContext::from_store(&mut self.store, self.instance);
-            data_context.save_value(url)
+            data_context.save_value(request.to_json())
         };
 
         let result = self
@@ -158,21 +172,27 @@ impl WasmInstance {
         self.decode_response_state(result)
     }
 
-    fn decode_response_state(&self, value: JsJson) -> Option<ResponseState> {
-        if let JsJson::Null = value {
-            return None;
+    fn decode_response_state(&self, value: JsJson) -> HandleUrlResult {
+        match &value {
+            JsJson::Null => return HandleUrlResult::Html,
+            JsJson::String(value) if value == HANDLER_RESPONSE_PENDING => {
+                return HandleUrlResult::Pending;
+            }
+            _ => {}
         }
 
         let response: Result<ResponseState, vertigo::JsJsonContext> =
             decode_json::<ResponseState>(value);
 
-        if let Ok(response) = response {
-            return Some(response);
+        match response {
+            Ok(response) => HandleUrlResult::Response(response),
+            Err(err) => {
+                log::error!("decode_response_state: decode error = {err:#?}");
+                HandleUrlResult::Response(ResponseState::internal_error(
+                    "Invalid response from server handler",
+                ))
+            }
         }
-
-        log::error!("decode_response_state: decode error = {response:#?}");
-
-        None
     }
 
     pub fn send_fetch_response(&mut self, callback: CallbackId, response: SsrFetchResponse) {
@@ -55,16 +55,24 @@ fn attr_to_group_variant(key: impl AsRef<str>, value: TokenStream2) -> TokenStre
         "on_change" => quote! { vertigo::AttrGroupValue::on_change(#value) },
         "on_change_file" => quote! { vertigo::AttrGroupValue::on_change_file(#value) },
         "on_click" => quote! { vertigo::AttrGroupValue::on_click(#value) },
+        "on_contextmenu" | "on_dblclick" | "on_drag" | "on_drag_end" | "on_drag_enter"
+        | "on_drag_leave" | "on_drag_over" | "on_drag_start" | "on_drop" | "on_pointer_cancel"
+        | "on_pointer_down" | "on_pointer_enter" | "on_pointer_leave" | "on_pointer_move"
+        | "on_pointer_up" | "on_scroll" | "on_touch_cancel" | "on_touch_end" | "on_touch_move"
+        | "on_touch_start" | "on_wheel" => quote! { vertigo::AttrGroupValue::on_event(#value) },
         "on_dropfile" => quote! { vertigo::AttrGroupValue::on_dropfile(#value) },
+        "on_focus" => quote! { vertigo::AttrGroupValue::on_focus(#value) },
         "on_input" => quote! { vertigo::AttrGroupValue::on_input(#value) },
         "on_intersect" => quote! { vertigo::AttrGroupValue::on_intersect(#value) },
         "on_key_down" => quote! { vertigo::AttrGroupValue::on_key_down(#value) },
+        "on_key_up" => quote! { vertigo::AttrGroupValue::on_key_up(#value) },
         "on_load" => quote! { vertigo::AttrGroupValue::on_load(#value) },
         "on_mouse_down" => quote! { vertigo::AttrGroupValue::on_mouse_down(#value) },
         "on_mouse_enter" => quote! { vertigo::AttrGroupValue::on_mouse_enter(#value) },
         "on_mouse_leave" => quote! { vertigo::AttrGroupValue::on_mouse_leave(#value) },
         "on_mouse_up" => quote! { vertigo::AttrGroupValue::on_mouse_up(#value) },
         "on_submit" | "form" => quote! { vertigo::AttrGroupValue::on_submit(#value) },
+        key if key.starts_with("on:") => quote! { vertigo::AttrGroupValue::on_event(#value) },
         _ => quote! { {#value}.into() },
     }
 }
@@ -160,12 +160,20 @@ fn push_attribute(
         return;
     }
 
+    if let Some(event) = name.strip_prefix("on:") {
+        out_attr.push(quote! { .on_event(#event, #value) });
+        return;
+    }
+
     let method_str = match name.as_str() {
         "hook_key_down" | "on_blur" | "on_change" | "on_change_file" | "on_click"
-        | "on_dropfile" | "on_input" | "on_intersect" | "on_key_down" | "on_load"
-        | "on_mouse_down" | "on_mouse_enter" | "on_mouse_leave" | "on_mouse_up" | "on_submit" => {
-            &name
-        }
+        | "on_contextmenu" | "on_dblclick" | "on_drag" | "on_drag_end" | "on_drag_enter"
+        | "on_drag_leave" | "on_drag_over" | "on_drag_start" | "on_drop" | "on_dropfile"
+        | "on_focus" | "on_input" | "on_intersect" | "on_key_down" | "on_key_up" | "on_load"
+        | "on_mouse_down" | "on_mouse_enter" | "on_mouse_leave" | "on_mouse_up"
+        | "on_pointer_cancel" | "on_pointer_down" | "on_pointer_enter" | "on_pointer_leave"
+        | "on_pointer_move" | "on_pointer_up" | "on_scroll" | "on_submit" | "on_touch_cancel"
+        | "on_touch_end" | "on_touch_move" | "on_touch_start" | "on_wheel" => &name,
 
         "form" => "on_submit",
 
@@ -286,6 +294,16 @@ mod tests {
             "on_mouse_up",
             "on_dropfile",
             "hook_key_down",
+            "on_focus",
+            "on_key_up",
+            "on_scroll",
+            "on_wheel",
+            "on_pointer_move",
+            "on_touch_start",
+            "on_drag_over",
+            "on_drop",
+            "on_contextmenu",
+            "on_dblclick",
         ];
         for attr in event_attrs {
             let (out_attr, class_values) = run_push_attribute(attr, quote! { my_handler });
@@ -299,6 +317,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_push_attribute_generic_event() {
+        let (out_attr, class_values) = run_push_attribute("on:my-event", quote! { my_handler });
+        assert_eq!(out_attr.len(), 1);
+        let s: String = out_attr[0]
+            .to_string()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        assert_eq!(s, ".on_event(\"my-event\",my_handler)");
+        assert!(class_values.is_empty());
+    }
+
     #[test]
     fn test_push_attribute_form_alias() {
         // "form" is an alias for on_submit
@@ -4,23 +4,53 @@ use quote::quote;
 use syn::{Expr, Meta, parse_macro_input, spanned::Spanned};
 
 /// Converts pseudo-javascript expression to DomAccess chain.
+///
+/// Expression ending with `.await` is converted to awaited `fetch_async()` call.
 pub(crate) fn js_expression(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as Expr);
 
     // Extract possible #[node_ref] as a base
     let base = extract_base(&input);
 
+    let (input, is_await) = split_await(&input);
+
     // Generate the output code
-    let output = generate_calls(&input, base.is_some());
+    let output = generate_calls(input, base.is_some());
 
     let base = base.unwrap_or_else(|| quote! { vertigo::get_driver() });
 
-    TokenStream::from(quote! {
-        #base
-            .dom_access()
-            #output
-            .fetch()
-    })
+    if is_await {
+        TokenStream::from(quote! {
+            #base
+                .dom_access()
+                #output
+                .fetch_async()
+                .await
+        })
+    } else {
+        TokenStream::from(quote! {
+            #base
+                .dom_access()
+                #output
+                .fetch()
+        })
+    }
+}
+
+/// Detect trailing `.await`, also inside a group (an expression passed through `js!` macro_rules) or parentheses.
+fn split_await(expr: &Expr) -> (&Expr, bool) {
+    match expr {
+        Expr::Await(expr_await) => (expr_await.base.as_ref(), true),
+        Expr::Group(group) => match split_await(&group.expr) {
+            (_, false) => (expr, false),
+            awaited => awaited,
+        },
+        Expr::Paren(paren) => match split_await(&paren.expr) {
+            (_, false) => (expr, false),
+            awaited => awaited,
+        },
+        expr => (expr, false),
+    }
 }
 
 /// Recursively generate consecutive calls to DomAccess methods based on pseudo-javascript expression.
@@ -92,6 +122,10 @@ fn extract_base(input: &Expr) -> Option<TokenStream2> {
         Expr::Call(call) => &call.attrs,
         Expr::Path(path) => &path.attrs,
         Expr::Group(group) => &group.attrs,
+        Expr::Await(expr_await) if expr_await.attrs.is_empty() => {
+            return extract_base(&expr_await.base);
+        }
+        Expr::Await(expr_await) => &expr_await.attrs,
         _ => {
             emit_warning!(input.span(), "Unsupported base {:?}", input);
             return None;
@@ -1,13 +1,13 @@
-use std::rc::Rc;
+use std::{future::Future, rc::Rc};
 
 use crate::{
-    DomNode,
+    DomNode, Resource, get_driver,
     render::{render_value, render_value_option},
 };
 
 use super::{
     DropResource, GraphValue, Value, context::Context, get_dependencies, graph_id::GraphId,
-    struct_mut::ValueMut,
+    struct_mut::ValueMut, time_gate,
 };
 
 /// A reactive value that is read-only and computed by dependency graph.
@@ -168,6 +168,36 @@ impl<T: Clone + PartialEq + 'static> Computed<T> {
         DropResource::from_struct(graph_value)
     }
 
+    /// Reactively follow this [Computed], but apply a change only after the value stays the same for `time` milliseconds.
+    ///
+    /// The current value is available immediately, every next change restarts the timer.
+    /// Useful for i.e. search-as-you-type. During SSR changes are applied immediately.
+    ///
+    /// ```rust
+    /// use vertigo::Value;
+    ///
+    /// let query = Value::new(String::new());
+    /// let search = query.to_computed().debounce(300);
+    /// ```
+    pub fn debounce(&self, time: u32) -> Computed<T> {
+        time_gate::debounce(self, time)
+    }
+
+    /// Reactively follow this [Computed], but apply changes at most once per `time` milliseconds.
+    ///
+    /// A change is applied immediately if there was none in the last `time` milliseconds,
+    /// otherwise the latest value is applied when the time passes. During SSR changes are applied immediately.
+    ///
+    /// ```rust
+    /// use vertigo::Value;
+    ///
+    /// let scroll = Value::new(0);
+    /// let position = scroll.to_computed().throttle(100);
+    /// ```
+    pub fn throttle(&self, time: u32) -> Computed<T> {
+        time_gate::throttle(self, time)
+    }
+
     /// Render value inside this [Computed]. See [Value::render_value()] for examples.
     pub fn render_value(&self, render: impl Fn(T) -> DomNode + 'static) -> DomNode {
         render_value(self.clone(), render)
@@ -179,6 +209,84 @@ impl<T: Clone + PartialEq + 'static> Computed<T> {
     }
 }
 
+impl<T: Clone + PartialEq + 'static> Computed<Resource<T>> {
+    /// Creates new [`Computed<Resource<T>>`] which state is determined by provided async generator function.
+    ///
+    /// The function is run (and returned future spawned) when the value starts being observed,
+    /// and again every time the values it read using `context` change. The value is [Resource::Loading]
+    /// until the latest future completes, results of futures superseded by a newer run are discarded.
+    ///
+    /// ```rust
+    /// use vertigo::{Computed, RequestBuilder, Value};
+    ///
+    /// let query = Value::new("vertigo".to_string());
+    ///
+    /// let results = Computed::from_async({
+    ///     let query = query.clone();
+    ///     move |context| {
+    ///         let query = query.get(context);
+    ///         async move {
+    ///             RequestBuilder::get(format!("/api/search?q={query}"))
+    ///                 .call()
+    ///                 .await
+    ///                 .into_data::<Vec<String>>()
+    ///                 .into()
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn from_async<Fut, F>(get_value: F) -> Computed<Resource<T>>
+    where
+        F: Fn(&Context) -> Fut + 'static,
+        Fut: Future<Output = Resource<T>> + 'static,
+    {
+        let run_id = Rc::new(ValueMut::new(0u64));
+        let result = Value::new((0u64, Resource::Loading));
+
+        let runner = Computed::from({
+            let run_id = run_id.clone();
+            let result = result.clone();
+
+            move |context| {
+                if context.is_transaction() {
+                    // Reading in a transaction doesn't start a new run
+                    return run_id.get();
+                }
+
+                let future = get_value(context);
+                let id = run_id.change(|id| {
+                    *id += 1;
+                    *id
+                });
+
+                let run_id = run_id.clone();
+                let result = result.clone();
+
+                get_driver().spawn(async move {
+                    let value = future.await;
+
+                    if run_id.get() == id {
+                        result.set((id, value));
+                    }
+                });
+
+                id
+            }
+        });
+
+        Computed::from(move |context| {
+            let id = runner.get(context);
+            let (result_id, value) = result.get(context);
+
+            if result_id == id {
+                value
+            } else {
+                Resource::Loading
+            }
+        })
+    }
+}
+
 impl<T: Clone + PartialEq + 'static> From<Value<T>> for Computed<T> {
     fn from(val: Value<T>) -> Self {
         val.to_computed()
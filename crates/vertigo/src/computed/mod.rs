@@ -8,6 +8,7 @@ mod graph_id;
 mod graph_value;
 mod reactive;
 pub mod struct_mut;
+mod time_gate;
 mod to_computed;
 mod value;
 mod value_inner;
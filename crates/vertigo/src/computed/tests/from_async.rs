@@ -0,0 +1,93 @@
+use std::rc::Rc;
+
+use crate::{
+    Resource,
+    computed::{
+        Computed, Value,
+        struct_mut::{ValueMut, VecMut},
+        tests::box_value_version::SubscribeValueVer,
+    },
+    dev::{FutureBox, FutureBoxSend},
+    driver_module::api::api_timers,
+    transaction,
+};
+
+async fn next_ticks() {
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+}
+
+#[tokio::test]
+async fn test_from_async_discards_superseded_runs() {
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async {
+            api_timers().mock_zero_timeouts();
+
+            let query = Value::new(1);
+            let pending: Rc<VecMut<FutureBoxSend<u32>>> = Rc::new(VecMut::new());
+
+            let result = Computed::from_async({
+                let query = query.clone();
+                let pending = pending.clone();
+
+                move |context| {
+                    let query = query.get(context);
+                    let (sender, receiver) = FutureBox::new();
+                    pending.push(sender);
+
+                    async move { Resource::Ready(query * receiver.await) }
+                }
+            });
+
+            let result = SubscribeValueVer::new(result);
+            assert_eq!(result.get(), (Resource::Loading, 1));
+
+            query.set(2);
+            assert_eq!(pending.map(|list| list.len()), 2);
+
+            // Newer run finishes first
+            pending.map(|list| list[1].clone()).publish(10);
+            next_ticks().await;
+            assert_eq!(result.get(), (Resource::Ready(20), 2));
+
+            // Result of the superseded run is ignored
+            pending.map(|list| list[0].clone()).publish(10);
+            next_ticks().await;
+            assert_eq!(result.get(), (Resource::Ready(20), 2));
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_from_async_not_started_in_transaction() {
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async {
+            api_timers().mock_zero_timeouts();
+
+            let started = Rc::new(ValueMut::new(0));
+
+            let result = Computed::from_async({
+                let started = started.clone();
+
+                move |_| {
+                    started.change(|started| *started += 1);
+                    async move { Resource::Ready(1) }
+                }
+            });
+
+            // Reading in a transaction doesn't start a future
+            let value = transaction(|context| result.get(context));
+            next_ticks().await;
+            assert_eq!(value, Resource::Loading);
+            assert_eq!(started.get(), 0);
+
+            let result = SubscribeValueVer::new(result);
+            next_ticks().await;
+            assert_eq!(started.get(), 1);
+            assert_eq!(result.get(), (Resource::Ready(1), 2));
+        })
+        .await;
+}
@@ -1,4 +1,6 @@
 pub mod app_state;
 pub mod box_value_version;
 pub mod computed;
+pub mod from_async;
 pub mod nested_reactivity;
+pub mod time_gate;
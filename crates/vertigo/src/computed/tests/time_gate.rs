@@ -0,0 +1,222 @@
+use std::rc::Rc;
+
+use crate::{
+    computed::{Value, struct_mut::ValueMut, tests::box_value_version::SubscribeValueVer},
+    dev::CallbackId,
+    driver_module::api::api_timers,
+};
+
+/// Clock controlled by the test, timeouts fire only when the time is advanced
+struct MockClock {
+    now: ValueMut<u32>,
+    timers: ValueMut<Vec<(u32, CallbackId)>>,
+}
+
+impl MockClock {
+    fn install() -> Rc<MockClock> {
+        let clock = Rc::new(MockClock {
+            now: ValueMut::new(0),
+            timers: ValueMut::new(Vec::new()),
+        });
+
+        api_timers().set_mock_handler({
+            let clock = clock.clone();
+            move |duration, callback_id, _kind| {
+                let due = clock.now.get() + duration;
+                clock
+                    .timers
+                    .change(|timers| timers.push((due, callback_id)));
+            }
+        });
+
+        clock
+    }
+
+    fn advance(&self, time: u32) {
+        let now = self.now.get() + time;
+        self.now.set(now);
+
+        loop {
+            let next = self.timers.change(|timers| {
+                let index = timers
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (due, _))| *due <= now)
+                    .min_by_key(|(_, (due, _))| *due)
+                    .map(|(index, _)| index)?;
+
+                Some(timers.remove(index).1)
+            });
+
+            // Cancelled timers are not found in the timers store
+            match next {
+                Some(callback_id) => api_timers().callback_timeout(callback_id),
+                None => break,
+            }
+        }
+    }
+}
+
+#[test]
+fn test_debounce() {
+    let clock = MockClock::install();
+
+    let value = Value::new(1);
+    let debounced = SubscribeValueVer::new(value.to_computed().debounce(100));
+    assert_eq!(debounced.get(), (1, 1));
+
+    value.set(2);
+    clock.advance(50);
+    value.set(3);
+    clock.advance(50);
+    assert_eq!(debounced.get(), (1, 1));
+
+    clock.advance(50);
+    assert_eq!(debounced.get(), (3, 2));
+
+    // Returning to the shown value cancels the change
+    value.set(4);
+    value.set(3);
+    clock.advance(200);
+    assert_eq!(debounced.get(), (3, 2));
+}
+
+#[test]
+fn test_throttle() {
+    let clock = MockClock::install();
+
+    let value = Value::new(1);
+    let throttled = SubscribeValueVer::new(value.to_computed().throttle(100));
+    assert_eq!(throttled.get(), (1, 1));
+
+    value.set(2);
+    assert_eq!(throttled.get(), (2, 2));
+
+    value.set(3);
+    clock.advance(50);
+    value.set(4);
+    assert_eq!(throttled.get(), (2, 2));
+
+    // The latest value is applied when the window ends
+    clock.advance(50);
+    assert_eq!(throttled.get(), (4, 3));
+
+    clock.advance(100);
+    value.set(5);
+    assert_eq!(throttled.get(), (5, 4));
+}
+
+#[test]
+fn test_debounce_reconnect() {
+    let clock = MockClock::install();
+
+    let value = Value::new(1);
+    let debounced = value.to_computed().debounce(100);
+
+    let current = SubscribeValueVer::new(debounced.clone());
+    value.set(2);
+    clock.advance(50);
+
+    // Pending change is dropped together with the last observer
+    drop(current);
+    clock.advance(50);
+
+    // The next observer starts with the current value
+    let current = SubscribeValueVer::new(debounced.clone());
+    assert_eq!(current.get(), (2, 1));
+
+    value.set(3);
+    clock.advance(50);
+
+    // Another observer coming and going doesn't restart the timer
+    drop(SubscribeValueVer::new(debounced.clone()));
+    clock.advance(50);
+    assert_eq!(current.get(), (3, 2));
+}
+
+#[test]
+fn test_throttle_reconnect() {
+    let clock = MockClock::install();
+
+    let value = Value::new(1);
+    let throttled = value.to_computed().throttle(100);
+
+    let current = SubscribeValueVer::new(throttled.clone());
+    value.set(2);
+    value.set(3);
+    assert_eq!(current.get(), (2, 2));
+
+    drop(current);
+    clock.advance(100);
+
+    let current = SubscribeValueVer::new(throttled.clone());
+    assert_eq!(current.get(), (3, 1));
+
+    // Window of the previous observer is gone
+    value.set(4);
+    assert_eq!(current.get(), (4, 2));
+}
+
+#[test]
+fn test_set_debounced() {
+    let clock = MockClock::install();
+
+    let value = Value::new(1);
+    let current = SubscribeValueVer::new(value.to_computed());
+
+    value.set_debounced(2, 100);
+    clock.advance(50);
+    value.set_debounced(3, 100);
+    clock.advance(50);
+    assert_eq!(current.get(), (1, 1));
+
+    clock.advance(50);
+    assert_eq!(current.get(), (3, 2));
+
+    // Direct set cancels the pending one
+    value.set_debounced(4, 100);
+    value.set(5);
+    clock.advance(100);
+    assert_eq!(current.get(), (5, 3));
+}
+
+#[test]
+fn test_set_debounced_cancel() {
+    let clock = MockClock::install();
+
+    let value = Value::new(Rc::new(1));
+    let current = SubscribeValueVer::new(value.to_computed());
+
+    // Pending set holds its value until it's applied or cancelled
+    let pending = Rc::new(2);
+    value.set_debounced(pending.clone(), 100);
+    assert_eq!(Rc::strong_count(&pending), 2);
+
+    value.set(Rc::new(3));
+    assert_eq!(Rc::strong_count(&pending), 1);
+
+    value.set_debounced(pending.clone(), 100);
+    value.set_debounced(Rc::new(4), 100);
+    assert_eq!(Rc::strong_count(&pending), 1);
+
+    // Dropping the value cancels its pending set
+    value.set_debounced(pending.clone(), 100);
+    drop(current);
+    drop(value);
+    assert_eq!(Rc::strong_count(&pending), 1);
+
+    clock.advance(100);
+}
+
+#[test]
+fn test_no_delay_on_server() {
+    // Without timers (as during SSR) changes are applied immediately
+    let value = Value::new(1);
+    let debounced = SubscribeValueVer::new(value.to_computed().debounce(100));
+    let throttled = SubscribeValueVer::new(value.to_computed().throttle(100));
+
+    value.set(2);
+    value.set_debounced(3, 100);
+    assert_eq!(debounced.get(), (3, 3));
+    assert_eq!(throttled.get(), (3, 3));
+}
@@ -0,0 +1,185 @@
+use std::rc::Rc;
+
+use crate::driver_module::api::api_timers;
+
+use super::{Computed, DropResource, Value, dependencies::get_dependencies, struct_mut::ValueMut};
+
+type OnChange<T> = Rc<dyn Fn(&Rc<TimeGate<T>>, T)>;
+
+/// Connection of a time-based operator ([Computed::debounce], [Computed::throttle]) to its source.
+///
+/// It exists only while the operator is observed, and so does its timer. Values can't be set while
+/// the dependency graph is refreshing, so a change of the shown value made by the source subscription
+/// is announced (by bumping `tick`) when the transaction ends.
+struct TimeGate<T> {
+    shown: Rc<ValueMut<Option<T>>>,
+    latest: ValueMut<Option<T>>,
+    timer: ValueMut<Option<DropResource>>,
+    changed: ValueMut<bool>,
+    tick: Value<u64>,
+}
+
+impl<T: Clone + PartialEq + 'static> TimeGate<T> {
+    fn is_shown(&self, value: &T) -> bool {
+        self.shown.map(|shown| shown.as_ref() == Some(value))
+    }
+
+    fn show(&self, value: T) {
+        self.shown.set(Some(value));
+        self.changed.set(true);
+    }
+
+    /// Let the operator know that the shown value has changed
+    fn notify(&self) {
+        if self
+            .changed
+            .change(|changed| std::mem::replace(changed, false))
+        {
+            self.tick.change(|tick| *tick += 1);
+        }
+    }
+
+    fn start_timer(self: &Rc<Self>, time: u32, on_end: impl Fn(&Rc<Self>) + 'static) {
+        let gate = Rc::downgrade(self);
+
+        let timer = api_timers().timeout(time, move || {
+            if let Some(gate) = gate.upgrade() {
+                gate.timer.set(None);
+                on_end(&gate);
+                gate.notify();
+            }
+        });
+
+        self.timer.set(Some(timer));
+    }
+
+    fn stop_timer(&self) {
+        self.timer.set(None);
+    }
+
+    fn is_waiting(&self) -> bool {
+        self.timer.map(|timer| timer.is_some())
+    }
+
+    fn connect(
+        source: &Computed<T>,
+        shown: &Rc<ValueMut<Option<T>>>,
+        tick: &Value<u64>,
+        on_change: OnChange<T>,
+    ) -> DropResource {
+        let gate = Rc::new(TimeGate {
+            shown: shown.clone(),
+            latest: ValueMut::new(None),
+            timer: ValueMut::new(None),
+            changed: ValueMut::new(false),
+            tick: tick.clone(),
+        });
+
+        let subscription = source.clone().subscribe({
+            let gate = gate.clone();
+
+            move |value| {
+                if gate.shown.map(|shown| shown.is_none()) {
+                    // The first value, already shown by the operator
+                    gate.shown.set(Some(value));
+                    return;
+                }
+
+                on_change(&gate, value);
+            }
+        });
+
+        let transaction_end = get_dependencies().hooks.on_after_transaction({
+            let gate = gate.clone();
+            move || gate.notify()
+        });
+
+        DropResource::new(move || {
+            subscription.off();
+            transaction_end.off();
+
+            // The next observer starts with the current value of the source
+            gate.shown.set(None);
+        })
+    }
+
+    /// Create operator computed, `on_change` decides what to do with a new value of `source`
+    fn computed(
+        source: &Computed<T>,
+        on_change: impl Fn(&Rc<TimeGate<T>>, T) + 'static,
+    ) -> Computed<T> {
+        let shown = Rc::new(ValueMut::new(None));
+        let on_change: OnChange<T> = Rc::new(on_change);
+
+        let tick = Value::with_connect(0, {
+            let source = source.clone();
+            let shown = shown.clone();
+            move |tick| TimeGate::connect(&source, &shown, tick, on_change.clone())
+        });
+
+        let source = source.clone();
+
+        Computed::from(move |context| {
+            tick.get(context);
+
+            match shown.get() {
+                Some(shown) => shown,
+                // Not connected yet
+                None => source.get(context),
+            }
+        })
+    }
+}
+
+pub(crate) fn debounce<T: Clone + PartialEq + 'static>(
+    source: &Computed<T>,
+    time: u32,
+) -> Computed<T> {
+    if !api_timers().is_delay_enabled() {
+        return source.clone();
+    }
+
+    TimeGate::computed(source, move |gate, value| {
+        if gate.is_shown(&value) {
+            // Returned to the shown value before the time passed
+            gate.stop_timer();
+        } else {
+            gate.start_timer(time, move |gate| gate.show(value.clone()));
+        }
+    })
+}
+
+pub(crate) fn throttle<T: Clone + PartialEq + 'static>(
+    source: &Computed<T>,
+    time: u32,
+) -> Computed<T> {
+    if !api_timers().is_delay_enabled() {
+        return source.clone();
+    }
+
+    TimeGate::computed(source, move |gate, value| {
+        if gate.is_waiting() {
+            // The latest value will be shown when the window ends
+            gate.latest.set(Some(value));
+            return;
+        }
+
+        if !gate.is_shown(&value) {
+            gate.show(value);
+            throttle_window(gate, time);
+        }
+    })
+}
+
+fn throttle_window<T: Clone + PartialEq + 'static>(gate: &Rc<TimeGate<T>>, time: u32) {
+    gate.start_timer(time, move |gate| {
+        let latest = gate.latest.change(Option::take);
+
+        if let Some(latest) = latest
+            && !gate.is_shown(&latest)
+        {
+            gate.show(latest);
+            throttle_window(gate, time);
+        }
+    });
+}
@@ -2,7 +2,9 @@ use std::rc::Rc;
 
 use vertigo_macro::bind;
 
-use crate::{Context, DomNode, ToComputed, computed::value_inner::ValueInner};
+use crate::{
+    Context, DomNode, ToComputed, computed::value_inner::ValueInner, driver_module::api::api_timers,
+};
 
 use super::{Computed, DropResource, GraphId, dependencies::get_dependencies};
 
@@ -116,6 +118,8 @@ impl<T: Clone + PartialEq + 'static> Value<T> {
     }
 
     pub fn set(&self, value: T) {
+        self.inner.cancel_debounce();
+
         get_dependencies().transaction(|_| {
             let need_refresh = self.inner.set(value);
             if need_refresh {
@@ -124,6 +128,36 @@ impl<T: Clone + PartialEq + 'static> Value<T> {
         });
     }
 
+    /// Set the value after `time` milliseconds, unless it's set again in the meantime.
+    ///
+    /// Every call cancels the previously scheduled one, so does [set](Value::set).
+    /// During SSR the value is set immediately.
+    ///
+    /// ```rust
+    /// use vertigo::Value;
+    ///
+    /// let query = Value::new(String::new());
+    ///
+    /// // i.e. in on_input handler
+    /// query.set_debounced("vertigo".to_string(), 300);
+    /// ```
+    pub fn set_debounced(&self, value: T, time: u32) {
+        if !api_timers().is_delay_enabled() {
+            self.set(value);
+            return;
+        }
+
+        let inner = Rc::downgrade(&self.inner);
+
+        let timer = api_timers().timeout(time, move || {
+            if let Some(inner) = inner.upgrade() {
+                Value { inner }.set(value.clone());
+            }
+        });
+
+        self.inner.set_debounce(timer);
+    }
+
     /// Render value (reactively transforms `T` into `DomNode`)
     ///
     /// See [computed_tuple](macro.computed_tuple.html) if you want to render multiple values in a handy way.
@@ -5,6 +5,7 @@ pub struct ValueInner<T: PartialEq + Clone + 'static> {
     pub id: GraphId,
     value: ValueMut<T>,
     events: EventEmitter<T>,
+    debounce: ValueMut<Option<DropResource>>,
 }
 
 impl<T: PartialEq + Clone + 'static> ValueInner<T> {
@@ -13,6 +14,7 @@ impl<T: PartialEq + Clone + 'static> ValueInner<T> {
             id: GraphId::new_value(),
             value: ValueMut::new(value.clone()),
             events: EventEmitter::default(),
+            debounce: ValueMut::new(None),
         }
     }
 
@@ -31,6 +33,17 @@ impl<T: PartialEq + Clone + 'static> ValueInner<T> {
         self.events.add(callback)
     }
 
+    /// Replace (cancel) the pending debounced set
+    pub fn set_debounce(&self, timer: DropResource) {
+        self.debounce.set(Some(timer));
+    }
+
+    /// Cancel the pending debounced set, if there is one
+    pub fn cancel_debounce(&self) {
+        let pending = self.debounce.change(Option::take);
+        drop(pending);
+    }
+
     pub fn get(&self) -> T {
         self.value.get()
     }
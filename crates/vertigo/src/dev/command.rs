@@ -1,8 +1,8 @@
 use vertigo_macro::AutoJsJson;
 
 use crate::{
-    DomId, JsJson, JsJsonContext, JsJsonDeserialize,
-    dev::{SsrFetchRequest, SsrFetchResponse, callback_id::CallbackId},
+    DomId, FetchProgress, JsJson, JsJsonContext, JsJsonDeserialize, ServerResponse,
+    dev::{OfflineCacheEntry, SsrFetchRequest, SsrFetchResponse, callback_id::CallbackId},
     driver_module::StaticString,
 };
 
@@ -13,13 +13,46 @@ pub fn decode_json<T: JsJsonDeserialize>(json: JsJson) -> Result<T, JsJsonContex
 #[derive(AutoJsJson, Debug)]
 pub enum CommandForBrowser {
     FetchCacheGet,
+    SsrContextGet,
     FetchExec {
         request: SsrFetchRequest,
         callback: CallbackId,
+        /// Report progress with [CommandForWasm::FetchProgress]
+        progress: bool,
+    },
+    /// Abort request started with [CommandForBrowser::FetchExec], no response is sent back
+    FetchAbort {
+        callback: CallbackId,
+    },
+    /// Execute request and send [FetchStreamEvent](crate::FetchStreamEvent)s to the callback (via [CommandForWasm::CallbackCall])
+    FetchStream {
+        request: SsrFetchRequest,
+        callback: CallbackId,
+    },
+    FetchStreamAbort {
+        callback: CallbackId,
     },
     SetStatus {
         status: u16,
     },
+    SetResponseHeader {
+        name: String,
+        value: String,
+    },
+    Redirect {
+        url: String,
+        status: u16,
+    },
+    /// Override SSR timeout (in milliseconds) for current request
+    SetSsrTimeout {
+        time: u32,
+    },
+    /// Time (in milliseconds) left until SSR timeout
+    SsrTimeLeftGet,
+    /// Response of async server handler
+    ServerHandlerResponse {
+        response: ServerResponse,
+    },
     IsBrowser,
     GetDateNow,
 
@@ -75,10 +108,51 @@ pub enum CommandForBrowser {
         expires_in: u64,
     },
 
+    StorageGet {
+        kind: StorageKind,
+        key: String,
+    },
+    StorageSet {
+        kind: StorageKind,
+        key: String,
+        value: JsJson,
+    },
+    StorageRemove {
+        kind: StorageKind,
+        key: String,
+    },
+    /// Subscribe to changes of the key made in other tabs (`storage` event)
+    StorageWatch {
+        kind: StorageKind,
+        key: String,
+        callback: CallbackId,
+    },
+    StorageUnwatch {
+        callback: CallbackId,
+    },
+
+    /// Read the response stored in IndexedDB, [OfflineCacheEntry] or null is sent to the callback
+    OfflineCacheGet {
+        request: SsrFetchRequest,
+        callback: CallbackId,
+    },
+    OfflineCacheSet {
+        request: SsrFetchRequest,
+        entry: OfflineCacheEntry,
+    },
+    /// Remove all responses stored in IndexedDB
+    OfflineCacheClear,
+
     GetEnv {
         name: String,
     },
 
+    RequestHeaderGet {
+        name: String,
+    },
+    RequestMethodGet,
+    RequestBodyGet,
+
     Log {
         kind: ConsoleLogLevel,
         message: String,
@@ -98,6 +172,26 @@ pub enum CommandForBrowser {
     JsApiCall {
         commands: Vec<JsApiCommand>,
     },
+    /// Call JS API and resolve the result (if it's a Promise), which is sent back as [CommandForWasm::JsApiResponse]
+    JsApiCallAsync {
+        commands: Vec<JsApiCommand>,
+        callback: CallbackId,
+    },
+    /// Forget the JS function created for a [JsCallback](crate::JsCallback)
+    JsCallbackRelease {
+        callback: CallbackId,
+    },
+    /// Read bytes of a file (see [DropFileItem](crate::DropFileItem)), sent back as [CommandForWasm::JsApiResponse]
+    FileRead {
+        file: u64,
+        start: u64,
+        end: u64,
+        callback: CallbackId,
+    },
+    /// Forget the file (see [DropFileItem](crate::DropFileItem))
+    FileRelease {
+        file: u64,
+    },
 
     DomBulkUpdate {
         list: Vec<DriverDomCommand>,
@@ -128,6 +222,14 @@ pub enum LocationTarget {
     History,
 }
 
+#[derive(AutoJsJson, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKind {
+    /// `window.localStorage`
+    Local,
+    /// `window.sessionStorage`
+    Session,
+}
+
 #[derive(AutoJsJson, Debug, Clone, Copy)]
 pub enum LocationCallbackMode {
     Add,
@@ -149,7 +251,7 @@ pub enum TimerKind {
 pub mod browser_response {
     use vertigo_macro::AutoJsJson;
 
-    use crate::{InstantType, JsJson};
+    use crate::{InstantType, JsJson, dev::SsrContext};
 
     #[derive(AutoJsJson)]
     pub struct FetchCacheGet {
@@ -181,11 +283,50 @@ pub mod browser_response {
         pub value: JsJson,
     }
 
+    /// Value of a storage key, also sent to [CommandForBrowser::StorageWatch] callbacks
+    #[derive(AutoJsJson)]
+    pub struct StorageGet {
+        /// False if the key is missing (`value` is `null` then)
+        pub stored: bool,
+        pub value: JsJson,
+    }
+
+    impl StorageGet {
+        pub fn into_value(self) -> Option<JsJson> {
+            self.stored.then_some(self.value)
+        }
+    }
+
     #[derive(AutoJsJson)]
     pub struct GetEnv {
         pub value: Option<String>,
     }
 
+    #[derive(AutoJsJson)]
+    pub struct RequestHeaderGet {
+        pub value: Option<String>,
+    }
+
+    #[derive(AutoJsJson)]
+    pub struct SsrContextGet {
+        pub value: Option<SsrContext>,
+    }
+
+    #[derive(AutoJsJson)]
+    pub struct SsrTimeLeftGet {
+        pub value: Option<u32>,
+    }
+
+    #[derive(AutoJsJson)]
+    pub struct RequestMethodGet {
+        pub value: Option<String>,
+    }
+
+    #[derive(AutoJsJson)]
+    pub struct RequestBodyGet {
+        pub value: Option<Vec<u8>>,
+    }
+
     #[derive(AutoJsJson)]
     pub struct TimezoneOffset {
         pub value: i32,
@@ -211,6 +352,11 @@ pub enum CommandForWasm {
         callback: CallbackId,
     },
 
+    FetchProgress {
+        callback: CallbackId,
+        progress: FetchProgress,
+    },
+
     Websocket {
         callback: CallbackId,
         message: WebsocketMessageFromBrowser,
@@ -229,6 +375,13 @@ pub enum CommandForWasm {
         callback_id: CallbackId,
         value: JsJson,
     },
+
+    /// Result of [CommandForBrowser::JsApiCallAsync] (`success` is false if the Promise was rejected)
+    JsApiResponse {
+        callback: CallbackId,
+        success: bool,
+        value: JsJson,
+    },
 }
 
 #[derive(AutoJsJson, Clone, Debug)]
@@ -7,14 +7,20 @@ pub mod inspect;
 mod long_ptr;
 pub use long_ptr::LongPtr;
 
+mod ssr_context;
+pub use ssr_context::{SSR_TIMEZONE_OFFSET_COOKIE, SsrContext};
+
 mod ssr_fetch_response;
 pub use ssr_fetch_response::{
-    SsrFetchCache, SsrFetchRequest, SsrFetchRequestBody, SsrFetchResponse, SsrFetchResponseContent,
+    OfflineCacheEntry, SsrFetchCache, SsrFetchRequest, SsrFetchRequestBody, SsrFetchResponse,
+    SsrFetchResponseContent, SsrFetchResponseKind, SsrFormField, SsrMultipartPart,
+    SsrMultipartValue,
 };
 
 pub use super::{
     computed::struct_mut::{BTreeMapMut, HashMapMut, ValueMut, VecDequeMut, VecMut},
     driver_module::{
+        api::HANDLER_RESPONSE_PENDING,
         driver::{VERTIGO_MOUNT_POINT_PLACEHOLDER, VERTIGO_PUBLIC_BUILD_PATH_PLACEHOLDER},
         js_value::{JsJsonListDecoder, MemoryBlock, MemoryBlockRead, MemoryBlockWrite},
     },
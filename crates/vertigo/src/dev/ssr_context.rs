@@ -0,0 +1,18 @@
+use vertigo_macro::AutoJsJson;
+
+use crate::InstantType;
+
+/// Name of the cookie in which the browser stores its timezone offset for SSR
+pub const SSR_TIMEZONE_OFFSET_COOKIE: &str = "vertigo-timezone-offset";
+
+/// Values used during SSR which are replayed by the browser during the first render,
+/// so the hydration of dates and random values is consistent.
+#[derive(AutoJsJson, Debug, Clone, Default, PartialEq)]
+pub struct SsrContext {
+    /// Current time (in milliseconds) used for the whole render
+    pub now: InstantType,
+    /// Seed for random numbers generator
+    pub seed: u32,
+    /// Timezone offset in minutes (as returned by JS `Date.getTimezoneOffset`)
+    pub timezone_offset: i32,
+}
@@ -2,12 +2,58 @@ use std::collections::BTreeMap;
 
 use vertigo_macro::AutoJsJson;
 
-use crate::{FetchMethod, JsJson};
+use crate::{FetchMethod, InstantType, JsJson};
 
 #[derive(AutoJsJson, Debug, Hash, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SsrFetchRequestBody {
     None,
-    Data { data: JsJson },
+    Data {
+        data: JsJson,
+    },
+    /// File selected by the user, sent by the browser without copying into wasm memory
+    File {
+        file: u64,
+    },
+    Text {
+        text: String,
+    },
+    Bytes {
+        data: Vec<u8>,
+    },
+    FormUrlEncoded {
+        fields: Vec<SsrFormField>,
+    },
+    /// `multipart/form-data` body, Content-Type header (with boundary) is generated by the client
+    Multipart {
+        parts: Vec<SsrMultipartPart>,
+    },
+}
+
+#[derive(AutoJsJson, Debug, Hash, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SsrFormField {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(AutoJsJson, Debug, Hash, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SsrMultipartPart {
+    pub name: String,
+    pub value: SsrMultipartValue,
+}
+
+#[derive(AutoJsJson, Debug, Hash, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SsrMultipartValue {
+    Text {
+        text: String,
+    },
+    Bytes {
+        data: Vec<u8>,
+        file_name: Option<String>,
+        content_type: Option<String>,
+    },
+    File {
+        file: u64,
+    },
 }
 
 #[derive(AutoJsJson, Debug, Hash, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -22,6 +68,8 @@ pub struct SsrFetchRequest {
 pub enum SsrFetchResponse {
     Ok {
         status: u32,
+        /// Response headers (with lowercase names)
+        headers: BTreeMap<String, String>,
         response: SsrFetchResponseContent,
     },
     Err {
@@ -29,10 +77,69 @@ pub enum SsrFetchResponse {
     },
 }
 
+impl SsrFetchResponse {
+    /// Network error or status which may be resolved by repeating the request
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Self::Ok { status, .. } => matches!(status, 408 | 429 | 500 | 502 | 503 | 504),
+            Self::Err { .. } => true,
+        }
+    }
+
+    /// Response with 2xx status
+    pub fn is_success(&self) -> bool {
+        match self {
+            Self::Ok { status, .. } => (200..300).contains(status),
+            Self::Err { .. } => false,
+        }
+    }
+}
+
+/// Response kept in the browser's IndexedDB (see [RequestBuilder::offline_cache](crate::RequestBuilder::offline_cache))
+#[derive(AutoJsJson, Debug, Clone)]
+pub struct OfflineCacheEntry {
+    /// When the response was received (milliseconds since epoch)
+    pub stored_at: InstantType,
+    pub response: SsrFetchResponse,
+}
+
 #[derive(AutoJsJson, Debug, Hash, Clone)]
 pub enum SsrFetchResponseContent {
     Json(JsJson),
     Text(String),
+    /// Response with Content-Type which is neither JSON nor text (i.e. image, archive or protobuf)
+    Bytes(Vec<u8>),
+}
+
+impl SsrFetchResponseContent {
+    /// Decide how the response body should be decoded, basing on its Content-Type
+    pub fn kind_for(content_type: Option<&str>) -> SsrFetchResponseKind {
+        let Some(content_type) = content_type else {
+            return SsrFetchResponseKind::Json;
+        };
+
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+
+        if mime.is_empty() || mime == "application/json" || mime.ends_with("+json") {
+            SsrFetchResponseKind::Json
+        } else if mime.starts_with("text/") {
+            SsrFetchResponseKind::Text
+        } else {
+            SsrFetchResponseKind::Bytes
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SsrFetchResponseKind {
+    Json,
+    Text,
+    Bytes,
 }
 
 #[derive(AutoJsJson, Debug, Hash, Clone, Default)]
@@ -55,3 +162,38 @@ impl SsrFetchCache {
         self.data.get(request)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SsrFetchResponseContent, SsrFetchResponseKind};
+
+    #[test]
+    fn test_kind_for() {
+        let kind_for = SsrFetchResponseContent::kind_for;
+
+        assert_eq!(kind_for(None), SsrFetchResponseKind::Json);
+        assert_eq!(kind_for(Some("")), SsrFetchResponseKind::Json);
+        assert_eq!(
+            kind_for(Some("application/json; charset=utf-8")),
+            SsrFetchResponseKind::Json
+        );
+        assert_eq!(
+            kind_for(Some("application/problem+json")),
+            SsrFetchResponseKind::Json
+        );
+        assert_eq!(
+            kind_for(Some("Application/JSON")),
+            SsrFetchResponseKind::Json
+        );
+        assert_eq!(
+            kind_for(Some("text/html;charset=UTF-8")),
+            SsrFetchResponseKind::Text
+        );
+        assert_eq!(kind_for(Some(" TEXT/CSV ")), SsrFetchResponseKind::Text);
+        assert_eq!(kind_for(Some("image/png")), SsrFetchResponseKind::Bytes);
+        assert_eq!(
+            kind_for(Some("application/octet-stream")),
+            SsrFetchResponseKind::Bytes
+        );
+    }
+}
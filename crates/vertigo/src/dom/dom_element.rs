@@ -17,9 +17,35 @@ use super::{
     dom_element_ref::DomElementRef,
     dom_id::DomId,
     dom_node::DomNode,
-    events::{ClickEvent, DropFileEvent, IntersectionEvent, KeyDownEvent},
+    events::{
+        ClickEvent, DomEvent, DropFileEvent, IntersectionEvent, KeyDownEvent, KeyUpEvent,
+        MouseEvent,
+    },
 };
 
+macro_rules! dom_event_handlers {
+    ($(($name:ident, $name_rc:ident, $event:literal)),* $(,)?) => {
+        $(
+            #[doc = concat!("Handle `", $event, "` event, see [on_event](Self::on_event).")]
+            pub fn $name(self, callback: impl Into<Callback1<DomEvent, ()>>) -> Self {
+                self.on_event($event, callback)
+            }
+
+            pub fn $name_rc(self, callback: Rc<Callback1<DomEvent, ()>>) -> Self {
+                self.on_event_rc($event, callback)
+            }
+        )*
+
+        /// Get DOM event name for handler name (i.e. `on_scroll` -> `scroll`)
+        fn dom_event_name(handler: &str) -> Option<&'static str> {
+            match handler {
+                $(stringify!($name) => Some($event),)*
+                _ => None,
+            }
+        }
+    };
+}
+
 /// A Real DOM representative - element kind
 pub struct DomElement {
     id_dom: DomId,
@@ -131,12 +157,27 @@ impl DomElement {
             ("on_mouse_up", AttrGroupValue::OnMouseUp(on_mouse_up)) => {
                 self.on_mouse_up_rc(on_mouse_up)
             }
+            ("on_focus", AttrGroupValue::OnFocus(on_focus)) => self.on_focus_rc(on_focus),
+            ("on_key_up", AttrGroupValue::OnKeyUp(on_key_up)) => self.on_key_up_rc(on_key_up),
+            (_, AttrGroupValue::OnEvent(on_event)) => {
+                let event = key
+                    .strip_prefix("on:")
+                    .or_else(|| Self::dom_event_name(&key));
+
+                match event {
+                    Some(event) => self.on_event_rc(event, on_event),
+                    None => {
+                        log::error!("Unknown event handler {key}");
+                        self
+                    }
+                }
+            }
             ("on_submit", AttrGroupValue::OnSubmit(on_submit))
             | ("form", AttrGroupValue::OnSubmit(on_submit)) => self.on_submit_rc(on_submit),
             ("tw", AttrGroupValue::AttrValue(value)) => self.attr("class", value),
             (_, AttrGroupValue::AttrValue(value)) => self.attr(key, value),
             (_, _) => {
-                crate::log::error!("Invalid attribute type for key {key}");
+                log::error!("Invalid attribute type for key {key}");
                 self
             }
         }
@@ -288,20 +329,7 @@ impl DomElement {
         let on_change_file = self.install_callback1(on_change_file);
 
         self.add_event_listener("change_file", move |data| {
-            let params = data.map_list(|mut params: JsJsonListDecoder| {
-                let files = params.get_vec("change file", |item: JsJson| {
-                    item.map_list(|mut item: JsJsonListDecoder| {
-                        let name = item.get_string("name")?;
-                        let data = item.get_buffer("data")?;
-
-                        Ok(DropFileItem::new(name, data))
-                    })
-                })?;
-
-                Ok(DropFileEvent::new(files))
-            });
-
-            match params {
+            match get_drop_file_event(data) {
                 Ok(params) => {
                     on_change_file(params);
                 }
@@ -321,8 +349,12 @@ impl DomElement {
     pub fn on_click_rc(self, on_click: Rc<Callback1<ClickEvent, ()>>) -> Self {
         let on_click = self.install_callback1(on_click);
 
-        self.add_event_listener("click", move |_data| {
-            let click_event = ClickEvent::default();
+        self.add_event_listener("click", move |data| {
+            let mouse = get_mouse_event(data)
+                .inspect_err(|error| log::error!("on_click -> params decode error -> {error}"))
+                .unwrap_or_default();
+
+            let click_event = ClickEvent::new(mouse);
             on_click(click_event.clone());
             JsJson::from(click_event)
         })
@@ -336,20 +368,7 @@ impl DomElement {
         let on_dropfile = self.install_callback1(on_dropfile);
 
         self.add_event_listener("drop", move |data| {
-            let params = data.map_list(|mut params: JsJsonListDecoder| {
-                let files = params.get_vec("drop file", |item: JsJson| {
-                    item.map_list(|mut item: JsJsonListDecoder| {
-                        let name = item.get_string("name")?;
-                        let data = item.get_buffer("data")?;
-
-                        Ok(DropFileItem::new(name, data))
-                    })
-                })?;
-
-                Ok(DropFileEvent::new(files))
-            });
-
-            match params {
+            match get_drop_file_event(data) {
                 Ok(params) => {
                     on_dropfile(params);
                 }
@@ -362,6 +381,92 @@ impl DomElement {
         })
     }
 
+    /// Handle any DOM event by its name (i.e. `"scroll"`, `"dragover"`, `"my-custom-event"`).
+    ///
+    /// Callback receives [DomEvent] with event data, and can call
+    /// [prevent_default](DomEvent::prevent_default) or [stop_propagation](DomEvent::stop_propagation).
+    ///
+    /// In [dom!](crate::dom!) macro it's available as `on:<event name>` attribute.
+    ///
+    /// ```rust
+    /// use vertigo::{dom, DomEvent, Value};
+    ///
+    /// let scroll_top = Value::new(0.0);
+    ///
+    /// let on_scroll = {
+    ///     let scroll_top = scroll_top.clone();
+    ///     move |event: DomEvent| {
+    ///         if let Some(top) = event.get_number("scrollTop") {
+    ///             scroll_top.set(top);
+    ///         }
+    ///     }
+    /// };
+    ///
+    /// let on_drag_over = |event: DomEvent| event.prevent_default();
+    ///
+    /// dom! {
+    ///     <div on:scroll={on_scroll} on:dragover={on_drag_over}>"Content"</div>
+    /// };
+    /// ```
+    pub fn on_event(
+        self,
+        name: impl Into<String>,
+        on_event: impl Into<Callback1<DomEvent, ()>>,
+    ) -> Self {
+        self.on_event_rc(name, Rc::new(on_event.into()))
+    }
+
+    pub fn on_event_rc(
+        self,
+        name: impl Into<String>,
+        on_event: Rc<Callback1<DomEvent, ()>>,
+    ) -> Self {
+        let on_event = self.install_callback1(on_event);
+
+        self.add_event_listener(format!("event:{}", name.into()), move |data| {
+            let event = DomEvent::new(data);
+            on_event(event.clone());
+            JsJson::from(event)
+        })
+    }
+
+    dom_event_handlers!(
+        (on_contextmenu, on_contextmenu_rc, "contextmenu"),
+        (on_dblclick, on_dblclick_rc, "dblclick"),
+        (on_drag, on_drag_rc, "drag"),
+        (on_drag_end, on_drag_end_rc, "dragend"),
+        (on_drag_enter, on_drag_enter_rc, "dragenter"),
+        (on_drag_leave, on_drag_leave_rc, "dragleave"),
+        (on_drag_over, on_drag_over_rc, "dragover"),
+        (on_drag_start, on_drag_start_rc, "dragstart"),
+        (on_drop, on_drop_rc, "drop"),
+        (on_pointer_cancel, on_pointer_cancel_rc, "pointercancel"),
+        (on_pointer_down, on_pointer_down_rc, "pointerdown"),
+        (on_pointer_enter, on_pointer_enter_rc, "pointerenter"),
+        (on_pointer_leave, on_pointer_leave_rc, "pointerleave"),
+        (on_pointer_move, on_pointer_move_rc, "pointermove"),
+        (on_pointer_up, on_pointer_up_rc, "pointerup"),
+        (on_scroll, on_scroll_rc, "scroll"),
+        (on_touch_cancel, on_touch_cancel_rc, "touchcancel"),
+        (on_touch_end, on_touch_end_rc, "touchend"),
+        (on_touch_move, on_touch_move_rc, "touchmove"),
+        (on_touch_start, on_touch_start_rc, "touchstart"),
+        (on_wheel, on_wheel_rc, "wheel"),
+    );
+
+    pub fn on_focus(self, on_focus: impl Into<Callback<()>>) -> Self {
+        self.on_focus_rc(Rc::new(on_focus.into()))
+    }
+
+    pub fn on_focus_rc(self, on_focus: Rc<Callback<()>>) -> Self {
+        let on_focus = self.install_callback(on_focus);
+
+        self.add_event_listener("focus", move |_data| {
+            on_focus();
+            JsJson::Null
+        })
+    }
+
     pub fn on_input(self, on_input: impl Into<Callback1<String, ()>>) -> Self {
         self.on_input_rc(Rc::new(on_input.into()))
     }
@@ -428,6 +533,29 @@ impl DomElement {
         })
     }
 
+    pub fn on_key_up(self, on_key_up: impl Into<Callback1<KeyUpEvent, bool>>) -> Self {
+        self.on_key_up_rc(Rc::new(on_key_up.into()))
+    }
+
+    pub fn on_key_up_rc(self, on_key_up: Rc<Callback1<KeyUpEvent, bool>>) -> Self {
+        let on_key_up = self.install_callback1(on_key_up);
+
+        self.add_event_listener("keyup", move |data| match get_key_up_event(data) {
+            Ok(event) => {
+                let prevent_default = on_key_up(event);
+
+                match prevent_default {
+                    true => JsJson::True,
+                    false => JsJson::False,
+                }
+            }
+            Err(error) => {
+                log::error!("on_key_up -> params decode error -> {error}");
+                JsJson::False
+            }
+        })
+    }
+
     pub fn on_load(self, on_load: impl Into<Callback<()>>) -> Self {
         self.on_load_rc(Rc::new(on_load.into()))
     }
@@ -441,59 +569,87 @@ impl DomElement {
         })
     }
 
-    pub fn on_mouse_down(self, on_mouse_down: impl Into<Callback<bool>>) -> Self {
+    pub fn on_mouse_down(self, on_mouse_down: impl Into<Callback1<MouseEvent, bool>>) -> Self {
         self.on_mouse_down_rc(Rc::new(on_mouse_down.into()))
     }
 
-    pub fn on_mouse_down_rc(self, on_mouse_down: Rc<Callback<bool>>) -> Self {
-        let on_mouse_down = self.install_callback(on_mouse_down);
+    pub fn on_mouse_down_rc(self, on_mouse_down: Rc<Callback1<MouseEvent, bool>>) -> Self {
+        let on_mouse_down = self.install_callback1(on_mouse_down);
 
-        self.add_event_listener("mousedown", move |_data| {
-            if on_mouse_down() {
-                JsJson::True
-            } else {
+        self.add_event_listener("mousedown", move |data| match get_mouse_event(data) {
+            Ok(event) => {
+                if on_mouse_down(event) {
+                    JsJson::True
+                } else {
+                    JsJson::False
+                }
+            }
+            Err(error) => {
+                log::error!("on_mouse_down -> params decode error -> {error}");
                 JsJson::False
             }
         })
     }
 
-    pub fn on_mouse_enter(self, on_mouse_enter: impl Into<Callback<()>>) -> Self {
+    pub fn on_mouse_enter(self, on_mouse_enter: impl Into<Callback1<MouseEvent, ()>>) -> Self {
         self.on_mouse_enter_rc(Rc::new(on_mouse_enter.into()))
     }
 
-    pub fn on_mouse_enter_rc(self, on_mouse_enter: Rc<Callback<()>>) -> Self {
-        let on_mouse_enter = self.install_callback(on_mouse_enter);
+    pub fn on_mouse_enter_rc(self, on_mouse_enter: Rc<Callback1<MouseEvent, ()>>) -> Self {
+        let on_mouse_enter = self.install_callback1(on_mouse_enter);
+
+        self.add_event_listener("mouseenter", move |data| {
+            match get_mouse_event(data) {
+                Ok(event) => {
+                    on_mouse_enter(event);
+                }
+                Err(error) => {
+                    log::error!("on_mouse_enter -> params decode error -> {error}");
+                }
+            }
 
-        self.add_event_listener("mouseenter", move |_data| {
-            on_mouse_enter();
             JsJson::Null
         })
     }
 
-    pub fn on_mouse_leave(self, on_mouse_leave: impl Into<Callback<()>>) -> Self {
+    pub fn on_mouse_leave(self, on_mouse_leave: impl Into<Callback1<MouseEvent, ()>>) -> Self {
         self.on_mouse_leave_rc(Rc::new(on_mouse_leave.into()))
     }
 
-    pub fn on_mouse_leave_rc(self, on_mouse_leave: Rc<Callback<()>>) -> Self {
-        let on_mouse_leave = self.install_callback(on_mouse_leave);
+    pub fn on_mouse_leave_rc(self, on_mouse_leave: Rc<Callback1<MouseEvent, ()>>) -> Self {
+        let on_mouse_leave = self.install_callback1(on_mouse_leave);
+
+        self.add_event_listener("mouseleave", move |data| {
+            match get_mouse_event(data) {
+                Ok(event) => {
+                    on_mouse_leave(event);
+                }
+                Err(error) => {
+                    log::error!("on_mouse_leave -> params decode error -> {error}");
+                }
+            }
 
-        self.add_event_listener("mouseleave", move |_data| {
-            on_mouse_leave();
             JsJson::Null
         })
     }
 
-    pub fn on_mouse_up(self, on_mouse_up: impl Into<Callback<bool>>) -> Self {
+    pub fn on_mouse_up(self, on_mouse_up: impl Into<Callback1<MouseEvent, bool>>) -> Self {
         self.on_mouse_up_rc(Rc::new(on_mouse_up.into()))
     }
 
-    pub fn on_mouse_up_rc(self, on_mouse_up: Rc<Callback<bool>>) -> Self {
-        let on_mouse_up = self.install_callback(on_mouse_up);
+    pub fn on_mouse_up_rc(self, on_mouse_up: Rc<Callback1<MouseEvent, bool>>) -> Self {
+        let on_mouse_up = self.install_callback1(on_mouse_up);
 
-        self.add_event_listener("mouseup", move |_data| {
-            if on_mouse_up() {
-                JsJson::True
-            } else {
+        self.add_event_listener("mouseup", move |data| match get_mouse_event(data) {
+            Ok(event) => {
+                if on_mouse_up(event) {
+                    JsJson::True
+                } else {
+                    JsJson::False
+                }
+            }
+            Err(error) => {
+                log::error!("on_mouse_up -> params decode error -> {error}");
                 JsJson::False
             }
         })
@@ -527,17 +683,19 @@ impl DomElement {
 
     fn add_event_listener(
         self,
-        name: &'static str,
+        name: impl Into<String>,
         callback: impl Fn(JsJson) -> JsJson + 'static,
     ) -> Self {
+        let name = name.into();
         let (callback_id, drop) = api_callbacks().register(callback);
 
+        get_driver_dom().callback_add(self.id_dom, name.clone(), callback_id);
+
         let drop_event = DropResource::new(move || {
             get_driver_dom().callback_remove(self.id_dom, name, callback_id);
             drop.off();
         });
 
-        get_driver_dom().callback_add(self.id_dom, name, callback_id);
         self.subscriptions.push(drop_event);
         self
     }
@@ -610,3 +768,112 @@ fn get_key_down_event(data: JsJson) -> Result<KeyDownEvent, String> {
         })
     })
 }
+
+fn get_key_up_event(data: JsJson) -> Result<KeyUpEvent, String> {
+    data.map_list(|mut params: JsJsonListDecoder| {
+        let key = params.get_string("key")?;
+        let code = params.get_string("code")?;
+        let alt_key = params.get_bool("altKey")?;
+        let ctrl_key = params.get_bool("ctrlKey")?;
+        let shift_key = params.get_bool("shiftKey")?;
+        let meta_key = params.get_bool("metaKey")?;
+        params.expect_no_more()?;
+
+        Ok(KeyUpEvent {
+            key,
+            code,
+            alt_key,
+            ctrl_key,
+            shift_key,
+            meta_key,
+        })
+    })
+}
+
+fn get_mouse_event(data: JsJson) -> Result<MouseEvent, String> {
+    data.map_list(|mut params: JsJsonListDecoder| {
+        let client_x = params.get_f64("clientX")?;
+        let client_y = params.get_f64("clientY")?;
+        let page_x = params.get_f64("pageX")?;
+        let page_y = params.get_f64("pageY")?;
+        let offset_x = params.get_f64("offsetX")?;
+        let offset_y = params.get_f64("offsetY")?;
+        let button = params.get_f64("button")? as i16;
+        let buttons = params.get_u32("buttons")? as u16;
+        let alt_key = params.get_bool("altKey")?;
+        let ctrl_key = params.get_bool("ctrlKey")?;
+        let shift_key = params.get_bool("shiftKey")?;
+        let meta_key = params.get_bool("metaKey")?;
+        params.expect_no_more()?;
+
+        Ok(MouseEvent {
+            client_x,
+            client_y,
+            page_x,
+            page_y,
+            offset_x,
+            offset_y,
+            button,
+            buttons,
+            alt_key,
+            ctrl_key,
+            shift_key,
+            meta_key,
+        })
+    })
+}
+
+fn get_drop_file_event(data: JsJson) -> Result<DropFileEvent, String> {
+    data.map_list(|mut params: JsJsonListDecoder| {
+        let files = params.get_vec("files", |item: JsJson| {
+            item.map_list(|mut item: JsJsonListDecoder| {
+                let name = item.get_string("name")?;
+                let size = item.get_u64("size")?;
+                let mime_type = item.get_string("type")?;
+                let last_modified = item.get_u64("lastModified")?;
+                let file_id = item.get_u64("id")?;
+                item.expect_no_more()?;
+
+                Ok(DropFileItem::new(
+                    name,
+                    size,
+                    mime_type,
+                    last_modified,
+                    file_id,
+                ))
+            })
+        })?;
+
+        Ok(DropFileEvent::new(files))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::JsJson;
+
+    use super::get_key_up_event;
+
+    #[test]
+    fn test_key_up_event() {
+        let data = JsJson::List(vec![
+            JsJson::String("a".into()),
+            JsJson::String("KeyA".into()),
+            JsJson::False,
+            JsJson::True,
+            JsJson::False,
+            JsJson::False,
+        ]);
+
+        let Ok(event) = get_key_up_event(data) else {
+            panic!("KeyUpEvent expected");
+        };
+
+        assert_eq!(event.key, "a");
+        assert_eq!(event.code, "KeyA");
+        assert!(event.ctrl_key);
+        assert!(!event.alt_key && !event.shift_key && !event.meta_key);
+
+        assert!(get_key_up_event(JsJson::List(vec![JsJson::String("a".into())])).is_err());
+    }
+}
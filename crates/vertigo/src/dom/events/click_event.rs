@@ -2,9 +2,12 @@ use std::rc::Rc;
 
 use crate::{JsJson, computed::struct_mut::ValueMut};
 
-/// Structure passed as a parameter to callback on on_key_down event.
+use super::MouseEvent;
+
+/// Structure passed as a parameter to callback on on_click event.
 #[derive(Clone, Debug, Default)]
 pub struct ClickEvent {
+    mouse: Rc<MouseEvent>,
     inner: Rc<ValueMut<ClickEventInner>>,
 }
 
@@ -15,6 +18,18 @@ pub struct ClickEventInner {
 }
 
 impl ClickEvent {
+    pub fn new(mouse: MouseEvent) -> Self {
+        Self {
+            mouse: Rc::new(mouse),
+            inner: Default::default(),
+        }
+    }
+
+    /// Position, buttons and modifier keys of the click
+    pub fn mouse(&self) -> &MouseEvent {
+        &self.mouse
+    }
+
     pub fn stop_propagation(&self) {
         self.inner.change(|inner| inner.stop_propagation = true);
     }
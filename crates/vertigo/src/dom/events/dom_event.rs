@@ -0,0 +1,123 @@
+use std::rc::Rc;
+
+use crate::{JsJson, computed::struct_mut::ValueMut};
+
+use super::MouseEvent;
+
+/// Structure passed as a parameter to callback on generic DOM events
+/// (see [DomElement::on_event](crate::DomElement::on_event)).
+///
+/// Data contains primitive properties of JS event object (i.e. `type`, `clientX`, `deltaY`, `key`),
+/// `touches` list for touch events, and `value`, `scrollTop` and `scrollLeft`
+/// of the element the handler is attached to.
+#[derive(Clone, Debug)]
+pub struct DomEvent {
+    data: Rc<JsJson>,
+    inner: Rc<ValueMut<DomEventInner>>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct DomEventInner {
+    stop_propagation: bool,
+    prevent_default: bool,
+}
+
+impl DomEvent {
+    pub fn new(data: JsJson) -> Self {
+        Self {
+            data: Rc::new(data),
+            inner: Rc::new(ValueMut::new(DomEventInner::default())),
+        }
+    }
+
+    /// Raw event data
+    pub fn data(&self) -> &JsJson {
+        &self.data
+    }
+
+    /// Get property of the event
+    pub fn get(&self, name: &str) -> Option<&JsJson> {
+        match self.data.as_ref() {
+            JsJson::Object(object) => object.get(name),
+            _ => None,
+        }
+    }
+
+    pub fn get_number(&self, name: &str) -> Option<f64> {
+        match self.get(name) {
+            Some(JsJson::Number(value)) => Some(value.0),
+            _ => None,
+        }
+    }
+
+    pub fn get_string(&self, name: &str) -> Option<&str> {
+        match self.get(name) {
+            Some(JsJson::String(value)) => Some(value.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.get(name) {
+            Some(JsJson::True) => Some(true),
+            Some(JsJson::False) => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Mouse data of the event, available for mouse, pointer, wheel and drag events
+    pub fn mouse(&self) -> Option<MouseEvent> {
+        Some(MouseEvent {
+            client_x: self.get_number("clientX")?,
+            client_y: self.get_number("clientY")?,
+            page_x: self.get_number("pageX")?,
+            page_y: self.get_number("pageY")?,
+            offset_x: self.get_number("offsetX")?,
+            offset_y: self.get_number("offsetY")?,
+            button: self.get_number("button")? as i16,
+            buttons: self.get_number("buttons")? as u16,
+            alt_key: self.get_bool("altKey")?,
+            ctrl_key: self.get_bool("ctrlKey")?,
+            shift_key: self.get_bool("shiftKey")?,
+            meta_key: self.get_bool("metaKey")?,
+        })
+    }
+
+    pub fn stop_propagation(&self) {
+        self.inner.change(|inner| inner.stop_propagation = true);
+    }
+
+    pub fn prevent_default(&self) {
+        self.inner.change(|inner| inner.prevent_default = true);
+    }
+}
+
+impl From<DomEvent> for JsJson {
+    fn from(val: DomEvent) -> JsJson {
+        let inner = val.inner.get();
+        JsJson::Object(
+            [
+                (
+                    "stop_propagation".to_string(),
+                    JsJson::from(inner.stop_propagation),
+                ),
+                (
+                    "prevent_default".to_string(),
+                    JsJson::from(inner.prevent_default),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    }
+}
+
+impl std::fmt::Display for DomEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DomEvent={}",
+            self.get_string("type").unwrap_or_default()
+        )
+    }
+}
@@ -1,18 +1,135 @@
 use std::rc::Rc;
 
+use crate::{
+    InstantType, JsJson,
+    driver_module::api::{api_browser_command, api_js_promise},
+};
+
+/// File kept on JavaScript side, released when the last [DropFileItem] referencing it is dropped
+#[derive(Debug, PartialEq, Eq)]
+struct FileHandle {
+    id: u64,
+}
+
+impl Drop for FileHandle {
+    fn drop(&mut self) {
+        api_browser_command().file_release(self.id);
+    }
+}
+
+/// File dropped onto the element (`on_dropfile`) or selected in file input (`on_change_file`).
+///
+/// Content is not copied into wasm memory until requested by [read](DropFileItem::read),
+/// [read_range](DropFileItem::read_range) or [chunks](DropFileItem::chunks).
+/// The file can be also sent as a request body without copying (see [RequestBuilder::body_file](crate::RequestBuilder::body_file)).
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DropFileItem {
     pub name: String,
-    pub data: Rc<Vec<u8>>,
+    /// Size in bytes
+    pub size: u64,
+    /// MIME type reported by the browser (empty if unknown)
+    pub mime_type: String,
+    /// Last modification time (milliseconds since UNIX epoch)
+    pub last_modified: InstantType,
+    file: Rc<FileHandle>,
 }
 
 impl DropFileItem {
-    pub fn new(name: String, data: Vec<u8>) -> DropFileItem {
+    pub(crate) fn new(
+        name: String,
+        size: u64,
+        mime_type: String,
+        last_modified: InstantType,
+        file_id: u64,
+    ) -> DropFileItem {
         DropFileItem {
             name,
-            data: Rc::new(data),
+            size,
+            mime_type,
+            last_modified,
+            file: Rc::new(FileHandle { id: file_id }),
         }
     }
+
+    pub(crate) fn file_id(&self) -> u64 {
+        self.file.id
+    }
+
+    /// Read bytes from `start` (inclusive) to `end` (exclusive)
+    pub async fn read_range(&self, start: u64, end: u64) -> Result<Vec<u8>, String> {
+        let file = self.file.id;
+
+        let result = api_js_promise()
+            .wait(|callback| {
+                api_browser_command().file_read(file, start, end.min(self.size), callback);
+            })
+            .await;
+
+        match result {
+            Ok(JsJson::Vec(data)) => Ok(data),
+            Ok(other) => Err(format!("Unexpected file content: {}", other.typename())),
+            Err(JsJson::String(message)) => Err(message),
+            Err(other) => Err(format!("File read error: {other:?}")),
+        }
+    }
+
+    /// Read whole file
+    pub async fn read(&self) -> Result<Vec<u8>, String> {
+        self.read_range(0, self.size).await
+    }
+
+    /// Read the file in consecutive chunks of given size
+    ///
+    /// ```rust
+    /// use vertigo::DropFileItem;
+    ///
+    /// async fn checksum(file: DropFileItem) -> Result<u32, String> {
+    ///     let mut sum = 0u32;
+    ///     let mut chunks = file.chunks(1024 * 1024);
+    ///
+    ///     while let Some(chunk) = chunks.next_chunk().await {
+    ///         for byte in chunk? {
+    ///             sum = sum.wrapping_add(byte as u32);
+    ///         }
+    ///     }
+    ///
+    ///     Ok(sum)
+    /// }
+    /// ```
+    pub fn chunks(&self, chunk_size: u64) -> FileChunks {
+        FileChunks {
+            file: self.clone(),
+            position: 0,
+            chunk_size: chunk_size.max(1),
+        }
+    }
+}
+
+/// Chunked reader of [DropFileItem]
+pub struct FileChunks {
+    file: DropFileItem,
+    position: u64,
+    chunk_size: u64,
+}
+
+impl FileChunks {
+    /// Read next chunk, `None` when the whole file has been read
+    pub async fn next_chunk(&mut self) -> Option<Result<Vec<u8>, String>> {
+        if self.position >= self.file.size {
+            return None;
+        }
+
+        let end = (self.position + self.chunk_size).min(self.file.size);
+        let chunk = self.file.read_range(self.position, end).await;
+        self.position = end;
+
+        Some(chunk)
+    }
+
+    /// Number of bytes read so far
+    pub fn position(&self) -> u64 {
+        self.position
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -25,3 +142,123 @@ impl DropFileEvent {
         DropFileEvent { items }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::{
+        JsJson, computed::struct_mut::ValueMut, dev::command::CommandForBrowser,
+        driver_module::api::api_js_promise, external_api::safe_wrappers::set_command_mock,
+    };
+
+    use super::DropFileItem;
+
+    const CONTENT: &[u8] = b"Hello world";
+
+    /// Commands received by the browser
+    struct MockBrowser {
+        reads: Rc<ValueMut<Vec<(u64, u64)>>>,
+        releases: Rc<ValueMut<Vec<u64>>>,
+    }
+
+    /// Browser serving [CONTENT] as file 7
+    fn mock_browser() -> MockBrowser {
+        let reads = Rc::new(ValueMut::new(Vec::new()));
+        let releases = Rc::new(ValueMut::new(Vec::new()));
+
+        set_command_mock({
+            let reads = reads.clone();
+            let releases = releases.clone();
+
+            move |command| {
+                match command {
+                    CommandForBrowser::FileRead {
+                        file: 7,
+                        start,
+                        end,
+                        callback,
+                    } => {
+                        reads.change(|reads| reads.push((*start, *end)));
+                        let data = CONTENT[*start as usize..*end as usize].to_vec();
+                        api_js_promise().callback(*callback, Ok(JsJson::Vec(data)));
+                    }
+                    CommandForBrowser::FileRead { callback, .. } => {
+                        let error = JsJson::String("NotFoundError".into());
+                        api_js_promise().callback(*callback, Err(error));
+                    }
+                    CommandForBrowser::FileRelease { file } => {
+                        releases.change(|releases| releases.push(*file));
+                    }
+                    _ => {}
+                }
+                Some(JsJson::Null)
+            }
+        });
+
+        MockBrowser { reads, releases }
+    }
+
+    fn dropped_file(id: u64) -> DropFileItem {
+        DropFileItem::new(
+            "hello.txt".into(),
+            CONTENT.len() as u64,
+            "text/plain".into(),
+            0,
+            id,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_read() {
+        let MockBrowser { reads, .. } = mock_browser();
+        let file = dropped_file(7);
+
+        assert_eq!(file.read().await, Ok(CONTENT.to_vec()));
+        assert_eq!(file.read_range(6, 11).await, Ok(b"world".to_vec()));
+
+        // Range is clamped to the size of the file
+        assert_eq!(file.read_range(6, 100).await, Ok(b"world".to_vec()));
+        assert_eq!(reads.get(), vec![(0, 11), (6, 11), (6, 11)]);
+
+        assert_eq!(
+            dropped_file(8).read().await,
+            Err("NotFoundError".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chunks() {
+        let MockBrowser { reads, .. } = mock_browser();
+        let file = dropped_file(7);
+
+        let mut chunks = file.chunks(4);
+        let mut content = Vec::new();
+
+        while let Some(chunk) = chunks.next_chunk().await {
+            let Ok(chunk) = chunk else {
+                panic!("Chunk expected");
+            };
+            content.extend(chunk);
+        }
+
+        assert_eq!(content, CONTENT);
+        assert_eq!(chunks.position(), CONTENT.len() as u64);
+        assert_eq!(reads.get(), vec![(0, 4), (4, 8), (8, 11)]);
+    }
+
+    #[test]
+    fn test_release_on_drop() {
+        let MockBrowser { releases, .. } = mock_browser();
+
+        let file = dropped_file(7);
+        let copy = file.clone();
+
+        drop(file);
+        assert!(releases.get().is_empty());
+
+        // Released when the last copy is dropped
+        drop(copy);
+        assert_eq!(releases.get(), vec![7]);
+    }
+}
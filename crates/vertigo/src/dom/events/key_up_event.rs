@@ -0,0 +1,16 @@
+/// Structure passed as a parameter to callback on on_key_up event.
+#[derive(Clone, Debug)]
+pub struct KeyUpEvent {
+    pub key: String,
+    pub code: String,
+    pub alt_key: bool,
+    pub ctrl_key: bool,
+    pub shift_key: bool,
+    pub meta_key: bool,
+}
+
+impl std::fmt::Display for KeyUpEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "KeyUpEvent={}", self.key)
+    }
+}
@@ -9,3 +9,12 @@ pub use intersect_event::*;
 
 mod key_down_event;
 pub use key_down_event::*;
+
+mod key_up_event;
+pub use key_up_event::*;
+
+mod dom_event;
+pub use dom_event::*;
+
+mod mouse_event;
+pub use mouse_event::*;
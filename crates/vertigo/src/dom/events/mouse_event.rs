@@ -0,0 +1,26 @@
+/// Structure passed as a parameter to callback on mouse events (`on_click`, `on_mouse_down`, etc.).
+///
+/// Coordinates are in CSS pixels, `offset_*` are relative to the target element.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MouseEvent {
+    pub client_x: f64,
+    pub client_y: f64,
+    pub page_x: f64,
+    pub page_y: f64,
+    pub offset_x: f64,
+    pub offset_y: f64,
+    /// Button which changed state (`0` - main, `1` - auxiliary, `2` - secondary)
+    pub button: i16,
+    /// Bitmask of buttons pressed (`1` - main, `2` - secondary, `4` - auxiliary)
+    pub buttons: u16,
+    pub alt_key: bool,
+    pub ctrl_key: bool,
+    pub shift_key: bool,
+    pub meta_key: bool,
+}
+
+impl std::fmt::Display for MouseEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MouseEvent={},{}", self.client_x, self.client_y)
+    }
+}
@@ -1,7 +1,8 @@
 use std::{collections::BTreeMap, rc::Rc};
 
 use crate::{
-    Computed, Css, DomComment, DomElement, DomText, DropFileEvent, KeyDownEvent, Value,
+    Computed, Css, DomComment, DomElement, DomEvent, DomText, DropFileEvent, KeyDownEvent,
+    KeyUpEvent, MouseEvent, Value,
     dom::{
         attr_value::AttrValue,
         callback::{Callback, Callback1},
@@ -50,16 +51,20 @@ pub enum AttrGroupValue {
     OnBlur(Rc<Callback<()>>),
     OnChange(Rc<Callback1<String, ()>>),
     OnClick(Rc<Callback1<ClickEvent, ()>>),
+    /// Handler of event passing [DomEvent] (`on:<event name>`, `on_scroll`, `on_drag_over`, etc.)
+    OnEvent(Rc<Callback1<DomEvent, ()>>),
+    OnFocus(Rc<Callback<()>>),
     OnDropfile(Rc<Callback1<DropFileEvent, ()>>),
     OnChangeFile(Rc<Callback1<DropFileEvent, ()>>),
     OnInput(Rc<Callback1<String, ()>>),
     OnIntersect(Rc<Callback1<IntersectionEvent, ()>>),
     OnKeyDown(Rc<Callback1<KeyDownEvent, bool>>),
+    OnKeyUp(Rc<Callback1<KeyUpEvent, bool>>),
     OnLoad(Rc<Callback<()>>),
-    OnMouseDown(Rc<Callback<bool>>),
-    OnMouseEnter(Rc<Callback<()>>),
-    OnMouseLeave(Rc<Callback<()>>),
-    OnMouseUp(Rc<Callback<bool>>),
+    OnMouseDown(Rc<Callback1<MouseEvent, bool>>),
+    OnMouseEnter(Rc<Callback1<MouseEvent, ()>>),
+    OnMouseLeave(Rc<Callback1<MouseEvent, ()>>),
+    OnMouseUp(Rc<Callback1<MouseEvent, bool>>),
     OnSubmit(Rc<Callback<()>>),
 }
 
@@ -89,16 +94,19 @@ impl AttrGroupValue {
     group_value_constructor!(on_blur, Callback<()>, OnBlur);
     group_value_constructor!(on_change, Callback1<String, ()>, OnChange);
     group_value_constructor!(on_click, Callback1<ClickEvent, ()>, OnClick);
+    group_value_constructor!(on_event, Callback1<DomEvent, ()>, OnEvent);
+    group_value_constructor!(on_focus, Callback<()>, OnFocus);
     group_value_constructor!(on_dropfile, Callback1<DropFileEvent, ()>, OnDropfile);
     group_value_constructor!(on_change_file, Callback1<DropFileEvent, ()>, OnChangeFile);
     group_value_constructor!(on_input, Callback1<String, ()>, OnInput);
     group_value_constructor!(on_intersect, Callback1<IntersectionEvent, ()>, OnIntersect);
     group_value_constructor!(on_key_down, Callback1<KeyDownEvent, bool>, OnKeyDown);
+    group_value_constructor!(on_key_up, Callback1<KeyUpEvent, bool>, OnKeyUp);
     group_value_constructor!(on_load, Callback<()>, OnLoad);
-    group_value_constructor!(on_mouse_down, Callback<bool>, OnMouseDown);
-    group_value_constructor!(on_mouse_enter, Callback<()>, OnMouseEnter);
-    group_value_constructor!(on_mouse_leave, Callback<()>, OnMouseLeave);
-    group_value_constructor!(on_mouse_up, Callback<bool>, OnMouseUp);
+    group_value_constructor!(on_mouse_down, Callback1<MouseEvent, bool>, OnMouseDown);
+    group_value_constructor!(on_mouse_enter, Callback1<MouseEvent, ()>, OnMouseEnter);
+    group_value_constructor!(on_mouse_leave, Callback1<MouseEvent, ()>, OnMouseLeave);
+    group_value_constructor!(on_mouse_up, Callback1<MouseEvent, bool>, OnMouseUp);
     group_value_constructor!(on_submit, Callback<()>, OnSubmit);
 
     /// Extract [`Computed<String>`] from this [AttrGroupValue] if possible.
@@ -2,18 +2,19 @@ use std::rc::Rc;
 use vertigo_macro::store;
 
 use crate::{
-    InstantType, JsJson, JsJsonSerialize,
+    InstantType, JsJson, JsJsonSerialize, ServerResponse,
     dev::{
-        CallbackId, SsrFetchCache, SsrFetchRequest,
+        CallbackId, OfflineCacheEntry, SsrContext, SsrFetchCache, SsrFetchRequest,
         command::{
-            CommandForBrowser, ConsoleLogLevel, DriverDomCommand, LocationCallbackMode,
-            LocationSetMode, LocationTarget, TimerKind, browser_response, decode_json,
+            CommandForBrowser, ConsoleLogLevel, DriverDomCommand, JsApiCommand,
+            LocationCallbackMode, LocationSetMode, LocationTarget, StorageKind, TimerKind,
+            browser_response, decode_json,
         },
     },
     external_api::safe_wrappers,
 };
 
-use super::api_arguments;
+use super::{api_arguments, api_ssr_context};
 
 #[store]
 pub fn api_browser_command() -> Rc<CommandForBrowserApi> {
@@ -59,14 +60,59 @@ impl CommandForBrowserApi {
             .unwrap_or_else(SsrFetchCache::empty)
     }
 
-    pub fn fetch_exec(&self, request: SsrFetchRequest, callback: CallbackId) {
-        exec_command(CommandForBrowser::FetchExec { request, callback });
+    pub fn fetch_exec(&self, request: SsrFetchRequest, callback: CallbackId, progress: bool) {
+        exec_command(CommandForBrowser::FetchExec {
+            request,
+            callback,
+            progress,
+        });
+    }
+
+    pub fn fetch_abort(&self, callback: CallbackId) {
+        exec_command(CommandForBrowser::FetchAbort { callback });
+    }
+
+    pub fn fetch_stream(&self, request: SsrFetchRequest, callback: CallbackId) {
+        exec_command(CommandForBrowser::FetchStream { request, callback });
+    }
+
+    pub fn fetch_stream_abort(&self, callback: CallbackId) {
+        exec_command(CommandForBrowser::FetchStreamAbort { callback });
     }
 
     pub fn set_status(&self, status: u16) {
         exec_command(CommandForBrowser::SetStatus { status });
     }
 
+    pub fn set_response_header(&self, name: String, value: String) {
+        exec_command(CommandForBrowser::SetResponseHeader { name, value });
+    }
+
+    pub fn redirect(&self, url: String, status: u16) {
+        exec_command(CommandForBrowser::Redirect { url, status });
+    }
+
+    pub fn set_ssr_timeout(&self, time: u32) {
+        exec_command(CommandForBrowser::SetSsrTimeout { time });
+    }
+
+    pub fn ssr_time_left_get(&self) -> Option<u32> {
+        let response = exec_command(CommandForBrowser::SsrTimeLeftGet);
+
+        let response = decode_json::<browser_response::SsrTimeLeftGet>(response);
+        match response {
+            Ok(response) => response.value,
+            Err(err) => {
+                log::error!("ssr_time_left_get -> decode error = {err}");
+                None
+            }
+        }
+    }
+
+    pub fn server_handler_response(&self, response: ServerResponse) {
+        exec_command(CommandForBrowser::ServerHandlerResponse { response });
+    }
+
     pub fn is_browser(&self) -> bool {
         let response = exec_command(CommandForBrowser::IsBrowser);
         let response = decode_json::<browser_response::IsBrowser>(response);
@@ -79,6 +125,26 @@ impl CommandForBrowserApi {
         }
     }
 
+    pub fn ssr_context_get(&self) -> Option<SsrContext> {
+        let response = exec_command(CommandForBrowser::SsrContextGet);
+
+        let response = decode_json::<browser_response::SsrContextGet>(response);
+        match response {
+            Ok(response) => response.value,
+            Err(err) => {
+                log::error!("ssr_context_get -> decode error = {err}");
+                None
+            }
+        }
+    }
+
+    /// Current UTC timestamp, replaying the one from SSR during the first render
+    pub fn utc_now(&self) -> InstantType {
+        api_ssr_context()
+            .now()
+            .unwrap_or_else(|| self.get_date_now())
+    }
+
     pub fn get_date_now(&self) -> InstantType {
         let response = exec_command(CommandForBrowser::GetDateNow);
         let response = decode_json::<browser_response::GetDateNow>(response);
@@ -198,6 +264,52 @@ impl CommandForBrowserApi {
         }
     }
 
+    /// Get value stored under the key, `None` if the key is missing
+    pub fn storage_get(&self, kind: StorageKind, key: String) -> Option<JsJson> {
+        let response = exec_command(CommandForBrowser::StorageGet { kind, key });
+
+        let response = decode_json::<browser_response::StorageGet>(response);
+        match response {
+            Ok(response) => response.into_value(),
+            Err(err) => {
+                log::error!("storage_get -> decode error = {err}");
+                None
+            }
+        }
+    }
+
+    pub fn storage_set(&self, kind: StorageKind, key: String, value: JsJson) {
+        exec_command(CommandForBrowser::StorageSet { kind, key, value });
+    }
+
+    pub fn storage_remove(&self, kind: StorageKind, key: String) {
+        exec_command(CommandForBrowser::StorageRemove { kind, key });
+    }
+
+    pub fn storage_watch(&self, kind: StorageKind, key: String, callback: CallbackId) {
+        exec_command(CommandForBrowser::StorageWatch {
+            kind,
+            key,
+            callback,
+        });
+    }
+
+    pub fn storage_unwatch(&self, callback: CallbackId) {
+        exec_command(CommandForBrowser::StorageUnwatch { callback });
+    }
+
+    pub fn offline_cache_get(&self, request: SsrFetchRequest, callback: CallbackId) {
+        exec_command(CommandForBrowser::OfflineCacheGet { request, callback });
+    }
+
+    pub fn offline_cache_set(&self, request: SsrFetchRequest, entry: OfflineCacheEntry) {
+        exec_command(CommandForBrowser::OfflineCacheSet { request, entry });
+    }
+
+    pub fn offline_cache_clear(&self) {
+        exec_command(CommandForBrowser::OfflineCacheClear);
+    }
+
     pub fn get_env(&self, name: impl Into<String>) -> Option<String> {
         let response = exec_command(CommandForBrowser::GetEnv { name: name.into() });
 
@@ -211,6 +323,45 @@ impl CommandForBrowserApi {
         }
     }
 
+    pub fn request_header_get(&self, name: impl Into<String>) -> Option<String> {
+        let response = exec_command(CommandForBrowser::RequestHeaderGet { name: name.into() });
+
+        let response = decode_json::<browser_response::RequestHeaderGet>(response);
+        match response {
+            Ok(response) => response.value,
+            Err(err) => {
+                log::error!("request_header_get -> decode error = {err}");
+                None
+            }
+        }
+    }
+
+    pub fn request_method_get(&self) -> Option<String> {
+        let response = exec_command(CommandForBrowser::RequestMethodGet);
+
+        let response = decode_json::<browser_response::RequestMethodGet>(response);
+        match response {
+            Ok(response) => response.value,
+            Err(err) => {
+                log::error!("request_method_get -> decode error = {err}");
+                None
+            }
+        }
+    }
+
+    pub fn request_body_get(&self) -> Option<Vec<u8>> {
+        let response = exec_command(CommandForBrowser::RequestBodyGet);
+
+        let response = decode_json::<browser_response::RequestBodyGet>(response);
+        match response {
+            Ok(response) => response.value,
+            Err(err) => {
+                log::error!("request_body_get -> decode error = {err}");
+                None
+            }
+        }
+    }
+
     pub fn console_log(
         &self,
         kind: ConsoleLogLevel,
@@ -229,15 +380,22 @@ impl CommandForBrowserApi {
     }
 
     pub fn timezone_offset(&self) -> i32 {
+        let value = api_ssr_context()
+            .timezone_offset()
+            .unwrap_or_else(|| self.timezone_offset_js());
+
+        // Return in seconds to be compatible with chrono
+        // Opposite as JS returns the offset backwards
+        value * -60
+    }
+
+    /// Timezone offset in minutes as returned by JS
+    pub fn timezone_offset_js(&self) -> i32 {
         let response = exec_command(CommandForBrowser::TimezoneOffset);
 
         let response = decode_json::<browser_response::TimezoneOffset>(response);
         match response {
-            Ok(response) => {
-                // Return in seconds to be compatible with chrono
-                // Opposite as JS returns the offset backwards
-                response.value * -60
-            }
+            Ok(response) => response.value,
             Err(err) => {
                 log::error!("api.timezone_offset -> incorrect result = {err}");
 
@@ -250,7 +408,28 @@ impl CommandForBrowserApi {
         exec_command(CommandForBrowser::HistoryBack);
     }
 
+    pub fn js_api_call_async(&self, commands: Vec<JsApiCommand>, callback: CallbackId) {
+        exec_command(CommandForBrowser::JsApiCallAsync { commands, callback });
+    }
+
+    pub fn file_read(&self, file: u64, start: u64, end: u64, callback: CallbackId) {
+        exec_command(CommandForBrowser::FileRead {
+            file,
+            start,
+            end,
+            callback,
+        });
+    }
+
+    pub fn file_release(&self, file: u64) {
+        exec_command(CommandForBrowser::FileRelease { file });
+    }
+
     pub fn get_random(&self, min: u32, max: u32) -> u32 {
+        if let Some(value) = api_ssr_context().random(min, max) {
+            return value;
+        }
+
         let response = exec_command(CommandForBrowser::GetRandom { min, max });
 
         let response = decode_json::<browser_response::GetRandom>(response);
@@ -268,3 +447,44 @@ impl CommandForBrowserApi {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        JsJsonSerialize,
+        dev::{
+            SsrContext,
+            command::{CommandForBrowser, browser_response},
+        },
+        driver_module::api::api_ssr_context,
+        external_api::safe_wrappers::set_command_mock,
+    };
+
+    use super::api_browser_command;
+
+    #[test]
+    fn test_utc_now_replay() {
+        set_command_mock(|command| match command {
+            CommandForBrowser::GetDateNow => {
+                Some(browser_response::GetDateNow { value: 5000 }.to_json())
+            }
+            CommandForBrowser::SsrContextGet => Some(
+                browser_response::SsrContextGet {
+                    value: Some(SsrContext {
+                        now: 1000,
+                        seed: 0,
+                        timezone_offset: 0,
+                    }),
+                }
+                .to_json(),
+            ),
+            _ => None,
+        });
+
+        api_ssr_context().init();
+
+        // Wall clock is replayed, monotonic clock (timers, cache expiration) keeps running
+        assert_eq!(api_browser_command().utc_now(), 1000);
+        assert_eq!(api_browser_command().get_date_now(), 5000);
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
     dev::command::{CommandForWasm, decode_json},
 };
 
-use super::{api_fetch::api_fetch, api_location, api_timers, api_websocket};
+use super::{api_fetch::api_fetch, api_js_promise, api_location, api_timers, api_websocket};
 
 #[store]
 pub fn api_command_wasm() -> Rc<CommandWasmApi> {
@@ -29,6 +29,9 @@ impl CommandWasmApi {
                 CommandForWasm::FetchExecResponse { response, callback } => {
                     api_fetch().callback(callback, response);
                 }
+                CommandForWasm::FetchProgress { callback, progress } => {
+                    api_fetch().callback_progress(callback, progress);
+                }
                 CommandForWasm::Websocket { callback, message } => {
                     api_websocket().callback(callback, message);
                 }
@@ -42,6 +45,14 @@ impl CommandWasmApi {
                     use crate::driver_module::api::callbacks::api_callbacks;
                     return api_callbacks().call(callback_id, value);
                 }
+                CommandForWasm::JsApiResponse {
+                    callback,
+                    success,
+                    value,
+                } => {
+                    let result = if success { Ok(value) } else { Err(value) };
+                    api_js_promise().callback(callback, result);
+                }
             }
         }
 
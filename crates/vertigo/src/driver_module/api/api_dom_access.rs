@@ -1,9 +1,10 @@
 use crate::{
     DomId, JsJson,
     dev::command::{CommandForBrowser, JsApiCommand},
+    get_driver,
 };
 
-use super::{api_browser_command::exec_command, panic_message::api_panic_message};
+use super::{api_browser_command::exec_command, api_js_promise, panic_message::api_panic_message};
 
 #[derive(Default)]
 pub struct DomAccess {
@@ -68,4 +69,38 @@ impl DomAccess {
         };
         exec_command(command)
     }
+
+    /// Like [fetch](Self::fetch), but if the result is a Promise, waits for it to settle.
+    ///
+    /// Returns `Err` with the rejection reason (or error message) if the Promise was rejected.
+    /// During SSR always returns `Err`, as there is no JavaScript.
+    ///
+    /// ```rust
+    /// use vertigo::{get_driver, JsJson, Value};
+    ///
+    /// let clipboard = Value::new(String::new());
+    ///
+    /// get_driver().spawn(async move {
+    ///     let text = get_driver()
+    ///         .dom_access()
+    ///         .root("navigator")
+    ///         .get("clipboard")
+    ///         .call("readText", vec![])
+    ///         .fetch_async()
+    ///         .await;
+    ///
+    ///     if let Ok(JsJson::String(text)) = text {
+    ///         clipboard.set(text);
+    ///     }
+    /// });
+    /// ```
+    pub async fn fetch_async(self) -> Result<JsJson, JsJson> {
+        if get_driver().is_server() {
+            return Err(JsJson::String(
+                "JavaScript is not available during SSR".into(),
+            ));
+        }
+
+        api_js_promise().call(self.commands).await
+    }
 }
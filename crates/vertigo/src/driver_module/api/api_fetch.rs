@@ -2,9 +2,11 @@ use std::rc::Rc;
 use vertigo_macro::store;
 
 use super::{CallbackStore, api_browser_command};
-#[cfg(test)]
-use crate::dev::ValueMut;
-use crate::dev::{CallbackId, FutureBox, SsrFetchRequest, SsrFetchResponse};
+use crate::{
+    FetchProgress,
+    computed::{DropResource, struct_mut::HashMapMut},
+    dev::{CallbackId, FutureBox, SsrFetchRequest, SsrFetchResponse, ValueMut},
+};
 
 #[store]
 pub fn api_fetch() -> Rc<ApiFetch> {
@@ -15,10 +17,13 @@ pub fn api_fetch() -> Rc<ApiFetch> {
 type MockFetchHandler =
     crate::dev::ValueMut<Option<Rc<dyn Fn(SsrFetchRequest) -> SsrFetchResponse>>>;
 
+type ProgressCallbacks = HashMapMut<CallbackId, Rc<dyn Fn(FetchProgress)>>;
+
 pub struct ApiFetch {
     #[cfg(test)]
     mock_handler: MockFetchHandler,
     store: CallbackStore<SsrFetchResponse, ()>,
+    progress: Rc<ProgressCallbacks>,
 }
 
 impl ApiFetch {
@@ -27,6 +32,7 @@ impl ApiFetch {
             #[cfg(test)]
             mock_handler: ValueMut::new(None),
             store: CallbackStore::new(),
+            progress: Rc::new(HashMapMut::new()),
         })
     }
 
@@ -38,19 +44,48 @@ impl ApiFetch {
         self.mock_handler.set(Some(Rc::new(handler)));
     }
 
-    pub async fn fetch(&self, request: SsrFetchRequest) -> SsrFetchResponse {
+    /// Execute the request, `on_progress` makes the browser report upload and download progress
+    pub async fn fetch(
+        &self,
+        request: SsrFetchRequest,
+        on_progress: Option<Rc<dyn Fn(FetchProgress)>>,
+    ) -> SsrFetchResponse {
         #[cfg(test)]
         if let Some(handler) = self.mock_handler.get() {
             return handler(request);
         }
 
         let (sender, receiver) = FutureBox::<SsrFetchResponse>::new();
+        let finished = Rc::new(ValueMut::new(false));
 
-        let callback = self.store.register_once(move |response| {
-            sender.publish(response);
+        let (callback, drop_callback) = self.store.register({
+            let finished = finished.clone();
+            move |response| {
+                finished.set(true);
+                sender.publish(response);
+            }
         });
 
-        api_browser_command().fetch_exec(request, callback);
+        let progress = on_progress.is_some();
+
+        if let Some(on_progress) = on_progress {
+            self.progress.insert(callback, on_progress);
+        }
+
+        api_browser_command().fetch_exec(request, callback, progress);
+
+        // Abort the request if the future is dropped before the response arrives
+        let _abort = DropResource::new({
+            let progress = self.progress.clone();
+            move || {
+                drop_callback.off();
+                progress.remove(&callback);
+
+                if !finished.get() {
+                    api_browser_command().fetch_abort(callback);
+                }
+            }
+        });
 
         receiver.await
     }
@@ -58,13 +93,23 @@ impl ApiFetch {
     pub fn callback(&self, callback: CallbackId, response: SsrFetchResponse) {
         self.store.call(callback, response);
     }
+
+    pub fn callback_progress(&self, callback: CallbackId, progress: FetchProgress) {
+        // Progress may arrive after the request was aborted
+        if let Some(on_progress) = self.progress.get(&callback) {
+            on_progress(progress);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::FetchMethod;
+    use crate::dev::command::{CommandForBrowser, CommandForWasm};
     use crate::dev::{SsrFetchRequestBody, SsrFetchResponseContent};
+    use crate::driver_module::api::api_command_wasm;
+    use crate::external_api::safe_wrappers::set_command_mock;
+    use crate::{FetchMethod, JsJsonSerialize};
     use std::collections::BTreeMap;
 
     #[tokio::test]
@@ -75,6 +120,7 @@ mod tests {
             assert_eq!(request.url, "https://test.com");
             SsrFetchResponse::Ok {
                 status: 200,
+                headers: BTreeMap::new(),
                 response: SsrFetchResponseContent::Text("mocked response".to_string()),
             }
         });
@@ -86,10 +132,12 @@ mod tests {
             body: SsrFetchRequestBody::None,
         };
 
-        let response = api.fetch(request).await;
+        let response = api.fetch(request, None).await;
 
         match response {
-            SsrFetchResponse::Ok { status, response } => {
+            SsrFetchResponse::Ok {
+                status, response, ..
+            } => {
                 assert_eq!(status, 200);
                 match response {
                     SsrFetchResponseContent::Text(text) => {
@@ -101,4 +149,85 @@ mod tests {
             _ => panic!("Expected Ok response"),
         }
     }
+
+    #[tokio::test]
+    async fn test_fetch_progress_command() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let callback_id = Rc::new(ValueMut::new(None));
+
+                set_command_mock({
+                    let callback_id = callback_id.clone();
+                    move |command| {
+                        if let CommandForBrowser::FetchExec {
+                            callback, progress, ..
+                        } = command
+                        {
+                            assert!(progress);
+                            callback_id.set(Some(*callback));
+                        }
+                        None
+                    }
+                });
+
+                let reported = Rc::new(ValueMut::new(Vec::new()));
+
+                let request = SsrFetchRequest {
+                    method: FetchMethod::GET,
+                    url: "https://test.com".to_string(),
+                    headers: BTreeMap::new(),
+                    body: SsrFetchRequestBody::None,
+                };
+
+                let response = tokio::task::spawn_local({
+                    let reported = reported.clone();
+                    async move {
+                        let on_progress = Rc::new(move |progress| {
+                            reported.change(|list| list.push(progress));
+                        });
+                        api_fetch().fetch(request, Some(on_progress)).await
+                    }
+                });
+
+                tokio::task::yield_now().await;
+                let Some(callback) = callback_id.get() else {
+                    panic!("fetch was not executed");
+                };
+
+                let progress = FetchProgress {
+                    sent: 0,
+                    sent_total: None,
+                    received: 50,
+                    received_total: Some(100),
+                };
+
+                api_command_wasm().command_from_js(
+                    CommandForWasm::FetchProgress { callback, progress }.to_json(),
+                );
+                assert_eq!(reported.get(), vec![progress]);
+
+                api_command_wasm().command_from_js(
+                    CommandForWasm::FetchExecResponse {
+                        response: SsrFetchResponse::Err {
+                            message: "done".into(),
+                        },
+                        callback,
+                    }
+                    .to_json(),
+                );
+
+                let Ok(response) = response.await else {
+                    panic!("fetch task failed");
+                };
+                assert!(matches!(response, SsrFetchResponse::Err { .. }));
+
+                // Progress reported after the response is ignored
+                api_command_wasm().command_from_js(
+                    CommandForWasm::FetchProgress { callback, progress }.to_json(),
+                );
+                assert_eq!(reported.get().len(), 1);
+            })
+            .await;
+    }
 }
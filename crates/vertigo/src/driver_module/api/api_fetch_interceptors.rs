@@ -0,0 +1,232 @@
+use std::{future::Future, pin::Pin, rc::Rc};
+use vertigo_macro::store;
+
+use crate::{
+    DropResource, RequestResponse,
+    computed::struct_mut::ValueMut,
+    dev::{CallbackId, SsrFetchRequest, SsrFetchResponse},
+};
+
+type RequestInterceptor = dyn Fn(&mut SsrFetchRequest);
+type ResponseInterceptor =
+    dyn Fn(RequestResponse) -> Pin<Box<dyn Future<Output = FetchInterception>>>;
+
+/// How many times response interceptors can repeat a single request
+const MAX_INTERCEPTOR_RETRIES: u32 = 3;
+
+/// Result of a response interceptor (see [Driver::intercept_response](crate::Driver::intercept_response)).
+pub enum FetchInterception {
+    /// Pass the (possibly changed) response to the next interceptor and finally to the caller
+    Response(RequestResponse),
+    /// Send the request again (i.e. with refreshed auth token), the new response goes through all interceptors
+    Retry(SsrFetchRequest),
+}
+
+#[store]
+pub fn api_fetch_interceptors() -> Rc<FetchInterceptors> {
+    Rc::new(FetchInterceptors {
+        request: Rc::new(ValueMut::new(Vec::new())),
+        response: Rc::new(ValueMut::new(Vec::new())),
+    })
+}
+
+/// Interceptors in order of registration
+type InterceptorList<T> = Rc<ValueMut<Vec<(CallbackId, Rc<T>)>>>;
+
+pub struct FetchInterceptors {
+    request: InterceptorList<RequestInterceptor>,
+    response: InterceptorList<ResponseInterceptor>,
+}
+
+/// Add the interceptor to the list, dropping returned resource removes it
+fn register<T: ?Sized + 'static>(list: &InterceptorList<T>, interceptor: Rc<T>) -> DropResource {
+    let id = CallbackId::new();
+    list.change(|list| list.push((id, interceptor)));
+
+    let list = list.clone();
+    DropResource::new(move || {
+        list.change(|list| list.retain(|(item_id, _)| *item_id != id));
+    })
+}
+
+impl FetchInterceptors {
+    pub fn add_request(
+        &self,
+        interceptor: impl Fn(&mut SsrFetchRequest) + 'static,
+    ) -> DropResource {
+        register(&self.request, Rc::new(interceptor))
+    }
+
+    pub fn add_response(
+        &self,
+        interceptor: impl Fn(RequestResponse) -> Pin<Box<dyn Future<Output = FetchInterception>>>
+        + 'static,
+    ) -> DropResource {
+        register(&self.response, Rc::new(interceptor))
+    }
+
+    pub fn intercept_request(&self, request: &mut SsrFetchRequest) {
+        let list = self.request.map(|list| list.clone());
+
+        for (_, interceptor) in list {
+            interceptor(request);
+        }
+    }
+
+    /// Pass the response through interceptors, `send` is used to repeat the request if requested
+    pub async fn intercept_response<Fut: Future<Output = SsrFetchResponse>>(
+        &self,
+        request: SsrFetchRequest,
+        response: SsrFetchResponse,
+        send: impl Fn(SsrFetchRequest) -> Fut,
+    ) -> SsrFetchResponse {
+        let list = self.response.map(|list| list.clone());
+
+        let mut request = request;
+        let mut response = response;
+        let mut retries = 0;
+
+        'chain: loop {
+            for (_, interceptor) in &list {
+                match interceptor(RequestResponse::new(request.clone(), response)).await {
+                    FetchInterception::Response(result) => {
+                        (request, response) = result.into_parts();
+                    }
+                    FetchInterception::Retry(new_request) => {
+                        if retries >= MAX_INTERCEPTOR_RETRIES {
+                            return SsrFetchResponse::Err {
+                                message: format!(
+                                    "Request to {} repeated too many times by fetch interceptors",
+                                    new_request.url
+                                ),
+                            };
+                        }
+
+                        retries += 1;
+                        response = send(new_request.clone()).await;
+                        request = new_request;
+                        continue 'chain;
+                    }
+                }
+            }
+
+            return response;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, rc::Rc};
+
+    use crate::{
+        FetchInterception, JsJson, RequestBuilder, RequestResponse,
+        computed::struct_mut::ValueMut,
+        dev::{SsrFetchResponse, SsrFetchResponseContent, command::CommandForBrowser},
+        driver_module::api::api_fetch,
+        external_api::safe_wrappers::set_command_mock,
+        get_driver,
+    };
+
+    #[tokio::test]
+    async fn test_fetch_interceptors() {
+        let _base_url = get_driver().intercept_request(|request| {
+            request.url = format!("https://test.com{}", request.url);
+        });
+
+        let _refresh = get_driver().intercept_response(|response| {
+            if response.status() == Some(401) {
+                let mut request = response.request().clone();
+                request.headers.insert("Authorization".into(), "new".into());
+                return FetchInterception::Retry(request);
+            }
+
+            FetchInterception::Response(response)
+        });
+
+        api_fetch().set_mock_handler(|request| {
+            assert_eq!(request.url, "https://test.com/api");
+
+            let status = match request.headers.get("Authorization") {
+                Some(token) if token == "new" => 200,
+                _ => 401,
+            };
+
+            SsrFetchResponse::Ok {
+                status,
+                headers: BTreeMap::new(),
+                response: SsrFetchResponseContent::Json(JsJson::Null),
+            }
+        });
+
+        let response = RequestBuilder::get("/api").call().await;
+        assert_eq!(response.status(), Some(200));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_interceptors_drop() {
+        let base_url = get_driver().intercept_request(|request| {
+            request.url = format!("https://test.com{}", request.url);
+        });
+
+        let status = get_driver().intercept_response(|response| {
+            let (request, _) = response.into_parts();
+            FetchInterception::Response(RequestResponse::new(
+                request,
+                SsrFetchResponse::Ok {
+                    status: 204,
+                    headers: BTreeMap::new(),
+                    response: SsrFetchResponseContent::Json(JsJson::Null),
+                },
+            ))
+        });
+
+        let urls = Rc::new(ValueMut::new(Vec::new()));
+
+        api_fetch().set_mock_handler({
+            let urls = urls.clone();
+            move |request| {
+                urls.change(|urls| urls.push(request.url));
+                SsrFetchResponse::Ok {
+                    status: 200,
+                    headers: BTreeMap::new(),
+                    response: SsrFetchResponseContent::Json(JsJson::Null),
+                }
+            }
+        });
+
+        let response = RequestBuilder::get("/api").call().await;
+        assert_eq!(response.status(), Some(204));
+
+        drop(base_url);
+        drop(status);
+
+        let response = RequestBuilder::get("/api").call().await;
+        assert_eq!(response.status(), Some(200));
+
+        assert_eq!(urls.get(), vec!["https://test.com/api", "/api"]);
+    }
+
+    #[test]
+    fn test_fetch_interceptors_stream() {
+        let _base_url = get_driver().intercept_request(|request| {
+            request.url = format!("https://test.com{}", request.url);
+        });
+
+        let streamed = Rc::new(ValueMut::new(None));
+
+        set_command_mock({
+            let streamed = streamed.clone();
+            move |command| {
+                if let CommandForBrowser::FetchStream { request, .. } = command {
+                    streamed.set(Some(request.url.clone()));
+                }
+                None
+            }
+        });
+
+        let _stream = RequestBuilder::get("/events").stream(|_| {});
+
+        assert_eq!(streamed.get(), Some("https://test.com/events".to_string()));
+    }
+}
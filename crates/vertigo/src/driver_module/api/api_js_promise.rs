@@ -0,0 +1,46 @@
+use std::rc::Rc;
+use vertigo_macro::store;
+
+use super::{CallbackStore, api_browser_command};
+use crate::{
+    JsJson,
+    dev::{CallbackId, FutureBox, command::JsApiCommand},
+};
+
+#[store]
+pub fn api_js_promise() -> Rc<ApiJsPromise> {
+    Rc::new(ApiJsPromise {
+        store: CallbackStore::new(),
+    })
+}
+
+/// Results of JS calls returning a Promise
+pub struct ApiJsPromise {
+    store: CallbackStore<Result<JsJson, JsJson>, ()>,
+}
+
+impl ApiJsPromise {
+    pub async fn call(&self, commands: Vec<JsApiCommand>) -> Result<JsJson, JsJson> {
+        self.wait(|callback| {
+            api_browser_command().js_api_call_async(commands, callback);
+        })
+        .await
+    }
+
+    /// Send a command answered with [CommandForWasm::JsApiResponse](crate::dev::command::CommandForWasm::JsApiResponse) and wait for the result
+    pub async fn wait(&self, send: impl FnOnce(CallbackId)) -> Result<JsJson, JsJson> {
+        let (sender, receiver) = FutureBox::<Result<JsJson, JsJson>>::new();
+
+        let callback = self.store.register_once(move |result| {
+            sender.publish(result);
+        });
+
+        send(callback);
+
+        receiver.await
+    }
+
+    pub fn callback(&self, callback: CallbackId, result: Result<JsJson, JsJson>) {
+        self.store.call(callback, result);
+    }
+}
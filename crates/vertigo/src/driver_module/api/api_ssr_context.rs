@@ -0,0 +1,129 @@
+use std::rc::Rc;
+use vertigo_macro::store;
+
+use crate::{
+    InstantType,
+    computed::struct_mut::ValueMut,
+    dev::{SSR_TIMEZONE_OFFSET_COOKIE, SsrContext},
+};
+
+use super::api_browser_command;
+
+const TIMEZONE_COOKIE_EXPIRES_IN: u64 = 365 * 24 * 60 * 60;
+
+#[store]
+pub fn api_ssr_context() -> Rc<SsrContextReplay> {
+    Rc::new(SsrContextReplay {
+        context: ValueMut::new(None),
+        random_state: ValueMut::new(0),
+    })
+}
+
+/// Provides time, timezone and random numbers from [SsrContext] while it's active
+/// (during the whole SSR and the first render in the browser).
+pub struct SsrContextReplay {
+    context: ValueMut<Option<SsrContext>>,
+    random_state: ValueMut<u64>,
+}
+
+impl SsrContextReplay {
+    pub fn init(&self) {
+        let context = api_browser_command().ssr_context_get();
+
+        if let Some(context) = &context {
+            self.random_state.set(context.seed.into());
+        }
+
+        self.context.set(context);
+    }
+
+    /// Stop replaying values after the first render in the browser
+    pub fn finish_first_render(&self) {
+        if !api_browser_command().is_browser() {
+            return;
+        }
+
+        let context = self.context.change(|context| context.take());
+
+        // Let the server know the timezone for the next render
+        let timezone_offset = api_browser_command().timezone_offset_js();
+
+        if context.map(|context| context.timezone_offset) != Some(timezone_offset) {
+            api_browser_command().cookie_set(
+                SSR_TIMEZONE_OFFSET_COOKIE.into(),
+                timezone_offset.to_string(),
+                TIMEZONE_COOKIE_EXPIRES_IN,
+            );
+        }
+    }
+
+    pub fn now(&self) -> Option<InstantType> {
+        self.context
+            .map(|context| context.as_ref().map(|context| context.now))
+    }
+
+    pub fn timezone_offset(&self) -> Option<i32> {
+        self.context
+            .map(|context| context.as_ref().map(|context| context.timezone_offset))
+    }
+
+    /// Random number from `min..=max` range
+    pub fn random(&self, min: u32, max: u32) -> Option<u32> {
+        if self.context.map(|context| context.is_none()) {
+            return None;
+        }
+
+        if max <= min {
+            return Some(min);
+        }
+
+        let range = u64::from(max - min) + 1;
+        Some(min + (self.next_random() % range) as u32)
+    }
+
+    // SplitMix64
+    fn next_random(&self) -> u64 {
+        let state = self.random_state.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+        self.random_state.set(state);
+
+        let mut value = state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{computed::struct_mut::ValueMut, dev::SsrContext};
+
+    use super::SsrContextReplay;
+
+    fn replay(seed: u32) -> SsrContextReplay {
+        SsrContextReplay {
+            context: ValueMut::new(Some(SsrContext {
+                now: 1000,
+                seed,
+                timezone_offset: -60,
+            })),
+            random_state: ValueMut::new(seed.into()),
+        }
+    }
+
+    #[test]
+    fn test_random_replay() {
+        let first = replay(42);
+        let second = replay(42);
+
+        for _ in 0..100 {
+            let value = first.random(5, 10);
+            assert_eq!(value, second.random(5, 10));
+            assert!(value.is_some_and(|value| (5..=10).contains(&value)));
+        }
+
+        assert_eq!(first.random(7, 7), Some(7));
+        assert!(first.random(0, u32::MAX).is_some());
+        assert_eq!(first.now(), Some(1000));
+        assert_eq!(first.timezone_offset(), Some(-60));
+    }
+}
@@ -36,6 +36,21 @@ impl ApiTimers {
         self.mock_handler.set(Some(Rc::new(handler)));
     }
 
+    /// Mock timers so that 0ms timeouts (used by [Driver::spawn](crate::Driver::spawn)) fire in the next tick,
+    /// other timers never fire.
+    ///
+    /// Must be called inside of a `LocalSet`
+    #[cfg(test)]
+    pub fn mock_zero_timeouts(&self) {
+        self.set_mock_handler(|duration, callback_id, _kind| {
+            if duration == 0 {
+                tokio::task::spawn_local(async move {
+                    api_timers().callback_timeout(callback_id);
+                });
+            }
+        });
+    }
+
     fn set<F: Fn() + 'static>(&self, duration: u32, callback: F, kind: TimerKind) -> DropResource {
         let (callback_id, drop) = self.timers.register(move |_| {
             callback();
@@ -65,6 +80,18 @@ impl ApiTimers {
         self.set(duration, callback, TimerKind::Interval)
     }
 
+    /// Whether time-based operators (i.e. [Computed::debounce](crate::Computed::debounce)) should delay changes.
+    ///
+    /// During SSR a pending timeout would hold the response, so changes are applied immediately.
+    pub fn is_delay_enabled(&self) -> bool {
+        #[cfg(test)]
+        if self.mock_handler.map(|handler| handler.is_some()) {
+            return true;
+        }
+
+        api_browser_command().is_browser()
+    }
+
     pub fn callback_timeout(&self, callback: CallbackId) {
         self.timers.call(callback, ());
     }
@@ -0,0 +1,81 @@
+use crate::{
+    JsJson, JsJsonSerialize,
+    computed::DropResource,
+    dev::{CallbackId, command::CommandForBrowser},
+};
+
+use super::{api_browser_command::exec_command, callbacks::api_callbacks};
+
+/// Key of the object under which the callback id is passed to JavaScript.
+const JS_CALLBACK_KEY: &str = "__vertigo_callback";
+
+/// Handle to a Rust closure which can be passed as an argument to JavaScript functions
+/// (see [Driver::js_callback](crate::Driver::js_callback)).
+///
+/// On the JavaScript side it's turned into a function. Calling this function runs the closure
+/// with the list of arguments ([JsJson::List]) and returns the value returned by the closure.
+/// The same handle always becomes the same function, so it can be used with `removeEventListener`.
+///
+/// If JavaScript calls the function synchronously while handling a call from Rust
+/// (i.e. `dispatchEvent` or `forEach` invoked via `DomAccess`), the closure
+/// runs right after that call finishes and the function returns `null`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JsCallback {
+    id: CallbackId,
+}
+
+impl JsCallback {
+    pub(crate) fn new<F: Fn(JsJson) -> JsJson + 'static>(
+        callback: F,
+    ) -> (JsCallback, DropResource) {
+        let (id, drop_callback) = api_callbacks().register(callback);
+
+        let drop = DropResource::new(move || {
+            drop_callback.off();
+            exec_command(CommandForBrowser::JsCallbackRelease { callback: id });
+        });
+
+        (JsCallback { id }, drop)
+    }
+}
+
+impl From<JsCallback> for JsJson {
+    fn from(value: JsCallback) -> Self {
+        JsJson::Object([(JS_CALLBACK_KEY.to_string(), value.id.to_json())].into())
+    }
+}
+
+impl From<&JsCallback> for JsJson {
+    fn from(value: &JsCallback) -> Self {
+        (*value).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{JsJson, JsJsonNumber, JsJsonSerialize, get_driver};
+
+    use super::{JS_CALLBACK_KEY, api_callbacks};
+
+    #[test]
+    fn test_js_callback() {
+        let (callback, drop) = get_driver().js_callback(|args| match args {
+            JsJson::List(list) => JsJson::Number(JsJsonNumber(list.len() as f64)),
+            _ => JsJson::Null,
+        });
+
+        let JsJson::Object(object) = JsJson::from(callback) else {
+            panic!("expected object");
+        };
+        assert_eq!(object.get(JS_CALLBACK_KEY), Some(&callback.id.to_json()));
+
+        let args = JsJson::List(vec![JsJson::True, JsJson::Null]);
+        assert_eq!(
+            api_callbacks().call(callback.id, args.clone()),
+            JsJson::Number(JsJsonNumber(2.0))
+        );
+
+        drop.off();
+        assert_eq!(api_callbacks().call(callback.id, args), JsJson::Null);
+    }
+}
@@ -11,11 +11,17 @@ mod panic_message;
 pub use panic_message::api_panic_message;
 
 mod server_handler;
-pub use server_handler::api_server_handler;
+pub(crate) use server_handler::HandlerResponse;
+pub use server_handler::{
+    HANDLER_RESPONSE_PENDING, ServerRequest, ServerResponse, api_server_handler,
+};
 
 mod api_fetch_cache;
 pub use api_fetch_cache::api_fetch_cache;
 
+mod api_ssr_context;
+pub use api_ssr_context::api_ssr_context;
+
 mod api_browser_command;
 pub use api_browser_command::api_browser_command;
 
@@ -25,6 +31,15 @@ pub use api_command_wasm::api_command_wasm;
 mod api_fetch;
 pub use api_fetch::api_fetch;
 
+mod api_fetch_interceptors;
+pub use api_fetch_interceptors::{FetchInterception, api_fetch_interceptors};
+
+mod api_js_promise;
+pub use api_js_promise::api_js_promise;
+
+mod js_callback;
+pub use js_callback::JsCallback;
+
 mod utils;
 pub use utils::CallbackStore;
 
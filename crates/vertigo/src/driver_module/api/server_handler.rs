@@ -1,26 +1,142 @@
-use std::{collections::HashMap, rc::Rc};
+use std::{collections::HashMap, future::Future, pin::Pin, rc::Rc};
 use vertigo::AutoJsJson;
 use vertigo_macro::store;
 
-use crate::{JsJson, computed::struct_mut::ValueMut, driver_module::js_value::JsJsonSerialize};
+use crate::{
+    JsJson,
+    computed::struct_mut::ValueMut,
+    driver_module::js_value::{JsJsonSerialize, from_json},
+    get_driver,
+};
+
+use super::api_browser_command;
 
 type PlainHandler = dyn Fn(&str) -> Option<String>;
+type RequestHandler = dyn Fn(ServerRequest) -> Option<HandlerResponse>;
+
+/// Returned from wasm to the server if response will be delivered later by async handler
+pub const HANDLER_RESPONSE_PENDING: &str = "pending";
+
+/// Incoming HTTP request passed to [Driver::server_handler](crate::Driver::server_handler) during SSR.
+#[derive(AutoJsJson, Clone, Debug, Default)]
+pub struct ServerRequest {
+    /// HTTP method in upper case (i.e. `GET`)
+    pub method: String,
+    /// Local url (without mount point) together with query string
+    pub url: String,
+    /// Headers of the request (names lowercased)
+    pub headers: HashMap<String, String>,
+    /// Body of the request
+    #[js_json(default)]
+    pub body: Vec<u8>,
+}
+
+impl ServerRequest {
+    /// Decode request passed by the server, also from older servers sending only the url
+    pub(crate) fn from_payload(payload: JsJson) -> Result<Self, String> {
+        match payload {
+            JsJson::String(url) => Ok(Self {
+                method: "GET".into(),
+                url,
+                ..Default::default()
+            }),
+            payload => from_json::<ServerRequest>(payload),
+        }
+    }
+
+    /// Path part of the url
+    pub fn path(&self) -> &str {
+        match self.url.split_once('?') {
+            Some((path, _)) => path,
+            None => &self.url,
+        }
+    }
+
+    /// Query string of the url (without `?`)
+    pub fn query(&self) -> Option<&str> {
+        self.url.split_once('?').map(|(_, query)| query)
+    }
+
+    /// Get header by name (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_lowercase())
+            .map(|value| value.as_str())
+    }
+}
 
-#[derive(AutoJsJson)]
-pub struct ResponseState {
+/// Response returned from [Driver::server_handler](crate::Driver::server_handler) during SSR.
+///
+/// ```rust
+/// use vertigo::ServerResponse;
+///
+/// let response = ServerResponse::bytes("application/xml", b"<urlset/>".to_vec())
+///     .header("cache-control", "public, max-age=3600");
+/// ```
+#[derive(AutoJsJson, Clone, Debug, PartialEq)]
+pub struct ServerResponse {
     pub status: u16,
     pub headers: HashMap<String, String>,
     pub body: Vec<u8>,
 }
 
+impl ServerResponse {
+    /// Create empty response with provided status
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: HashMap::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Create `200 OK` response with `text/plain` content type
+    pub fn text(body: impl Into<String>) -> Self {
+        Self::bytes("text/plain", body.into().into_bytes())
+    }
+
+    /// Create `200 OK` response with custom content type
+    pub fn bytes(content_type: impl Into<String>, body: Vec<u8>) -> Self {
+        Self::new(200)
+            .header("content-type", content_type)
+            .body(body)
+    }
+
+    /// Set status code
+    pub fn status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Add header (name is lowercased)
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers
+            .insert(name.into().to_lowercase(), value.into());
+        self
+    }
+
+    /// Set body
+    pub fn body(mut self, body: Vec<u8>) -> Self {
+        self.body = body;
+        self
+    }
+}
+
+pub(crate) enum HandlerResponse {
+    Ready(ServerResponse),
+    Pending(Pin<Box<dyn Future<Output = ServerResponse>>>),
+}
+
 pub struct ServerHandler {
     plains_handler: ValueMut<Option<Rc<PlainHandler>>>,
+    request_handler: ValueMut<Option<Rc<RequestHandler>>>,
 }
 
 impl ServerHandler {
     pub fn new() -> ServerHandler {
         ServerHandler {
             plains_handler: ValueMut::new(None),
+            request_handler: ValueMut::new(None),
         }
     }
 
@@ -28,25 +144,38 @@ impl ServerHandler {
         self.plains_handler.set(Some(Rc::new(callback)));
     }
 
-    pub fn handler(&self, url: &str) -> JsJson {
-        self.plains_handler.map(|handler| match handler {
-            Some(handler) => {
-                if let Some(response) = handler(url).map(|inner| inner.into_bytes()) {
-                    let mut headers = HashMap::<String, String>::new();
-                    headers.insert("content-type".into(), "text/plain".into());
+    pub(crate) fn request_handler(
+        &self,
+        callback: impl Fn(ServerRequest) -> Option<HandlerResponse> + 'static,
+    ) {
+        self.request_handler.set(Some(Rc::new(callback)));
+    }
+
+    pub fn handler(&self, request: ServerRequest) -> JsJson {
+        let plain_response = self
+            .plains_handler
+            .map(|handler| handler.as_ref().and_then(|handler| handler(&request.url)));
+
+        if let Some(response) = plain_response {
+            return ServerResponse::text(response).to_json();
+        }
+
+        let Some(handler) = self.request_handler.get() else {
+            return JsJson::Null;
+        };
 
-                    return ResponseState {
-                        status: 200,
-                        headers,
-                        body: response,
-                    }
-                    .to_json();
-                }
+        match handler(request) {
+            Some(HandlerResponse::Ready(response)) => response.to_json(),
+            Some(HandlerResponse::Pending(future)) => {
+                get_driver().spawn(async move {
+                    let response = future.await;
+                    api_browser_command().server_handler_response(response);
+                });
 
-                JsJson::Null
+                JsJson::String(HANDLER_RESPONSE_PENDING.into())
             }
             None => JsJson::Null,
-        })
+        }
     }
 }
 
@@ -54,3 +183,114 @@ impl ServerHandler {
 pub fn api_server_handler() -> Rc<ServerHandler> {
     Rc::new(ServerHandler::new())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{HandlerResponse, ServerHandler, ServerRequest, ServerResponse};
+    use crate::{JsJson, JsJsonSerialize, driver_module::js_value::from_json};
+
+    #[test]
+    fn test_server_response() {
+        let response = ServerResponse::bytes("image/png", vec![0, 159, 255])
+            .status(201)
+            .header("Cache-Control", "no-cache");
+
+        assert_eq!(
+            response,
+            ServerResponse {
+                status: 201,
+                headers: HashMap::from([
+                    ("content-type".to_string(), "image/png".to_string()),
+                    ("cache-control".to_string(), "no-cache".to_string()),
+                ]),
+                body: vec![0, 159, 255],
+            }
+        );
+    }
+
+    #[test]
+    fn test_handler() {
+        let handler = ServerHandler::new();
+        assert_eq!(handler.handler(ServerRequest::default()), JsJson::Null);
+
+        handler.request_handler(|request| {
+            if request.method != "POST" || request.path() != "/echo" {
+                return None;
+            }
+
+            let query = request.query().unwrap_or_default().to_string();
+            let response = ServerResponse::bytes("application/octet-stream", request.body)
+                .status(202)
+                .header("x-query", query);
+
+            Some(HandlerResponse::Ready(response))
+        });
+
+        let request = ServerRequest {
+            method: "POST".into(),
+            url: "/echo?a=1".into(),
+            headers: HashMap::new(),
+            body: vec![1, 2, 255],
+        };
+
+        let Ok(response) = from_json::<ServerResponse>(handler.handler(request)) else {
+            panic!("ServerResponse expected");
+        };
+        assert_eq!(response.status, 202);
+        assert_eq!(response.body, vec![1, 2, 255]);
+        assert_eq!(
+            response.headers.get("x-query").map(String::as_str),
+            Some("a=1")
+        );
+
+        // Not handled, so HTML is rendered
+        assert_eq!(
+            handler.handler(ServerRequest {
+                method: "GET".into(),
+                url: "/echo".into(),
+                ..Default::default()
+            }),
+            JsJson::Null
+        );
+
+        // Plain handler takes precedence
+        handler.plains(|url| (url == "/echo?a=1").then(|| "plain".to_string()));
+        let request = ServerRequest {
+            method: "POST".into(),
+            url: "/echo?a=1".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            handler.handler(request),
+            ServerResponse::text("plain").to_json()
+        );
+    }
+
+    #[test]
+    fn test_request_payload() {
+        // Sent by older servers
+        let Ok(request) = ServerRequest::from_payload(JsJson::String("/page?id=1".into())) else {
+            panic!("Url payload not accepted");
+        };
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path(), "/page");
+        assert_eq!(request.query(), Some("id=1"));
+
+        let sent = ServerRequest {
+            method: "PUT".into(),
+            url: "/page".into(),
+            headers: HashMap::from([("x-token".to_string(), "abc".to_string())]),
+            body: vec![7],
+        };
+        let Ok(request) = ServerRequest::from_payload(sent.to_json()) else {
+            panic!("Request payload not accepted");
+        };
+        assert_eq!(request.method, "PUT");
+        assert_eq!(request.header("X-Token"), Some("abc"));
+        assert_eq!(request.body, vec![7]);
+
+        assert!(ServerRequest::from_payload(JsJson::True).is_err());
+    }
+}
@@ -2,15 +2,19 @@ use std::{future::Future, pin::Pin, rc::Rc};
 use vertigo_macro::{AutoJsJson, store};
 
 use crate::{
-    Context, Css, DomNode, Instant, InstantType, JsJson, WebsocketMessage,
+    Context, Css, DomNode, FetchInterception, Instant, InstantType, JsCallback, JsJson,
+    RequestResponse, ServerRequest, ServerResponse, WebsocketMessage,
     computed::{DropResource, get_dependencies, struct_mut::ValueMut},
     css::get_css_manager,
     dev::{
-        FutureBox,
+        FutureBox, SsrFetchRequest,
         command::{LocationSetMode, LocationTarget},
     },
     driver_module::{
-        api::{api_browser_command, api_location, api_server_handler, api_timers, api_websocket},
+        api::{
+            HandlerResponse, api_browser_command, api_fetch_interceptors, api_location,
+            api_server_handler, api_timers, api_websocket,
+        },
         dom::get_driver_dom,
         utils::futures_spawn::spawn_local,
     },
@@ -53,6 +57,14 @@ impl FetchMethod {
         }
         .into()
     }
+
+    /// Methods which can be safely repeated (i.e. when retrying a request)
+    pub fn is_idempotent(&self) -> bool {
+        matches!(
+            self,
+            Self::GET | Self::HEAD | Self::PUT | Self::DELETE | Self::OPTIONS | Self::TRACE
+        )
+    }
 }
 
 type Executable = dyn Fn(Pin<Box<dyn Future<Output = ()> + 'static>>);
@@ -127,6 +139,15 @@ impl Driver {
         api_browser_command().cookie_json_set(cname.into(), cvalue, expires_in);
     }
 
+    /// Remove all responses kept by [offline cache](RequestBuilder::offline_cache), i.e. when the user logs out.
+    ///
+    /// Data already loaded into [LazyCache](crate::LazyCache)s stays in memory.
+    pub fn offline_cache_clear(&self) {
+        if self.is_browser() {
+            api_browser_command().offline_cache_clear();
+        }
+    }
+
     /// Go back in client's (browser's) history
     pub fn history_back(&self) {
         api_browser_command().history_back();
@@ -150,7 +171,7 @@ impl Driver {
 
     /// Gets current UTC timestamp
     pub fn utc_now(&self) -> InstantType {
-        api_browser_command().get_date_now()
+        api_browser_command().utc_now()
     }
 
     /// Gets browsers time zone offset in seconds
@@ -248,6 +269,37 @@ impl Driver {
         DomAccess::default()
     }
 
+    /// Register a Rust closure which can be passed as an argument to JavaScript functions
+    /// using [DomAccess] or [js!](crate::js) macro.
+    ///
+    /// The closure receives a list of arguments the JS function was called with,
+    /// and its return value is returned to JavaScript. It can be called as long as returned [DropResource] is kept.
+    ///
+    /// ```rust
+    /// use vertigo::{get_driver, js, JsJson, Value};
+    ///
+    /// let width = Value::new(0.0);
+    ///
+    /// let (on_resize, drop_resize) = get_driver().js_callback({
+    ///     let width = width.clone();
+    ///     move |_| {
+    ///         if let JsJson::Number(value) = js! { window.innerWidth } {
+    ///             width.set(value.0);
+    ///         }
+    ///         JsJson::Null
+    ///     }
+    /// });
+    ///
+    /// js! { window.addEventListener("resize", on_resize) };
+    /// ```
+    #[must_use]
+    pub fn js_callback(
+        &self,
+        callback: impl Fn(JsJson) -> JsJson + 'static,
+    ) -> (JsCallback, DropResource) {
+        JsCallback::new(callback)
+    }
+
     /// Function added for diagnostic purposes. It allows you to check whether a block with a transaction is missing somewhere.
     pub fn on_after_transaction(&self, callback: impl Fn() + 'static) -> DropResource {
         get_dependencies().hooks.on_after_transaction(callback)
@@ -278,6 +330,60 @@ impl Driver {
         api_browser_command().get_env(name)
     }
 
+    /// Get a header of the incoming HTTP request (case-insensitive) during SSR.
+    ///
+    /// Always returns `None` in the browser.
+    ///
+    /// ```rust
+    /// use vertigo::get_driver;
+    ///
+    /// let language = get_driver().request_header("accept-language");
+    /// ```
+    pub fn request_header(&self, name: impl Into<String>) -> Option<String> {
+        if self.is_server() {
+            api_browser_command().request_header_get(name)
+        } else {
+            None
+        }
+    }
+
+    /// Get method of the incoming HTTP request (in upper case, i.e. `POST`) during SSR.
+    ///
+    /// Always returns `None` in the browser.
+    ///
+    /// ```rust
+    /// use vertigo::get_driver;
+    ///
+    /// let is_post = get_driver().request_method().as_deref() == Some("POST");
+    /// ```
+    pub fn request_method(&self) -> Option<String> {
+        if self.is_server() {
+            api_browser_command().request_method_get()
+        } else {
+            None
+        }
+    }
+
+    /// Get body of the incoming HTTP request during SSR.
+    ///
+    /// Allows to handle HTML forms posted back to the same page (without JavaScript),
+    /// i.e. to re-render the page with validation errors. Always returns `None` in the browser.
+    ///
+    /// ```rust
+    /// use vertigo::get_driver;
+    ///
+    /// let form = get_driver()
+    ///     .request_body()
+    ///     .and_then(|body| String::from_utf8(body).ok());
+    /// ```
+    pub fn request_body(&self) -> Option<Vec<u8>> {
+        if self.is_server() {
+            api_browser_command().request_body_get()
+        } else {
+            None
+        }
+    }
+
     /// Get public path to build directory where the browser can access WASM and other build files.
     pub fn public_build_path(&self, path: impl Into<String>) -> String {
         let path = path.into();
@@ -353,6 +459,150 @@ impl Driver {
         api_server_handler().plains(callback);
     }
 
+    /// Register handler that intercepts requests and generates custom responses during SSR.
+    ///
+    /// Should return `None` in the handler if regular HTML should be generated by the App.
+    /// Handler registered using [plains](struct.Driver.html#method.plains) takes precedence.
+    ///
+    /// ```rust
+    /// use vertigo::{get_driver, ServerResponse};
+    ///
+    /// get_driver().server_handler(|request| {
+    ///     match request.path() {
+    ///         "/sitemap.xml" => Some(
+    ///             ServerResponse::bytes("application/xml", b"<urlset/>".to_vec())
+    ///         ),
+    ///         "/api/health" if request.method == "GET" => Some(ServerResponse::text("OK")),
+    ///         _ => None,
+    ///     }
+    /// });
+    /// ```
+    pub fn server_handler(
+        &self,
+        callback: impl Fn(ServerRequest) -> Option<ServerResponse> + 'static,
+    ) {
+        api_server_handler()
+            .request_handler(move |request| callback(request).map(HandlerResponse::Ready));
+    }
+
+    /// Register async handler that intercepts requests and generates custom responses during SSR.
+    ///
+    /// Works like [server_handler](struct.Driver.html#method.server_handler), but the response
+    /// is provided by a future, so it can for example use [RequestBuilder] before answering.
+    ///
+    /// ```rust
+    /// use vertigo::{get_driver, ServerResponse};
+    ///
+    /// get_driver().server_handler_async(|request| {
+    ///     if request.path() != "/api/upstream-health" {
+    ///         return None;
+    ///     }
+    ///
+    ///     Some(async move {
+    ///         let response = get_driver().request_get("https://example.com/health").call().await;
+    ///         match response.status() {
+    ///             Some(200) => ServerResponse::text("OK"),
+    ///             _ => ServerResponse::new(502),
+    ///         }
+    ///     })
+    /// });
+    /// ```
+    pub fn server_handler_async<Fut: Future<Output = ServerResponse> + 'static>(
+        &self,
+        callback: impl Fn(ServerRequest) -> Option<Fut> + 'static,
+    ) {
+        api_server_handler().request_handler(move |request| {
+            callback(request).map(|future| HandlerResponse::Pending(Box::pin(future)))
+        });
+    }
+
+    /// Register interceptor which can rewrite every request before it's sent.
+    ///
+    /// Applies to [RequestBuilder] (so also [LazyCache](crate::LazyCache), [LazyListCache](crate::LazyListCache)
+    /// and [streamed requests](RequestBuilder::stream)) both in the browser and during SSR.
+    /// Interceptors are run in order of registration, dropping returned resource unregisters the interceptor.
+    ///
+    /// ```rust
+    /// use vertigo::get_driver;
+    ///
+    /// let interceptor = get_driver().intercept_request(|request| {
+    ///     if request.url.starts_with('/') {
+    ///         request.url = format!("https://api.example.com{}", request.url);
+    ///     }
+    ///     request.headers.insert("X-Client".into(), "vertigo".into());
+    /// });
+    /// ```
+    #[must_use]
+    pub fn intercept_request(
+        &self,
+        interceptor: impl Fn(&mut SsrFetchRequest) + 'static,
+    ) -> DropResource {
+        api_fetch_interceptors().add_request(interceptor)
+    }
+
+    /// Register interceptor which can inspect or transform every response, or ask to send the request again.
+    ///
+    /// Runs after timeouts and retries of the [RequestBuilder], in order of registration.
+    /// A single request can be repeated by interceptors up to 3 times. Responses of
+    /// [streamed requests](RequestBuilder::stream) are not intercepted, as they are never complete.
+    /// Dropping returned resource unregisters the interceptor.
+    ///
+    /// ```rust
+    /// use vertigo::{get_driver, FetchInterception};
+    ///
+    /// let interceptor = get_driver().intercept_response(|response| {
+    ///     if response.status() == Some(503) {
+    ///         vertigo::log::warn!("Service unavailable: {}", response.request().url);
+    ///     }
+    ///     FetchInterception::Response(response)
+    /// });
+    /// ```
+    #[must_use]
+    pub fn intercept_response(
+        &self,
+        interceptor: impl Fn(RequestResponse) -> FetchInterception + 'static,
+    ) -> DropResource {
+        api_fetch_interceptors()
+            .add_response(move |response| Box::pin(std::future::ready(interceptor(response))))
+    }
+
+    /// Register async response interceptor, i.e. to refresh the auth token and repeat the request.
+    ///
+    /// Works like [intercept_response](struct.Driver.html#method.intercept_response). Requests made
+    /// inside the interceptor are intercepted too, so take care not to loop on them.
+    ///
+    /// ```rust
+    /// use vertigo::{get_driver, FetchInterception};
+    ///
+    /// let interceptor = get_driver().intercept_response_async(|response| async move {
+    ///     if response.status() != Some(401) || response.request().url == "/auth/refresh" {
+    ///         return FetchInterception::Response(response);
+    ///     }
+    ///
+    ///     let token = get_driver()
+    ///         .request_post("/auth/refresh")
+    ///         .call()
+    ///         .await
+    ///         .into_data::<String>();
+    ///
+    ///     match token {
+    ///         Ok(token) => {
+    ///             let mut request = response.request().clone();
+    ///             request.headers.insert("Authorization".into(), format!("Bearer {token}"));
+    ///             FetchInterception::Retry(request)
+    ///         }
+    ///         Err(_) => FetchInterception::Response(response),
+    ///     }
+    /// });
+    /// ```
+    #[must_use]
+    pub fn intercept_response_async<Fut: Future<Output = FetchInterception> + 'static>(
+        &self,
+        interceptor: impl Fn(RequestResponse) -> Fut + 'static,
+    ) -> DropResource {
+        api_fetch_interceptors().add_response(move |response| Box::pin(interceptor(response)))
+    }
+
     /// Allow to set custom HTTP status code during SSR
     ///
     /// ```rust
@@ -366,6 +616,85 @@ impl Driver {
         }
     }
 
+    /// Redirect to another url
+    ///
+    /// During SSR this responds with provided redirection status (301, 302, 303, 307 or 308)
+    /// and `Location` header instead of rendered HTML. In the browser the current location
+    /// is replaced in the history, so the url should point to a route within the app.
+    ///
+    /// ```rust
+    /// use vertigo::get_driver;
+    ///
+    /// let driver = get_driver();
+    /// driver.redirect(driver.route_to_public("/login"), 302);
+    /// ```
+    pub fn redirect(&self, url: impl Into<String>, status: u16) {
+        let url = url.into();
+        if self.is_server() {
+            api_browser_command().redirect(url, status);
+        } else {
+            self.history_replace(&url);
+        }
+    }
+
+    /// Allow to set custom HTTP response header during SSR
+    ///
+    /// Cookies set using [cookie_set](struct.Driver.html#method.cookie_set) during SSR
+    /// are sent as `Set-Cookie` headers automatically.
+    ///
+    /// ```rust
+    /// use vertigo::get_driver;
+    ///
+    /// get_driver().set_response_header("Cache-Control", "public, max-age=3600")
+    /// ```
+    pub fn set_response_header(&self, name: impl Into<String>, value: impl Into<String>) {
+        if self.is_server() {
+            api_browser_command().set_response_header(name.into(), value.into());
+        }
+    }
+
+    /// Override SSR timeout (in milliseconds) for the current request
+    ///
+    /// The time is counted from the start of the rendering. After the timeout the server
+    /// responds according to the policy it was configured with (`--ssr-timeout-policy`).
+    ///
+    /// ```rust
+    /// use vertigo::get_driver;
+    ///
+    /// get_driver().set_ssr_timeout(30_000)
+    /// ```
+    pub fn set_ssr_timeout(&self, time: u32) {
+        if self.is_server() {
+            api_browser_command().set_ssr_timeout(time);
+        }
+    }
+
+    /// Time (in milliseconds) left until SSR timeout, `None` in the browser
+    ///
+    /// Timers exceeding this budget are not run during SSR, so it allows to skip a delay
+    /// (or do something else instead) if it wouldn't make it into the rendered page.
+    ///
+    /// ```rust
+    /// use vertigo::get_driver;
+    ///
+    /// let driver = get_driver();
+    /// let delay = 500;
+    ///
+    /// if driver.ssr_time_left().is_none_or(|left| left > delay) {
+    ///     driver.spawn(async move {
+    ///         get_driver().sleep(delay).await;
+    ///         // ...
+    ///     });
+    /// }
+    /// ```
+    pub fn ssr_time_left(&self) -> Option<u32> {
+        if self.is_server() {
+            api_browser_command().ssr_time_left_get()
+        } else {
+            None
+        }
+    }
+
     /// Adds this CSS to manager producing a class name, which is returned
     ///
     /// There shouldn't be need to use it manually. It's used by `css!` macro.
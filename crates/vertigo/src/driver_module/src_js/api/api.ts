@@ -5,13 +5,17 @@ import { ModuleControllerType } from "../wasm_init";
 import { ExportType } from "../wasm_module";
 import { fetchCacheGet } from "./command/fetchCacheGet";
 import { fetchExec, FetchRequestType } from "./command/fetchExec";
+import { FetchStreams } from "./command/fetchStream";
 import { CallbackId } from "./types";
 import { Interval } from "./command/interval";
 import { AppLocation } from './location/AppLocation';
 import { Cookies } from "./command/cookies";
+import { StorageKind, Storages } from "./command/storage";
 import { getRandom } from "./command/getRandom";
 import { CommandType, DriverDom } from "./command/dom/dom";
 import { Metadata } from "./metadata";
+import { Files } from "./command/files";
+import { OfflineCache } from "./command/offlineCache";
 
 type JsApiCommandType =
     | { Root: { name: string } }
@@ -22,14 +26,33 @@ type JsApiCommandType =
 
 type ExecType
     = 'FetchCacheGet'
+    | 'SsrContextGet'
     | 'IsBrowser'
     | 'GetDateNow'
     | 'TimezoneOffset'
     | 'HistoryBack'
+    | 'OfflineCacheClear'
     | {
         FetchExec: {
             callback: CallbackId,
             request: FetchRequestType,
+            progress: boolean,
+        }
+    }
+    | {
+        FetchAbort: {
+            callback: CallbackId,
+        }
+    }
+    | {
+        FetchStream: {
+            callback: CallbackId,
+            request: FetchRequestType,
+        }
+    }
+    | {
+        FetchStreamAbort: {
+            callback: CallbackId,
         }
     }
     | {
@@ -104,11 +127,62 @@ type ExecType
             name: string,
         }
     }
+    | {
+        StorageGet: {
+            kind: StorageKind,
+            key: string,
+        }
+    }
+    | {
+        StorageSet: {
+            kind: StorageKind,
+            key: string,
+            value: JsJsonType,
+        }
+    }
+    | {
+        StorageRemove: {
+            kind: StorageKind,
+            key: string,
+        }
+    }
+    | {
+        StorageWatch: {
+            kind: StorageKind,
+            key: string,
+            callback: CallbackId,
+        }
+    }
+    | {
+        StorageUnwatch: {
+            callback: CallbackId,
+        }
+    }
+    | {
+        OfflineCacheGet: {
+            request: FetchRequestType,
+            callback: CallbackId,
+        }
+    }
+    | {
+        OfflineCacheSet: {
+            request: FetchRequestType,
+            entry: JsJsonType,
+        }
+    }
     | {
         GetEnv: {
             name: string
         }
     }
+    | {
+        RequestHeaderGet: {
+            name: string
+        }
+    }
+    | 'RequestMethodGet'
+    | 'RequestBodyGet'
+    | 'SsrTimeLeftGet'
     | {
         Log: {
             arg2: string, //"color: white; padding: 0 3px; background: green;",
@@ -129,6 +203,30 @@ type ExecType
             commands: Array<JsApiCommandType>
         }
     }
+    | {
+        JsApiCallAsync: {
+            commands: Array<JsApiCommandType>,
+            callback: CallbackId,
+        }
+    }
+    | {
+        JsCallbackRelease: {
+            callback: CallbackId,
+        }
+    }
+    | {
+        FileRead: {
+            file: number,
+            start: number,
+            end: number,
+            callback: CallbackId,
+        }
+    }
+    | {
+        FileRelease: {
+            file: number,
+        }
+    }
     | {
         DomBulkUpdate: {
             list: Array<CommandType>
@@ -141,16 +239,32 @@ export class Api {
     private readonly interval: Interval;
     private readonly location: AppLocation;
     private readonly cookie: Cookies;
-
+    private readonly storages: Storages;
+    private readonly offlineCache: OfflineCache;
+    private readonly files: Files;
+    private readonly fetchStreams: FetchStreams;
+    // Requests in flight, so they can be aborted (FetchAbort)
+    private readonly fetchControllers: Map<CallbackId, AbortController>;
+    // Functions created for callbacks passed from Rust (see JsCallback)
+    private readonly jsCallbacks: Map<CallbackId, (...args: Array<any>) => JsJsonType>;
+    // Depth of JS API calls made by wasm (callbacks invoked inside them can't call wasm synchronously)
+    private jsApiDepth: number;
 
     constructor(private readonly metadata: Metadata, private readonly getWasm: () => ModuleControllerType<ExportType>) {
         const appLocation = new AppLocation(getWasm);
 
-        this.dom = new DriverDom(metadata, appLocation, getWasm);
+        this.files = new Files();
+        this.fetchStreams = new FetchStreams(getWasm, this.files);
+        this.fetchControllers = new Map();
+        this.dom = new DriverDom(metadata, appLocation, this.files, getWasm);
         this.websocket = new DriverWebsocket(getWasm);
         this.interval = new Interval(getWasm);
         this.location = appLocation;
         this.cookie = new Cookies();
+        this.storages = new Storages(getWasm);
+        this.offlineCache = new OfflineCache(getWasm);
+        this.jsCallbacks = new Map();
+        this.jsApiDepth = 0;
     }
 
     exec(arg: JsJsonType): JsJsonType {
@@ -164,6 +278,12 @@ export class Api {
             return fetchCacheGet(this.metadata);
         }
 
+        if (safeArg === 'SsrContextGet') {
+            return {
+                value: this.metadata.getSsrContext(),
+            };
+        }
+
         if (safeArg === 'IsBrowser') {
             return {
                 value: true
@@ -187,8 +307,44 @@ export class Api {
             return null;
         }
 
+        if (safeArg === 'OfflineCacheClear') {
+            this.offlineCache.clear();
+            return null;
+        }
+
+        if (safeArg === 'RequestMethodGet' || safeArg === 'RequestBodyGet' || safeArg === 'SsrTimeLeftGet') {
+            // Only available during SSR
+            return {
+                value: null,
+            };
+        }
+
         if ('FetchExec' in safeArg) {
-            fetchExec(this.getWasm, safeArg.FetchExec.callback, safeArg.FetchExec.request);
+            const callback = safeArg.FetchExec.callback;
+            const controller = new AbortController();
+            this.fetchControllers.set(callback, controller);
+
+            const { request, progress } = safeArg.FetchExec;
+
+            fetchExec(this.getWasm, this.files, callback, request, controller.signal, progress).finally(() => {
+                this.fetchControllers.delete(callback);
+            });
+            return null;
+        }
+
+        if ('FetchAbort' in safeArg) {
+            this.fetchControllers.get(safeArg.FetchAbort.callback)?.abort();
+            this.fetchControllers.delete(safeArg.FetchAbort.callback);
+            return null;
+        }
+
+        if ('FetchStream' in safeArg) {
+            this.fetchStreams.start(safeArg.FetchStream.callback, safeArg.FetchStream.request);
+            return null;
+        }
+
+        if ('FetchStreamAbort' in safeArg) {
+            this.fetchStreams.abort(safeArg.FetchStreamAbort.callback);
             return null;
         }
 
@@ -255,6 +411,40 @@ export class Api {
             return null;
         }
 
+        if ('StorageGet' in safeArg) {
+            return this.storages.get(safeArg.StorageGet.kind, safeArg.StorageGet.key);
+        }
+
+        if ('StorageSet' in safeArg) {
+            this.storages.set(safeArg.StorageSet.kind, safeArg.StorageSet.key, safeArg.StorageSet.value);
+            return null;
+        }
+
+        if ('StorageRemove' in safeArg) {
+            this.storages.remove(safeArg.StorageRemove.kind, safeArg.StorageRemove.key);
+            return null;
+        }
+
+        if ('StorageWatch' in safeArg) {
+            this.storages.watch(safeArg.StorageWatch.kind, safeArg.StorageWatch.key, safeArg.StorageWatch.callback);
+            return null;
+        }
+
+        if ('StorageUnwatch' in safeArg) {
+            this.storages.unwatch(safeArg.StorageUnwatch.callback);
+            return null;
+        }
+
+        if ('OfflineCacheGet' in safeArg) {
+            this.offlineCache.get(safeArg.OfflineCacheGet.request, safeArg.OfflineCacheGet.callback);
+            return null;
+        }
+
+        if ('OfflineCacheSet' in safeArg) {
+            this.offlineCache.set(safeArg.OfflineCacheSet.request, safeArg.OfflineCacheSet.entry);
+            return null;
+        }
+
         if ('GetEnv' in safeArg) {
             const name = safeArg.GetEnv.name;
 
@@ -263,6 +453,13 @@ export class Api {
             }
         }
 
+        if ('RequestHeaderGet' in safeArg) {
+            // Request headers are only available during SSR
+            return {
+                value: null,
+            }
+        }
+
         if ('Log' in safeArg) {
             switch (safeArg.Log.kind) {
                 case 'Info': {
@@ -298,6 +495,30 @@ export class Api {
             return this.executeJsApiCall(safeArg.JsApiCall.commands);
         }
 
+        if ('JsApiCallAsync' in safeArg) {
+            this.executeJsApiCallAsync(safeArg.JsApiCallAsync.commands, safeArg.JsApiCallAsync.callback);
+            return null;
+        }
+
+        if ('JsCallbackRelease' in safeArg) {
+            this.jsCallbacks.delete(safeArg.JsCallbackRelease.callback);
+            return null;
+        }
+
+        if ('FileRead' in safeArg) {
+            const { file, start, end, callback } = safeArg.FileRead;
+            this.files.read(file, start, end).then(
+                (data) => this.jsApiRespond(callback, true, data),
+                (error) => this.jsApiRespond(callback, false, error),
+            );
+            return null;
+        }
+
+        if ('FileRelease' in safeArg) {
+            this.files.release(safeArg.FileRelease.file);
+            return null;
+        }
+
         if ('DomBulkUpdate' in safeArg) {
             this.dom.update(safeArg.DomBulkUpdate.list);
             return null;
@@ -308,6 +529,39 @@ export class Api {
     }
 
     private executeJsApiCall(commands: Array<JsApiCommandType>): JsJsonType {
+        return sanitize(this.evaluateJsApiCall(commands));
+    }
+
+    private executeJsApiCallAsync(commands: Array<JsApiCommandType>, callback: CallbackId) {
+        // Always respond asynchronously, wasm is not re-entrant
+        new Promise((resolve) => resolve(this.evaluateJsApiCall(commands)))
+            .then(
+                (value) => this.jsApiRespond(callback, true, value),
+                (error) => this.jsApiRespond(callback, false, error),
+            );
+    }
+
+    private jsApiRespond(callback: CallbackId, success: boolean, value: any) {
+        this.getWasm().wasmCommand({
+            JsApiResponse: {
+                callback,
+                success,
+                value: sanitize(value instanceof Error ? value.message : value),
+            }
+        });
+    }
+
+    private evaluateJsApiCall(commands: Array<JsApiCommandType>): any {
+        this.jsApiDepth += 1;
+
+        try {
+            return this.evaluateJsApiCommands(commands);
+        } finally {
+            this.jsApiDepth -= 1;
+        }
+    }
+
+    private evaluateJsApiCommands(commands: Array<JsApiCommandType>): any {
         let current: any = null;
 
         for (const command of commands) {
@@ -339,57 +593,111 @@ export class Api {
                     console.error('Set called on null');
                     return null;
                 }
-                current[command.Set.property] = command.Set.value;
+                current[command.Set.property] = this.resolveCallbacks(command.Set.value);
                 current = undefined;
             } else if ('Call' in command) {
                 if (current === null) {
                     console.error('Call called on null');
                     return null;
                 }
-                current = current[command.Call.method](...command.Call.args);
+                current = current[command.Call.method](...command.Call.args.map((arg) => this.resolveCallbacks(arg)));
             }
         }
 
-        // Convert result to JsJson - sanitize host objects (Window, Element, Function, etc.)
-        const isPlainObject = (obj: any): boolean => {
-            if (obj === null) return false;
-            if (typeof obj !== 'object') return false;
-            const proto = Object.getPrototypeOf(obj);
-            return proto === Object.prototype || proto === null;
-        };
+        return current;
+    }
 
-        const sanitize = (value: any): JsJsonType => {
-            if (value === null || value === undefined) {
-                return null;
-            }
-            if (typeof value === 'boolean') {
-                return value;
-            }
-            if (typeof value === 'string') {
-                return value;
-            }
-            if (typeof value === 'number') {
-                return value;
+    // Replace callback handles ({ __vertigo_callback: id }) with functions calling Rust
+    private resolveCallbacks(value: JsJsonType): any {
+        if (Array.isArray(value)) {
+            return value.map((item) => this.resolveCallbacks(item));
+        }
+
+        if (isPlainObject(value)) {
+            const object = value as { [k: string]: JsJsonType };
+            const keys = Object.keys(object);
+
+            if (keys.length === 1 && keys[0] === '__vertigo_callback' && typeof object['__vertigo_callback'] === 'number') {
+                return this.getJsCallback(object['__vertigo_callback']);
             }
-            if (value instanceof Uint8Array) {
-                return value;
+
+            const out: { [k: string]: any } = {};
+            for (const k of keys) {
+                out[k] = this.resolveCallbacks(object[k]);
             }
-            if (Array.isArray(value)) {
-                return value.map((v) => sanitize(v));
+            return out;
+        }
+
+        return value;
+    }
+
+    private getJsCallback(callback_id: CallbackId): (...args: Array<any>) => JsJsonType {
+        const existing = this.jsCallbacks.get(callback_id);
+        if (existing !== undefined) {
+            return existing;
+        }
+
+        const call = (value: JsJsonType): JsJsonType => this.getWasm().wasmCommand({
+            CallbackCall: {
+                callback_id,
+                value,
             }
-            if (isPlainObject(value)) {
-                const out: { [k: string]: JsJsonType } = {};
-                for (const k of Object.keys(value)) {
-                    out[k] = sanitize(value[k]);
-                }
-                return out;
+        });
+
+        const callback = (...args: Array<any>): JsJsonType => {
+            const value = sanitize(args);
+
+            if (this.jsApiDepth > 0) {
+                // Called synchronously by a JS API invoked from wasm (i.e. `dispatchEvent`, `forEach`),
+                // wasm is not re-entrant, so the call is deferred and its result is lost
+                queueMicrotask(() => call(value));
+                return null;
             }
 
-            // Host objects (Window, Element, DOM nodes, functions, class instances, etc.)
-            // are not serializable to JsJson. Return null for safety.
-            return null;
+            return call(value);
         };
 
-        return sanitize(current);
+        this.jsCallbacks.set(callback_id, callback);
+        return callback;
     }
 }
+
+// Convert result to JsJson - sanitize host objects (Window, Element, Function, etc.)
+const isPlainObject = (obj: any): boolean => {
+    if (obj === null) return false;
+    if (typeof obj !== 'object') return false;
+    const proto = Object.getPrototypeOf(obj);
+    return proto === Object.prototype || proto === null;
+};
+
+const sanitize = (value: any): JsJsonType => {
+    if (value === null || value === undefined) {
+        return null;
+    }
+    if (typeof value === 'boolean') {
+        return value;
+    }
+    if (typeof value === 'string') {
+        return value;
+    }
+    if (typeof value === 'number') {
+        return value;
+    }
+    if (value instanceof Uint8Array) {
+        return value;
+    }
+    if (Array.isArray(value)) {
+        return value.map((v) => sanitize(v));
+    }
+    if (isPlainObject(value)) {
+        const out: { [k: string]: JsJsonType } = {};
+        for (const k of Object.keys(value)) {
+            out[k] = sanitize(value[k]);
+        }
+        return out;
+    }
+
+    // Host objects (Window, Element, DOM nodes, functions, class instances, etc.)
+    // are not serializable to JsJson. Return null for safety.
+    return null;
+};
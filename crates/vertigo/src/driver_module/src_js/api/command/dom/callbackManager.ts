@@ -1,9 +1,11 @@
 import { ExportType } from "../../../wasm_module";
 import { getFiles } from "./dataTransfer";
+import { eventToJson, mouseEventToJson } from "./eventToJson";
 import { JsJsonType } from "../../../jsjson";
 import { ModuleControllerType } from "../../../wasm_init";
 import { MapNodes } from "./map_nodes";
 import { CallbackId } from "../../types";
+import { Files } from "../files";
 
 export class CallbackManager {
     private readonly getWasm: () => ModuleControllerType<ExportType>;
@@ -12,7 +14,7 @@ export class CallbackManager {
     // tracked separately (keyed by callback_id) for disconnect on remove.
     private observers: Map<CallbackId, IntersectionObserver>;
 
-    public constructor(getWasm: () => ModuleControllerType<ExportType>) {
+    public constructor(getWasm: () => ModuleControllerType<ExportType>, private readonly files: Files) {
         this.getWasm = getWasm;
         this.callbacks = new Map();
         this.observers = new Map();
@@ -24,6 +26,10 @@ export class CallbackManager {
         }
 
         const callback = (event: Event) => {
+            if (event_name.startsWith('event:')) {
+                return this.domEvent(event, callback_id);
+            }
+
             if (event_name === 'click') {
                 return this.click(event, callback_id);
             }
@@ -44,6 +50,10 @@ export class CallbackManager {
                 return this.blur(event, callback_id);
             }
 
+            if (event_name === 'focus') {
+                return this.focus(event, callback_id);
+            }
+
             if (event_name === 'mousedown') {
                 return this.mousedown(event, callback_id);
             }
@@ -64,6 +74,10 @@ export class CallbackManager {
                 return this.keydown(event, callback_id);
             }
 
+            if (event_name === 'keyup') {
+                return this.keyup(event, callback_id);
+            }
+
             if (event_name === 'hook_keydown') {
                 return this.keydown(event, callback_id);
             }
@@ -94,8 +108,7 @@ export class CallbackManager {
             document.addEventListener('keydown', callback, false);
         } else {
             const node = nodes.get('callback_add', id);
-            const domEventName = event_name === 'change_file' ? 'change' : event_name;
-            node.addEventListener(domEventName, callback, false);
+            node.addEventListener(domEventName(event_name), callback, false);
         }
     }
 
@@ -116,8 +129,7 @@ export class CallbackManager {
             document.removeEventListener('keydown', callback);
         } else {
             const node = nodes.get('callback_remove', id);
-            const domEventName = event_name === 'change_file' ? 'change' : event_name;
-            node.removeEventListener(domEventName, callback);
+            node.removeEventListener(domEventName(event_name), callback);
         }
     }
 
@@ -169,7 +181,7 @@ export class CallbackManager {
 
     private click(event: Event, callback_id: CallbackId) {
         event.preventDefault();
-        let click_event = this.wasmCallback(callback_id, undefined);
+        let click_event = this.wasmCallback(callback_id, mouseEventToJson(event));
 
         // Check if click_event is an object (JsJson Object type)
         if (click_event !== null && typeof click_event === 'object' && !Array.isArray(click_event)) {
@@ -182,6 +194,19 @@ export class CallbackManager {
         }
     }
 
+    private domEvent(event: Event, callback_id: CallbackId) {
+        const result = this.wasmCallback(callback_id, eventToJson(event));
+
+        if (result !== null && typeof result === 'object' && !Array.isArray(result)) {
+            if ('stop_propagation' in result && result['stop_propagation'] === true) {
+                event.stopPropagation();
+            }
+            if ('prevent_default' in result && result['prevent_default'] === true) {
+                event.preventDefault();
+            }
+        }
+    }
+
     private submit(event: Event, callback_id: CallbackId) {
         event.preventDefault();
         this.wasmCallback(callback_id, undefined);
@@ -213,30 +238,7 @@ export class CallbackManager {
         const target = event.target;
 
         if (target instanceof HTMLInputElement && target.files !== null && target.files.length > 0) {
-            const promises: Array<Promise<{ name: string, data: Uint8Array }>> = [];
-
-            for (let i = 0; i < target.files.length; i++) {
-                const file = target.files[i];
-                if (file !== undefined) {
-                    promises.push(
-                        file.arrayBuffer().then((buf) => ({
-                            name: file.name,
-                            data: new Uint8Array(buf),
-                        }))
-                    );
-                }
-            }
-
-            if (promises.length > 0) {
-                Promise.all(promises).then((files) => {
-                    const params = [];
-                    for (const f of files) {
-                        params.push([f.name, Array.from(f.data)]);
-                    }
-                    this.wasmCallback(callback_id, [params]);
-                }).catch((err) => console.error('changeFile ->', err));
-            }
-
+            this.wasmCallback(callback_id, [this.filesToJson(Array.from(target.files))]);
             target.value = '';
             return;
         }
@@ -244,28 +246,44 @@ export class CallbackManager {
         console.warn('changeFile: not a file input or no files', target);
     }
 
+    // Payload order MUST match the Rust decoder get_drop_file_event.
+    // Content is not sent, it's read on demand using the id.
+    private filesToJson(files: Array<File>): JsJsonType {
+        return files.map((file) => [
+            file.name,
+            file.size,
+            file.type,
+            file.lastModified,
+            this.files.add(file),
+        ]);
+    }
+
     private blur(_event: Event, callback_id: CallbackId) {
         this.wasmCallback(callback_id, undefined);
     }
 
+    private focus(_event: Event, callback_id: CallbackId) {
+        this.wasmCallback(callback_id, undefined);
+    }
+
     private mousedown(event: Event, callback_id: CallbackId) {
-        if (this.wasmCallback(callback_id, undefined)) {
+        if (this.wasmCallback(callback_id, mouseEventToJson(event))) {
             event.preventDefault()
         }
     }
 
     private mouseup(event: Event, callback_id: CallbackId) {
-        if (this.wasmCallback(callback_id, undefined)) {
+        if (this.wasmCallback(callback_id, mouseEventToJson(event))) {
             event.preventDefault()
         }
     }
 
-    private mouseenter(_event: Event, callback_id: CallbackId) {
-        this.wasmCallback(callback_id, undefined);
+    private mouseenter(event: Event, callback_id: CallbackId) {
+        this.wasmCallback(callback_id, mouseEventToJson(event));
     }
 
-    private mouseleave(_event: Event, callback_id: CallbackId) {
-        this.wasmCallback(callback_id, undefined);
+    private mouseleave(event: Event, callback_id: CallbackId) {
+        this.wasmCallback(callback_id, mouseEventToJson(event));
     }
 
     private drop(event: Event, callback_id: CallbackId) {
@@ -278,22 +296,7 @@ export class CallbackManager {
                 const files = getFiles(event.dataTransfer.items);
 
                 if (files.length) {
-                    Promise.all(files).then((files) => {
-                        const params = [];
-
-                        for (const file of files) {
-                            // Convert Uint8Array to array of numbers for JsJson
-                            const dataArray = Array.from(file.data);
-                            params.push([
-                                file.name,
-                                dataArray,
-                            ]);
-                        }
-
-                        this.wasmCallback(callback_id, [params]);
-                    }).catch((error) => {
-                        console.error('callback_drop -> promise.all -> ', error);
-                    });
+                    this.wasmCallback(callback_id, [this.filesToJson(files)]);
                 } else {
                     console.error('No files to send');
                 }
@@ -325,9 +328,43 @@ export class CallbackManager {
         console.warn('keydown ignore', event);
     }
 
+    private keyup(event: Event, callback_id: CallbackId) {
+        if (event instanceof KeyboardEvent) {
+            const result = this.wasmCallback(callback_id, [
+                event.key,
+                event.code,
+                event.altKey,
+                event.ctrlKey,
+                event.shiftKey,
+                event.metaKey
+            ]);
+
+            if (result === true) {
+                event.preventDefault();
+                event.stopPropagation();
+            }
+
+            return;
+        }
+
+        console.warn('keyup ignore', event);
+    }
+
     private load(event: Event, callback_id: CallbackId) {
         event.preventDefault();
         this.wasmCallback(callback_id, undefined);
     }
 
 }
+
+const domEventName = (event_name: string): string => {
+    if (event_name === 'change_file') {
+        return 'change';
+    }
+
+    if (event_name.startsWith('event:')) {
+        return event_name.slice('event:'.length);
+    }
+
+    return event_name;
+};
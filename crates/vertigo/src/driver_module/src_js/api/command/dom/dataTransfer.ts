@@ -1,10 +1,5 @@
-interface FileItemType {
-    name: string,
-    data: Uint8Array,
-}
-
-export function getFiles(items: DataTransferItemList): Array<Promise<FileItemType>> {
-    const files: Array<Promise<FileItemType>> = [];
+export function getFiles(items: DataTransferItemList): Array<File> {
+    const files: Array<File> = [];
 
     for (let i = 0; i < items.length; i++) {
         const item = items[i];
@@ -17,13 +12,7 @@ export function getFiles(items: DataTransferItemList): Array<Promise<FileItemTyp
             if (file === null) {
                 console.error(`dom -> drop -> index:${i} -> It's not a file`);
             } else {
-                files.push(file
-                    .arrayBuffer()
-                    .then((data): FileItemType => ({
-                        name: file.name,
-                        data: new Uint8Array(data),
-                    }))
-                );
+                files.push(file);
             }
         }
     }
@@ -1,5 +1,6 @@
 import { AppLocation } from "../../location/AppLocation";
 import { CallbackManager } from "./callbackManager";
+import { Files } from "../files";
 import { ExportType } from "../../../wasm_module";
 import { hydrate } from "./hydration";
 import { injects } from "./injects";
@@ -108,10 +109,10 @@ export class DriverDom {
     public readonly nodes: MapNodes;
     private readonly callbacks: CallbackManager;
 
-    public constructor(private readonly metadata: Metadata, appLocation: AppLocation, getWasm: () => ModuleControllerType<ExportType>) {
+    public constructor(private readonly metadata: Metadata, appLocation: AppLocation, files: Files, getWasm: () => ModuleControllerType<ExportType>) {
         this.appLocation = appLocation;
         this.nodes = new MapNodes();
-        this.callbacks = new CallbackManager(getWasm);
+        this.callbacks = new CallbackManager(getWasm, files);
 
         document.addEventListener('dragover', (ev): void => {
             // console.log('File(s) in drop zone');
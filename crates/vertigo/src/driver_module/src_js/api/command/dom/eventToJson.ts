@@ -0,0 +1,80 @@
+import { JsJsonType } from "../../../jsjson";
+
+const isPrimitive = (value: unknown): value is string | number | boolean => {
+    return typeof value === 'string' || typeof value === 'number' || typeof value === 'boolean';
+};
+
+const touchesToJson = (touches: TouchList): Array<JsJsonType> => {
+    const out: Array<JsJsonType> = [];
+
+    for (let i = 0; i < touches.length; i++) {
+        const touch = touches[i];
+        if (touch !== undefined) {
+            out.push({
+                identifier: touch.identifier,
+                clientX: touch.clientX,
+                clientY: touch.clientY,
+                pageX: touch.pageX,
+                pageY: touch.pageY,
+            });
+        }
+    }
+
+    return out;
+};
+
+// Data passed to generic event handler (DomEvent in Rust)
+export const eventToJson = (event: Event): JsJsonType => {
+    const out: { [key: string]: JsJsonType } = {};
+
+    // Primitive properties, including the inherited ones (clientX, deltaY, key, pointerId, ...)
+    for (const key in event) {
+        //@ts-expect-error - Event has no index signature
+        const value: unknown = event[key];
+
+        if (isPrimitive(value)) {
+            out[key] = value;
+        }
+    }
+
+    if (typeof TouchEvent !== 'undefined' && event instanceof TouchEvent) {
+        out['touches'] = touchesToJson(event.touches);
+        out['changedTouches'] = touchesToJson(event.changedTouches);
+    }
+
+    const target = event.currentTarget;
+
+    if (target instanceof Element) {
+        out['scrollTop'] = target.scrollTop;
+        out['scrollLeft'] = target.scrollLeft;
+    }
+
+    if (target instanceof HTMLInputElement || target instanceof HTMLTextAreaElement || target instanceof HTMLSelectElement) {
+        out['value'] = target.value;
+    }
+
+    return out;
+};
+
+// Payload order MUST match the Rust decoder get_mouse_event.
+export const mouseEventToJson = (event: Event): JsJsonType => {
+    if (event instanceof MouseEvent) {
+        return [
+            event.clientX,
+            event.clientY,
+            event.pageX,
+            event.pageY,
+            event.offsetX,
+            event.offsetY,
+            event.button,
+            event.buttons,
+            event.altKey,
+            event.ctrlKey,
+            event.shiftKey,
+            event.metaKey,
+        ];
+    }
+
+    // i.e. click triggered by keyboard on some browsers
+    return [0, 0, 0, 0, 0, 0, 0, 0, false, false, false, false];
+};
@@ -0,0 +1,170 @@
+// --- MOCKS ---
+class MockNode {
+    static ELEMENT_NODE = 1;
+    static TEXT_NODE = 3;
+    nodeType: number;
+    childNodes: MockNode[];
+    parentNode: MockNode | null;
+
+    constructor(type: number) {
+        this.nodeType = type;
+        this.childNodes = [];
+        this.parentNode = null;
+    }
+    remove() {
+        if (this.parentNode) {
+            const idx = this.parentNode.childNodes.indexOf(this);
+            if (idx > -1) this.parentNode.childNodes.splice(idx, 1);
+            this.parentNode = null;
+        }
+    }
+    appendChild(child: MockNode) {
+        if (child.parentNode) child.remove();
+        child.parentNode = this;
+        this.childNodes.push(child);
+        return this;
+    }
+    insertBefore(node: MockNode, ref: MockNode) {
+        const nodes = node instanceof MockFragment ? node.takeNodes() : [node];
+        for (const child of nodes) {
+            if (child.parentNode) child.remove();
+            child.parentNode = this;
+            this.childNodes.splice(this.childNodes.indexOf(ref), 0, child);
+        }
+    }
+    replaceWith(node: MockNode) {
+        const parent = this.parentNode;
+        if (parent === null) return;
+        parent.insertBefore(node, this);
+        this.remove();
+    }
+}
+
+class MockFragment extends MockNode {
+    constructor() {
+        super(11);
+    }
+    takeNodes(): MockNode[] {
+        const nodes = this.childNodes;
+        this.childNodes = [];
+        return nodes;
+    }
+}
+
+class MockElement extends MockNode {
+    tagName: string;
+    attributes: Map<string, string>;
+
+    constructor(tagName: string, attributes: Record<string, string> = {}) {
+        super(MockNode.ELEMENT_NODE);
+        this.tagName = tagName.toUpperCase();
+        this.attributes = new Map(Object.entries(attributes));
+    }
+    get children(): MockElement[] {
+        return this.childNodes.filter((node): node is MockElement => node instanceof MockElement);
+    }
+    hasAttribute(name: string) { return this.attributes.has(name); }
+    getAttribute(name: string) { return this.attributes.get(name) ?? null; }
+    child(node: MockNode) { this.appendChild(node); return this; }
+}
+
+class MockTemplate extends MockElement {
+    content: MockFragment;
+
+    constructor(path: string, ...content: MockNode[]) {
+        super('TEMPLATE', { 'data-vertigo-patch': path });
+        this.content = new MockFragment();
+        for (const node of content) {
+            this.content.appendChild(node);
+        }
+    }
+}
+
+class MockText extends MockNode {
+    textContent: string;
+    constructor(text: string) {
+        super(MockNode.TEXT_NODE);
+        this.textContent = text;
+    }
+}
+
+(globalThis as any).Node = MockNode;
+
+import { applyStreamPatches } from "./streamPatches";
+
+// --- TEST RUNNER ---
+function assert(condition: boolean, message: string) {
+    if (condition) {
+        console.log(`PASS: ${message}`);
+    } else {
+        console.error(`FAIL: ${message}`);
+        throw new Error(`Assertion failed: ${message}`);
+    }
+}
+
+const text = (node: MockNode | undefined): string | undefined =>
+    node?.childNodes.map((child) => child instanceof MockText ? child.textContent : text(child)).join('');
+
+// --- TESTS ---
+
+function testNestedPatches() {
+    console.log("\n--- Test stream patches 1: Nested patches applied in order ---");
+
+    const list = new MockElement('UL')
+        .child(new MockElement('LI').child(new MockText('Loading')))
+        .child(new MockElement('LI').child(new MockText('Loading')));
+
+    const script = new MockElement('SCRIPT');
+
+    const body = new MockElement('BODY')
+        .child(new MockText('\n'))
+        .child(new MockElement('HEADER'))
+        .child(list)
+        .child(new MockTemplate('1/1', new MockElement('LI').child(new MockText('B'))))
+        .child(new MockTemplate('1/1', new MockElement('LI').child(new MockText('B2'))))
+        .child(new MockTemplate('1/0', new MockElement('LI').child(new MockText('A'))))
+        .child(script);
+
+    applyStreamPatches(body as any);
+
+    assert(body.children.length === 3, "Patches removed");
+    assert(body.children[1] === list, "Parent element kept");
+    assert(text(list.children[0]) === 'A', "First item patched");
+    assert(text(list.children[1]) === 'B2', "Second item patched twice");
+    assert(body.children[2] === script, "Nodes after patches kept");
+}
+
+function testBodyPatch() {
+    console.log("\n--- Test stream patches 2: Content of the body replaced ---");
+
+    const metadata = new MockElement('DIV', { id: 'v-metadata' });
+
+    const body = new MockElement('BODY')
+        .child(new MockText('Loading'))
+        .child(new MockTemplate('', new MockElement('MAIN'), new MockText('Footer')))
+        .child(new MockTemplate('0', new MockElement('ARTICLE')))
+        .child(metadata);
+
+    applyStreamPatches(body as any);
+
+    assert(body.childNodes.length === 3, "Content replaced");
+    assert((body.childNodes[0] as MockElement).tagName === 'ARTICLE', "Element of replaced content patched");
+    assert(text(body) === 'Footer', "Text of replaced content kept");
+    assert(body.childNodes[2] === metadata, "Nodes after patches kept");
+}
+
+function testNoPatches() {
+    console.log("\n--- Test stream patches 3: Document without patches ---");
+
+    const body = new MockElement('BODY')
+        .child(new MockElement('DIV').child(new MockText('Ready')))
+        .child(new MockElement('TEMPLATE'));
+
+    applyStreamPatches(body as any);
+
+    assert(body.childNodes.length === 2, "Document untouched");
+}
+
+testNestedPatches();
+testBodyPatch();
+testNoPatches();
@@ -0,0 +1,65 @@
+// Changes of the body streamed by SSR after the beginning of the body was sent (`--ssr-streaming`).
+//
+// Each patch is a `<template data-vertigo-patch="PATH">` appended to the body, where PATH is a list
+// of child element indexes starting from the body (empty for the whole content of the body).
+// Paths refer to the document with previous patches applied, so they have to be applied in order.
+
+const PATCH_ATTR = 'data-vertigo-patch';
+
+const isPatch = (node: Element): node is HTMLTemplateElement =>
+    node.tagName === 'TEMPLATE' && node.hasAttribute(PATCH_ATTR);
+
+const findTarget = (content: Array<Element>, path: string): Element | null => {
+    const [first, ...rest] = path.split('/').map(Number);
+
+    let target = first === undefined ? undefined : content[first];
+
+    for (const index of rest) {
+        target = target?.children[index];
+    }
+
+    return target ?? null;
+};
+
+export const applyStreamPatches = (body: HTMLElement) => {
+    const children = Array.from(body.children);
+    const patches = children.filter(isPatch);
+
+    const boundary = patches[0];
+    if (boundary === undefined) {
+        return;
+    }
+
+    // Nodes sent in the beginning of the body precede the patches
+    const contentNodes = (): Array<ChildNode> => {
+        const nodes = Array.from(body.childNodes);
+        return nodes.slice(0, nodes.indexOf(boundary));
+    };
+
+    for (const patch of patches) {
+        const path = patch.getAttribute(PATCH_ATTR) ?? '';
+
+        if (path === '') {
+            for (const node of contentNodes()) {
+                node.remove();
+            }
+
+            body.insertBefore(patch.content, boundary);
+            continue;
+        }
+
+        const content = contentNodes().filter((node): node is Element => node.nodeType === Node.ELEMENT_NODE);
+        const target = findTarget(content, path);
+
+        if (target === null) {
+            console.error(`Stream patch: element ${path} not found`);
+            continue;
+        }
+
+        target.replaceWith(patch.content);
+    }
+
+    for (const patch of patches) {
+        patch.remove();
+    }
+};
@@ -2,6 +2,23 @@ import { JsJsonType } from "../../jsjson";
 import { ModuleControllerType } from "../../wasm_init";
 import { ExportType } from "../../wasm_module";
 import { CallbackId } from "../types";
+import { Files } from "./files";
+
+type MultipartValueType = {
+    Text: {
+        text: string
+    }
+} | {
+    Bytes: {
+        data: Uint8Array,
+        file_name: string | null,
+        content_type: string | null,
+    }
+} | {
+    File: {
+        file: number
+    }
+};
 
 export interface FetchRequestType {
     method: string,
@@ -11,16 +28,39 @@ export interface FetchRequestType {
         Data: {
             data: JsJsonType
         }
+    } | {
+        File: {
+            file: number
+        }
+    } | {
+        Text: {
+            text: string
+        }
+    } | {
+        Bytes: {
+            data: Uint8Array
+        }
+    } | {
+        FormUrlEncoded: {
+            fields: Array<{ name: string, value: string }>
+        }
+    } | {
+        Multipart: {
+            parts: Array<{ name: string, value: MultipartValueType }>
+        }
     }
 }
 
 type FetchResponseType = {
     Ok: {
         status: number,
+        headers: Record<string, string>,
         response: {
             Text: string
         } | {
             Json: JsJsonType,
+        } | {
+            Bytes: Uint8Array,
         }
     }
 } | {
@@ -29,7 +69,7 @@ type FetchResponseType = {
     }
 };
 
-const getHeaders = (headers: Array<{ k: string, v: string }>): Record<string, string> => {
+export const getHeaders = (headers: Array<{ k: string, v: string }>): Record<string, string> => {
     const result: Record<string, string> = {};
 
     for (const { k, v } of headers) {
@@ -39,11 +79,74 @@ const getHeaders = (headers: Array<{ k: string, v: string }>): Record<string, st
     return result;
 };
 
-const getBodyString = (body: FetchRequestType['body']): string | undefined => {
+const getFile = (files: Files, id: number): File => {
+    const file = files.get(id);
+
+    if (file === undefined) {
+        throw Error(`File not found: ${id}`);
+    }
+
+    return file;
+};
+
+const getFormData = (
+    parts: Array<{ name: string, value: MultipartValueType }>,
+    files: Files
+): FormData => {
+    const formData = new FormData();
+
+    for (const { name, value } of parts) {
+        if ('Text' in value) {
+            formData.append(name, value.Text.text);
+        } else if ('Bytes' in value) {
+            const { data, file_name, content_type } = value.Bytes;
+            const blob = new Blob([data], content_type === null ? {} : { type: content_type });
+
+            if (file_name === null) {
+                formData.append(name, blob);
+            } else {
+                formData.append(name, blob, file_name);
+            }
+        } else {
+            formData.append(name, getFile(files, value.File.file));
+        }
+    }
+
+    return formData;
+};
+
+export const getBody = (body: FetchRequestType['body'], files: Files): XMLHttpRequestBodyInit | undefined => {
     if (body === 'None') {
         return undefined;
     }
 
+    if ('File' in body) {
+        return getFile(files, body.File.file);
+    }
+
+    if ('Text' in body) {
+        return body.Text.text;
+    }
+
+    if ('Bytes' in body) {
+        return body.Bytes.data;
+    }
+
+    if ('FormUrlEncoded' in body) {
+        const params = new URLSearchParams();
+
+        for (const { name, value } of body.FormUrlEncoded.fields) {
+            params.append(name, value);
+        }
+
+        return params;
+    }
+
+    if ('Multipart' in body) {
+        //Content-Type with the boundary is set by the browser
+        return getFormData(body.Multipart.parts, files);
+    }
+
     return JSON.stringify(body.Data.data);
 };
 
@@ -53,15 +156,45 @@ const getBodyString = (body: FetchRequestType['body']): string | undefined => {
 export const parseJsonBody = (bodyText: string): JsJsonType | null =>
     bodyText.length === 0 ? null : JSON.parse(bodyText);
 
+type ResponseKind = 'Json' | 'Text' | 'Bytes';
+
+// Keep in sync with SsrFetchResponseContent::kind_for
+export const responseKind = (contentType: string | null): ResponseKind => {
+    const mime = ((contentType ?? '').split(';')[0] ?? '').trim().toLowerCase();
+
+    if (mime === '' || mime === 'application/json' || mime.endsWith('+json')) {
+        return 'Json';
+    }
+
+    if (mime.startsWith('text/')) {
+        return 'Text';
+    }
+
+    return 'Bytes';
+};
+
+export const responseHeaders = (response: Response): Record<string, string> => {
+    const headers: Record<string, string> = {};
+
+    response.headers.forEach((value, name) => {
+        headers[name.toLowerCase()] = value;
+    });
+
+    return headers;
+};
+
 const processResponse = async (response: Response): Promise<FetchResponseType> => {
     const status = response.status;
-    const contentType = response.headers.get("Content-Type");
+    const headers = responseHeaders(response);
 
     try {
-        if (contentType?.startsWith('text/plain;')) {
+        const kind = responseKind(response.headers.get("Content-Type"));
+
+        if (kind === 'Text') {
             return {
                 Ok: {
                     status,
+                    headers,
                     response: {
                         Text: await response.text(),
                     }
@@ -69,11 +202,24 @@ const processResponse = async (response: Response): Promise<FetchResponseType> =
             }
         }
 
+        if (kind === 'Bytes') {
+            return {
+                Ok: {
+                    status,
+                    headers,
+                    response: {
+                        Bytes: new Uint8Array(await response.arrayBuffer()),
+                    }
+                }
+            }
+        }
+
         const json = parseJsonBody(await response.text());
 
         return {
             Ok: {
                 status,
+                headers,
                 response: {
                     Json: json
                 }
@@ -88,22 +234,141 @@ const processResponse = async (response: Response): Promise<FetchResponseType> =
     }
 };
 
+export type FetchProgressType = {
+    sent: number,
+    sent_total: number | null,
+    received: number,
+    received_total: number | null,
+};
+
+const xhrHeaders = (xhr: XMLHttpRequest): Record<string, string> => {
+    const headers: Record<string, string> = {};
+
+    for (const line of xhr.getAllResponseHeaders().trim().split(/[\r\n]+/)) {
+        const separator = line.indexOf(':');
+
+        if (separator > 0) {
+            headers[line.slice(0, separator).trim().toLowerCase()] = line.slice(separator + 1).trim();
+        }
+    }
+
+    return headers;
+};
+
+const xhrResponse = (xhr: XMLHttpRequest): FetchResponseType => {
+    const status = xhr.status;
+    const headers = xhrHeaders(xhr);
+    const data = new Uint8Array(xhr.response as ArrayBuffer);
+
+    try {
+        const kind = responseKind(xhr.getResponseHeader("Content-Type"));
+
+        if (kind === 'Bytes') {
+            return { Ok: { status, headers, response: { Bytes: data } } };
+        }
+
+        const text = new TextDecoder().decode(data);
+
+        if (kind === 'Text') {
+            return { Ok: { status, headers, response: { Text: text } } };
+        }
+
+        return { Ok: { status, headers, response: { Json: parseJsonBody(text) } } };
+    } catch (error) {
+        return {
+            Err: {
+                message: String(error),
+            }
+        };
+    }
+};
+
+// fetch() doesn't report upload progress, so XMLHttpRequest is used when progress is requested
+const xhrExec = (
+    request: FetchRequestType,
+    files: Files,
+    signal: AbortSignal,
+    onProgress: (progress: FetchProgressType) => void,
+): Promise<FetchResponseType> => new Promise((resolve, reject) => {
+    const xhr = new XMLHttpRequest();
+    const progress: FetchProgressType = {
+        sent: 0,
+        sent_total: null,
+        received: 0,
+        received_total: null,
+    };
+
+    xhr.open(request.method, request.url);
+    xhr.responseType = 'arraybuffer';
+
+    for (const [name, value] of Object.entries(getHeaders(request.headers))) {
+        xhr.setRequestHeader(name, value);
+    }
+
+    xhr.upload.onprogress = (event) => {
+        progress.sent = event.loaded;
+        progress.sent_total = event.lengthComputable ? event.total : null;
+        onProgress({ ...progress });
+    };
+
+    xhr.onprogress = (event) => {
+        progress.received = event.loaded;
+        progress.received_total = event.lengthComputable ? event.total : null;
+        onProgress({ ...progress });
+    };
+
+    xhr.onload = () => resolve(xhrResponse(xhr));
+    xhr.onerror = () => reject(new Error('Network error'));
+    xhr.onabort = () => reject(new Error('Request aborted'));
+    signal.addEventListener('abort', () => xhr.abort());
+
+    xhr.send(getBody(request.body, files) ?? null);
+});
+
+const fetchResponse = async (
+    request: FetchRequestType,
+    files: Files,
+    signal: AbortSignal,
+): Promise<FetchResponseType> => {
+    const response = await fetch(request.url, {
+        method: request.method,
+        headers: getHeaders(request.headers),
+        body: getBody(request.body, files),
+        signal,
+    });
+
+    return processResponse(response);
+};
 
 export const fetchExec = async (
     getWasm: () => ModuleControllerType<ExportType>,
+    files: Files,
     callback_id: CallbackId,
-    request: FetchRequestType
+    request: FetchRequestType,
+    signal: AbortSignal,
+    progress: boolean,
 ): Promise<void> => {
     const wasm = getWasm();
 
+    const onProgress = (progress: FetchProgressType) => {
+        if (!signal.aborted) {
+            wasm.wasmCommand({
+                FetchProgress: {
+                    callback: callback_id,
+                    progress,
+                }
+            });
+        }
+    };
+
     try {
-        const response = await fetch(request.url, {
-            method: request.method,
-            headers: getHeaders(request.headers),
-            body: getBodyString(request.body),
-        });
+        const response2 = progress
+            ? await xhrExec(request, files, signal, onProgress)
+            : await fetchResponse(request, files, signal);
 
-        const response2 = await processResponse(response);
+        if (signal.aborted) {
+            return;
+        }
 
         wasm.wasmCommand({
             'FetchExecResponse': {
@@ -113,6 +378,11 @@ export const fetchExec = async (
         });
 
     } catch (err) {
+        if (signal.aborted) {
+            // Aborted by wasm (FetchAbort), the response is not expected anymore
+            return;
+        }
+
         console.error('fetch error (1)', err);
         const responseMessage = new String(err).toString();
 
@@ -0,0 +1,86 @@
+import { JsJsonType } from "../../jsjson";
+import { ModuleControllerType } from "../../wasm_init";
+import { ExportType } from "../../wasm_module";
+import { CallbackId } from "../types";
+import { FetchRequestType, getBody, getHeaders, responseHeaders } from "./fetchExec";
+import { Files } from "./files";
+
+// Streamed requests (RequestBuilder::stream), events are sent to the wasm callback
+export class FetchStreams {
+    private readonly controllers: Map<CallbackId, AbortController> = new Map();
+
+    constructor(
+        private readonly getWasm: () => ModuleControllerType<ExportType>,
+        private readonly files: Files,
+    ) {}
+
+    private emit(callback_id: CallbackId, value: JsJsonType) {
+        if (!this.controllers.has(callback_id)) {
+            // Aborted
+            return;
+        }
+
+        this.getWasm().wasmCommand({
+            CallbackCall: {
+                callback_id,
+                value,
+            }
+        });
+    }
+
+    public start(callback_id: CallbackId, request: FetchRequestType) {
+        const controller = new AbortController();
+        this.controllers.set(callback_id, controller);
+
+        this.run(callback_id, request, controller).catch((error) => {
+            if (!controller.signal.aborted) {
+                this.emit(callback_id, { Error: { message: String(error) } });
+            }
+        }).finally(() => {
+            if (this.controllers.get(callback_id) === controller) {
+                this.controllers.delete(callback_id);
+            }
+        });
+    }
+
+    private async run(callback_id: CallbackId, request: FetchRequestType, controller: AbortController): Promise<void> {
+        const response = await fetch(request.url, {
+            method: request.method,
+            headers: getHeaders(request.headers),
+            body: getBody(request.body, this.files),
+            signal: controller.signal,
+        });
+
+        this.emit(callback_id, {
+            Start: {
+                status: response.status,
+                headers: responseHeaders(response),
+            }
+        });
+
+        if (response.body !== null) {
+            const reader = response.body.getReader();
+
+            while (true) {
+                const { done, value } = await reader.read();
+
+                if (done) {
+                    break;
+                }
+
+                this.emit(callback_id, { Chunk: { data: value } });
+            }
+        }
+
+        this.emit(callback_id, 'End');
+    }
+
+    public abort(callback_id: CallbackId) {
+        const controller = this.controllers.get(callback_id);
+
+        if (controller !== undefined) {
+            this.controllers.delete(callback_id);
+            controller.abort();
+        }
+    }
+}
@@ -0,0 +1,31 @@
+// Files dropped or selected by the user, referenced by id from wasm (DropFileItem)
+export class Files {
+    private nextId: number = 1;
+    private readonly files: Map<number, File> = new Map();
+
+    public add(file: File): number {
+        const id = this.nextId;
+        this.nextId += 1;
+        this.files.set(id, file);
+        return id;
+    }
+
+    public get(id: number): File | undefined {
+        return this.files.get(id);
+    }
+
+    public release(id: number) {
+        this.files.delete(id);
+    }
+
+    public async read(id: number, start: number, end: number): Promise<Uint8Array> {
+        const file = this.files.get(id);
+
+        if (file === undefined) {
+            throw Error(`File not found: ${id}`);
+        }
+
+        const data = await file.slice(start, end).arrayBuffer();
+        return new Uint8Array(data);
+    }
+}
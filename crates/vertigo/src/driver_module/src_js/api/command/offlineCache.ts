@@ -0,0 +1,154 @@
+import { JsJsonType } from "../../jsjson";
+import { ModuleControllerType } from "../../wasm_init";
+import { ExportType } from "../../wasm_module";
+import { CallbackId } from "../types";
+import { FetchRequestType } from "./fetchExec";
+
+const DB_NAME = 'vertigo-offline-cache';
+const STORE_NAME = 'responses';
+// Oldest responses are evicted above this number of entries
+const MAX_ENTRIES = 500;
+
+// Responses of requests with RequestBuilder::offline_cache, kept in IndexedDB and keyed by the serialized request
+export class OfflineCache {
+    private db: Promise<IDBDatabase | null> | null = null;
+
+    constructor(private readonly getWasm: () => ModuleControllerType<ExportType>) {}
+
+    private open(): Promise<IDBDatabase | null> {
+        if (this.db === null) {
+            this.db = new Promise((resolve) => {
+                try {
+                    const request = window.indexedDB.open(DB_NAME, 1);
+                    let blocked = false;
+
+                    request.onupgradeneeded = () => {
+                        request.result.createObjectStore(STORE_NAME);
+                    };
+                    request.onsuccess = () => {
+                        const db = request.result;
+
+                        if (blocked) {
+                            // Already given up, next access opens the database again
+                            db.close();
+                            return;
+                        }
+
+                        // Don't block other tabs upgrading the database
+                        db.onversionchange = () => {
+                            db.close();
+                            this.db = null;
+                        };
+
+                        resolve(db);
+                    };
+                    request.onblocked = () => {
+                        // Other tab keeps an older version open, don't wait for it to be closed
+                        console.warn('OfflineCache: IndexedDB blocked by other tab');
+                        blocked = true;
+                        this.db = null;
+                        resolve(null);
+                    };
+                    request.onerror = () => {
+                        console.error('OfflineCache: unable to open IndexedDB', request.error);
+                        resolve(null);
+                    };
+                } catch (error) {
+                    // IndexedDB can be disabled (i.e. by privacy settings)
+                    console.error('OfflineCache: IndexedDB not available', error);
+                    resolve(null);
+                }
+            });
+        }
+
+        return this.db;
+    }
+
+    public get(request: FetchRequestType, callback_id: CallbackId) {
+        const respond = (value: JsJsonType) => {
+            this.getWasm().wasmCommand({
+                CallbackCall: {
+                    callback_id,
+                    value,
+                }
+            });
+        };
+
+        this.open().then((db) => {
+            if (db === null) {
+                respond(null);
+                return;
+            }
+
+            const query = db.transaction(STORE_NAME, 'readonly').objectStore(STORE_NAME).get(JSON.stringify(request));
+
+            query.onsuccess = () => respond(query.result ?? null);
+            query.onerror = () => {
+                console.error('OfflineCache: read error', query.error);
+                respond(null);
+            };
+        });
+    }
+
+    public set(request: FetchRequestType, entry: JsJsonType) {
+        this.open().then((db) => {
+            if (db === null) {
+                return;
+            }
+
+            const store = db.transaction(STORE_NAME, 'readwrite').objectStore(STORE_NAME);
+            const query = store.put(entry, JSON.stringify(request));
+
+            query.onsuccess = () => this.evict(store);
+            query.onerror = () => {
+                // i.e. quota exceeded
+                console.error('OfflineCache: write error', query.error);
+            };
+        });
+    }
+
+    public clear() {
+        this.open().then((db) => {
+            if (db === null) {
+                return;
+            }
+
+            const query = db.transaction(STORE_NAME, 'readwrite').objectStore(STORE_NAME).clear();
+
+            query.onerror = () => {
+                console.error('OfflineCache: clear error', query.error);
+            };
+        });
+    }
+
+    // Remove the oldest entries above MAX_ENTRIES
+    private evict(store: IDBObjectStore) {
+        const count = store.count();
+
+        count.onsuccess = () => {
+            const excess = count.result - MAX_ENTRIES;
+            if (excess <= 0) {
+                return;
+            }
+
+            const entries: Array<{ key: IDBValidKey, storedAt: number }> = [];
+            const cursor = store.openCursor();
+
+            cursor.onsuccess = () => {
+                const current = cursor.result;
+
+                if (current !== null) {
+                    entries.push({ key: current.key, storedAt: Number(current.value?.stored_at ?? 0) });
+                    current.continue();
+                    return;
+                }
+
+                entries.sort((a, b) => a.storedAt - b.storedAt);
+
+                for (const { key } of entries.slice(0, excess)) {
+                    store.delete(key);
+                }
+            };
+        };
+    }
+}
@@ -0,0 +1,94 @@
+import { JsJsonType } from "../../jsjson";
+import { ModuleControllerType } from "../../wasm_init";
+import { ExportType } from "../../wasm_module";
+import { CallbackId } from "../types";
+
+export type StorageKind = 'Local' | 'Session';
+
+interface StorageWatcher {
+    kind: StorageKind,
+    key: string,
+}
+
+// Value of the key, `stored` tells a missing key from a stored `null`
+type StoredValue = {
+    stored: boolean,
+    value: JsJsonType,
+};
+
+const getStorage = (kind: StorageKind): Storage | null => {
+    try {
+        return kind === 'Local' ? window.localStorage : window.sessionStorage;
+    } catch (error) {
+        // Storage can be disabled (i.e. by privacy settings)
+        console.error('Storage not available', error);
+        return null;
+    }
+};
+
+const parse = (key: string, value: string | null): StoredValue => {
+    if (value === null) {
+        return { stored: false, value: null };
+    }
+
+    try {
+        return { stored: true, value: JSON.parse(value) };
+    } catch (error) {
+        console.error(`Error deserializing storage value ${key}`, error);
+        return { stored: false, value: null };
+    }
+};
+
+// localStorage/sessionStorage access for PersistentValue, changes made in other tabs are sent to wasm callbacks
+export class Storages {
+    private readonly watchers: Map<CallbackId, StorageWatcher> = new Map();
+
+    constructor(private readonly getWasm: () => ModuleControllerType<ExportType>) {
+        window.addEventListener('storage', this.onStorage);
+    }
+
+    public get(kind: StorageKind, key: string): StoredValue {
+        return parse(key, getStorage(kind)?.getItem(key) ?? null);
+    }
+
+    public set(kind: StorageKind, key: string, value: JsJsonType) {
+        try {
+            getStorage(kind)?.setItem(key, JSON.stringify(value));
+        } catch (error) {
+            // i.e. quota exceeded
+            console.error(`Error saving storage value ${key}`, error);
+        }
+    }
+
+    public remove(kind: StorageKind, key: string) {
+        getStorage(kind)?.removeItem(key);
+    }
+
+    public watch(kind: StorageKind, key: string, callback: CallbackId) {
+        this.watchers.set(callback, { kind, key });
+    }
+
+    public unwatch(callback: CallbackId) {
+        this.watchers.delete(callback);
+    }
+
+    private onStorage = (event: StorageEvent) => {
+        for (const [callback_id, watcher] of Array.from(this.watchers.entries())) {
+            if (event.storageArea !== getStorage(watcher.kind)) {
+                continue;
+            }
+
+            // Key is null when the whole storage was cleared
+            if (event.key !== null && event.key !== watcher.key) {
+                continue;
+            }
+
+            this.getWasm().wasmCommand({
+                CallbackCall: {
+                    callback_id,
+                    value: parse(watcher.key, event.key === null ? null : event.newValue),
+                }
+            });
+        }
+    }
+}
@@ -1,3 +1,5 @@
+import { JsJsonType } from "../jsjson";
+
 export class Metadata {
     private readonly metadata: HTMLElement;
 
@@ -24,6 +26,21 @@ export class Metadata {
         return this.get('data-fetch-cache') ?? null;
     }
 
+    getSsrContext(): JsJsonType {
+        const value = this.get('data-ssr-context');
+
+        if (value === null) {
+            return null;
+        }
+
+        try {
+            return JSON.parse(value);
+        } catch (e) {
+            console.error('Error parsing SSR context', e);
+            return null;
+        }
+    }
+
     getEnabledHydration = (): boolean => {
         const value = this.get('data-env-disable-hydration');
         return value !== 'true';
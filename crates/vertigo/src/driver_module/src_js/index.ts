@@ -1,4 +1,5 @@
 import { WasmModule } from "./wasm_module";
+import { applyStreamPatches } from "./api/command/dom/streamPatches";
 
 // vertigo-cli compatibility version, change together with package version.
 const VERTIGO_COMPAT_VERSION_MAJOR = 0;
@@ -19,6 +20,9 @@ const runModule = async (wasm: string) => {
 
     moduleRun.add(wasm);
 
+    // The page is loaded, so all parts streamed by SSR are in place before hydration
+    applyStreamPatches(document.body);
+
     console.info(`Wasm module: "${wasm}" -> start`);
     const wasmModule = await WasmModule.create(wasm);
     console.info(`Wasm module: "${wasm}" -> initialized`);
@@ -1,8 +1,10 @@
 use crate::{
-    DomNode, JsJson,
+    DomNode, JsJsonSerialize, ServerRequest, ServerResponse,
     dev::LongPtr,
     driver_module::{
-        api::{api_arguments, api_command_wasm, api_fetch_cache, api_server_handler},
+        api::{
+            api_arguments, api_command_wasm, api_fetch_cache, api_server_handler, api_ssr_context,
+        },
         driver::get_driver,
         get_driver_dom,
         init_env::init_env,
@@ -14,25 +16,33 @@ pub fn start_app(init_app: fn() -> DomNode) {
     init_env();
 
     api_fetch_cache().init_cache();
+    api_ssr_context().init();
 
     let root_view = init_app();
 
     get_driver().set_root(root_view);
 
     get_driver_dom().flush_dom_changes();
+
+    api_ssr_context().finish_first_render();
 }
 
 #[doc(hidden)]
 #[unsafe(no_mangle)]
-pub fn vertigo_export_handle_url(url_ptr: u64) -> u64 {
-    let url_ptr = LongPtr::from(url_ptr);
-    let url = api_arguments().get_by_long_ptr(url_ptr);
+pub fn vertigo_export_handle_url(request_ptr: u64) -> u64 {
+    let request_ptr = LongPtr::from(request_ptr);
+    let request = api_arguments().get_by_long_ptr(request_ptr);
 
-    let JsJson::String(url) = url else {
-        panic!("string expected");
+    let response = match ServerRequest::from_payload(request) {
+        Ok(request) => api_server_handler().handler(request),
+        Err(err) => {
+            log::error!("vertigo_export_handle_url: invalid request: {err}");
+            ServerResponse::text("Invalid request")
+                .status(500)
+                .to_json()
+        }
     };
 
-    let response = api_server_handler().handler(&url);
     response.to_ptr_long().get_long_ptr()
 }
 
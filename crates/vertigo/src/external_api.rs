@@ -36,6 +36,27 @@ pub mod safe_wrappers {
         driver_module::api::api_arguments,
     };
 
+    #[cfg(test)]
+    type CommandMock = dyn Fn(&CommandForBrowser) -> Option<JsJson>;
+
+    #[cfg(test)]
+    thread_local! {
+        static COMMAND_MOCK: std::cell::RefCell<Option<std::rc::Rc<CommandMock>>> =
+            const { std::cell::RefCell::new(None) };
+    }
+
+    /// Respond to (or just inspect) commands sent to the browser, `None` falls back to the default response
+    #[cfg(test)]
+    pub fn set_command_mock(mock: impl Fn(&CommandForBrowser) -> Option<JsJson> + 'static) {
+        COMMAND_MOCK.with(|current| *current.borrow_mut() = Some(std::rc::Rc::new(mock)));
+    }
+
+    #[cfg(test)]
+    fn mocked_response(command: &CommandForBrowser) -> Option<JsJson> {
+        let mock = COMMAND_MOCK.with(|current| current.borrow().clone())?;
+        mock(command)
+    }
+
     pub fn safe_panic_message(_long_ptr: LongPtr) {}
 
     pub fn safe_dom_access(long_ptr: LongPtr) -> LongPtr {
@@ -45,6 +66,11 @@ pub mod safe_wrappers {
 
         match command_res {
             Ok(command) => {
+                #[cfg(test)]
+                if let Some(response) = mocked_response(&command) {
+                    return response.to_ptr_long();
+                }
+
                 let response = match command {
                     CommandForBrowser::FetchCacheGet => {
                         browser_response::FetchCacheGet { data: None }.to_json()
@@ -69,9 +95,29 @@ pub mod safe_wrappers {
                         }
                         .to_json()
                     }
+                    CommandForBrowser::StorageGet { .. } => browser_response::StorageGet {
+                        stored: false,
+                        value: JsJson::Null,
+                    }
+                    .to_json(),
                     CommandForBrowser::GetEnv { name: _ } => {
                         browser_response::GetEnv { value: None }.to_json()
                     }
+                    CommandForBrowser::RequestHeaderGet { name: _ } => {
+                        browser_response::RequestHeaderGet { value: None }.to_json()
+                    }
+                    CommandForBrowser::SsrContextGet => {
+                        browser_response::SsrContextGet { value: None }.to_json()
+                    }
+                    CommandForBrowser::SsrTimeLeftGet => {
+                        browser_response::SsrTimeLeftGet { value: None }.to_json()
+                    }
+                    CommandForBrowser::RequestMethodGet => {
+                        browser_response::RequestMethodGet { value: None }.to_json()
+                    }
+                    CommandForBrowser::RequestBodyGet => {
+                        browser_response::RequestBodyGet { value: None }.to_json()
+                    }
                     CommandForBrowser::TimezoneOffset => {
                         browser_response::TimezoneOffset { value: 0 }.to_json()
                     }
@@ -79,7 +125,14 @@ pub mod safe_wrappers {
                         browser_response::GetRandom { value: min }.to_json()
                     }
                     CommandForBrowser::FetchExec { .. }
+                    | CommandForBrowser::FetchAbort { .. }
+                    | CommandForBrowser::FetchStream { .. }
+                    | CommandForBrowser::FetchStreamAbort { .. }
                     | CommandForBrowser::SetStatus { .. }
+                    | CommandForBrowser::SetResponseHeader { .. }
+                    | CommandForBrowser::Redirect { .. }
+                    | CommandForBrowser::SetSsrTimeout { .. }
+                    | CommandForBrowser::ServerHandlerResponse { .. }
                     | CommandForBrowser::WebsocketRegister { .. }
                     | CommandForBrowser::WebsocketUnregister { .. }
                     | CommandForBrowser::WebsocketSendMessage { .. }
@@ -89,9 +142,20 @@ pub mod safe_wrappers {
                     | CommandForBrowser::LocationSet { .. }
                     | CommandForBrowser::CookieSet { .. }
                     | CommandForBrowser::CookieJsonSet { .. }
+                    | CommandForBrowser::StorageSet { .. }
+                    | CommandForBrowser::StorageRemove { .. }
+                    | CommandForBrowser::StorageWatch { .. }
+                    | CommandForBrowser::StorageUnwatch { .. }
+                    | CommandForBrowser::OfflineCacheGet { .. }
+                    | CommandForBrowser::OfflineCacheSet { .. }
+                    | CommandForBrowser::OfflineCacheClear
                     | CommandForBrowser::Log { .. }
                     | CommandForBrowser::HistoryBack
                     | CommandForBrowser::JsApiCall { .. }
+                    | CommandForBrowser::JsApiCallAsync { .. }
+                    | CommandForBrowser::JsCallbackRelease { .. }
+                    | CommandForBrowser::FileRead { .. }
+                    | CommandForBrowser::FileRelease { .. }
                     | CommandForBrowser::DomBulkUpdate { .. } => JsJson::Null,
                 };
 
@@ -0,0 +1,104 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    computed::{
+        DropResource,
+        struct_mut::{HashMapMut, ValueMut},
+    },
+    dev::{CallbackId, FutureBox, FutureBoxSend},
+};
+
+#[derive(Default)]
+struct AbortInner {
+    aborted: ValueMut<bool>,
+    listeners: HashMapMut<CallbackId, FutureBoxSend<()>>,
+}
+
+/// Signal passed to requests (see [RequestBuilder::abort_signal](crate::RequestBuilder::abort_signal)),
+/// aborted by its [AbortHandle].
+#[derive(Clone, Default)]
+pub struct AbortSignal {
+    inner: Rc<AbortInner>,
+}
+
+impl AbortSignal {
+    pub fn is_aborted(&self) -> bool {
+        self.inner.aborted.get()
+    }
+
+    /// Resolves once the signal is aborted
+    pub(crate) async fn wait(&self) {
+        if self.is_aborted() {
+            return;
+        }
+
+        let (sender, receiver) = FutureBox::new();
+        let id = CallbackId::new();
+        self.inner.listeners.insert(id, sender);
+
+        let _listener = DropResource::new({
+            let inner = self.inner.clone();
+            move || {
+                inner.listeners.remove(&id);
+            }
+        });
+
+        receiver.await
+    }
+
+    fn abort(&self) {
+        if self.inner.aborted.set_if_changed(true) {
+            for (_, listener) in self.inner.listeners.mem_replace(HashMap::new()) {
+                listener.publish(());
+            }
+        }
+    }
+}
+
+/// Allows to abort requests in flight, i.e. stale search requests when the user keeps typing.
+///
+/// Requests are aborted by calling [AbortHandle::abort] or by dropping the handle,
+/// so keeping the handle next to the data which needs the response is enough.
+///
+/// ```rust
+/// use vertigo::{AbortHandle, RequestBuilder, get_driver};
+///
+/// let handle = AbortHandle::new();
+///
+/// let request = RequestBuilder::get("/api/search?q=vertigo").abort_signal(handle.signal());
+///
+/// get_driver().spawn(async move {
+///     // Err("Request aborted") if the handle is dropped before the response arrives
+///     let _result = request.call().await.into_data::<Vec<String>>();
+/// });
+///
+/// drop(handle);
+/// ```
+#[derive(Default)]
+pub struct AbortHandle {
+    signal: AbortSignal,
+}
+
+impl AbortHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn signal(&self) -> AbortSignal {
+        self.signal.clone()
+    }
+
+    pub fn abort(&self) {
+        self.signal.abort();
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.signal.is_aborted()
+    }
+}
+
+impl Drop for AbortHandle {
+    fn drop(&mut self) {
+        self.signal.abort();
+    }
+}
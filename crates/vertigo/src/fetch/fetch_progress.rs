@@ -0,0 +1,63 @@
+use vertigo_macro::AutoJsJson;
+
+/// Progress of a request (see [RequestBuilder::on_progress](crate::RequestBuilder::on_progress)).
+///
+/// Totals are `None` if not known (i.e. response without `Content-Length`).
+#[derive(AutoJsJson, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FetchProgress {
+    /// Bytes of the request body sent
+    pub sent: u64,
+    pub sent_total: Option<u64>,
+    /// Bytes of the response body received
+    pub received: u64,
+    pub received_total: Option<u64>,
+}
+
+impl FetchProgress {
+    /// Part of the request body sent (from 0.0 to 1.0)
+    pub fn upload_ratio(&self) -> Option<f64> {
+        ratio(self.sent, self.sent_total)
+    }
+
+    /// Part of the response body received (from 0.0 to 1.0)
+    pub fn download_ratio(&self) -> Option<f64> {
+        ratio(self.received, self.received_total)
+    }
+}
+
+fn ratio(done: u64, total: Option<u64>) -> Option<f64> {
+    match total {
+        Some(0) => Some(1.0),
+        Some(total) => Some((done as f64 / total as f64).min(1.0)),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FetchProgress;
+
+    #[test]
+    fn test_progress_ratio() {
+        let progress = FetchProgress {
+            sent: 25,
+            sent_total: Some(100),
+            received: 10,
+            received_total: None,
+        };
+
+        assert_eq!(progress.upload_ratio(), Some(0.25));
+        assert_eq!(progress.download_ratio(), None);
+
+        // Empty body is complete, more data than declared is capped
+        let progress = FetchProgress {
+            sent: 0,
+            sent_total: Some(0),
+            received: 150,
+            received_total: Some(100),
+        };
+
+        assert_eq!(progress.upload_ratio(), Some(1.0));
+        assert_eq!(progress.download_ratio(), Some(1.0));
+    }
+}
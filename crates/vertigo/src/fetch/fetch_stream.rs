@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+
+use vertigo_macro::AutoJsJson;
+
+/// Event emitted by a streamed request (see [RequestBuilder::stream](crate::RequestBuilder::stream)).
+#[derive(AutoJsJson, Debug, Clone, PartialEq)]
+pub enum FetchStreamEvent {
+    /// Response status and headers (with lowercase names) were received
+    Start {
+        status: u32,
+        headers: BTreeMap<String, String>,
+    },
+    /// Next part of the response body
+    Chunk { data: Vec<u8> },
+    /// Whole response body was received
+    End,
+    /// Network error, no more events will be emitted
+    Error { message: String },
+}
+
+/// Splits streamed chunks into lines, handy for NDJSON or SSE-like responses.
+///
+/// Lines are returned without line endings (`\n` or `\r\n`), invalid UTF-8 is replaced.
+#[derive(Debug, Default)]
+pub struct LineDecoder {
+    buffer: Vec<u8>,
+}
+
+impl LineDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add next chunk of data, returns lines completed by this chunk.
+    pub fn push(&mut self, data: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(data);
+
+        let mut lines = Vec::new();
+
+        while let Some(position) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let mut line = self.buffer.drain(..=position).collect::<Vec<_>>();
+            line.pop();
+
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+
+            lines.push(String::from_utf8_lossy(&line).into_owned());
+        }
+
+        lines
+    }
+
+    /// Take the last line if the stream didn't end with a line ending.
+    pub fn finish(&mut self) -> Option<String> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        let line = std::mem::take(&mut self.buffer);
+        Some(String::from_utf8_lossy(&line).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineDecoder;
+
+    #[test]
+    fn test_line_decoder() {
+        let mut decoder = LineDecoder::new();
+
+        assert_eq!(decoder.push(b"{\"a\":1}\n{\"a\""), vec!["{\"a\":1}"]);
+        assert_eq!(decoder.push(b":2}\r\n\n{\"a\":3"), vec!["{\"a\":2}", ""]);
+        assert_eq!(decoder.finish(), Some("{\"a\":3".to_string()));
+        assert_eq!(decoder.finish(), None);
+    }
+}
@@ -2,10 +2,17 @@ use std::fmt::Debug;
 use std::rc::Rc;
 
 use crate::{
-    Computed, DomNode, DropResource, JsJsonDeserialize, RequestResponse, Resource, ToComputed,
+    AbortHandle, Computed, DomNode, DropResource, Instant, JsJsonDeserialize, RequestResponse,
+    Resource, ToComputed,
     computed::{ValueSynchronize, context::Context, struct_mut::ValueMut},
-    driver_module::api::{api_fetch, api_fetch_cache},
-    fetch::{api_response::ApiResponse, cache_value::CacheValue},
+    dev::{SsrFetchRequest, SsrFetchResponse},
+    driver_module::api::api_fetch_cache,
+    fetch::{
+        api_response::ApiResponse,
+        cache_value::CacheValue,
+        offline_cache::{offline_cache_load, offline_cache_store, offline_keep_stale},
+        request_builder::race,
+    },
     get_driver, transaction,
 };
 
@@ -55,12 +62,16 @@ fn get_unique_id() -> u64 {
 /// ```
 ///
 /// See ["todo" example](../src/vertigo_demo/app/todo/state.rs.html) in vertigo-demo package for more.
+///
+/// Request in flight is aborted when the last clone of the cache is dropped.
 pub struct LazyCache<T: PartialEq + 'static> {
     id: u64,
     value: CacheValue<T>,
     queued: Rc<ValueMut<bool>>,
     request: Rc<RequestBuilder>,
     map_response: Rc<dyn Fn(u32, RequestBody) -> MapResponse<T>>,
+    /// Shared by the clones, `None` in the copy used by the update task
+    abort: Option<Rc<AbortHandle>>,
 }
 
 impl<T: PartialEq + 'static> Debug for LazyCache<T> {
@@ -79,6 +90,7 @@ impl<T: PartialEq> Clone for LazyCache<T> {
             queued: self.queued.clone(),
             request: self.request.clone(),
             map_response: self.map_response.clone(),
+            abort: self.abort.clone(),
         }
     }
 }
@@ -96,8 +108,10 @@ impl<T: PartialEq> LazyCache<T> {
 
             let ttl = request.get_ttl();
 
-            let ssr_request = request.to_request(None);
+            let ssr_request = request.clone().to_request(None);
             if let Some(response) = api_fetch_cache().get_response(&ssr_request) {
+                offline_cache_store(&request, &ssr_request, &response);
+
                 let response = RequestResponse::new(ssr_request, response);
 
                 let new_value = response.into(map_response.as_ref());
@@ -121,6 +135,7 @@ impl<T: PartialEq> LazyCache<T> {
             queued: Rc::new(ValueMut::new(false)),
             request: Rc::new(request),
             map_response,
+            abort: Some(Rc::new(AbortHandle::new())),
         }
     }
 }
@@ -190,7 +205,13 @@ impl<T: PartialEq> LazyCache<T> {
 
         self.queued.set(true); //set lock
 
-        let self_clone = self.clone();
+        let signal = self.abort.as_ref().map(|abort| abort.signal());
+
+        // The task doesn't hold the abort handle, so dropping the cache aborts the request
+        let self_clone = LazyCache {
+            abort: None,
+            ..self.clone()
+        };
 
         get_driver().spawn(async move {
             if !self_clone.queued.get() {
@@ -201,10 +222,6 @@ impl<T: PartialEq> LazyCache<T> {
             let api_response = transaction(|context| self_clone.value.get(context));
 
             if force || api_response.needs_update() {
-                if with_loading {
-                    self_clone.value.set(ApiResponse::new_loading());
-                }
-
                 let request = transaction(|context| {
                     self_clone
                         .request
@@ -213,27 +230,79 @@ impl<T: PartialEq> LazyCache<T> {
                         .to_request_context(context)
                 });
 
-                let result = api_fetch().fetch(request.clone()).await;
-                let new_value =
-                    RequestResponse::new(request, result).into(self_clone.map_response.as_ref());
+                let mut restored = false;
 
-                let new_value = match new_value {
-                    Ok(value) => Resource::Ready(Rc::new(value)),
-                    Err(message) => Resource::Error(message),
+                if let ApiResponse::Uninitialized = api_response
+                    && let Some(offline) = offline_cache_load(&self_clone.request, &request).await
+                {
+                    let fresh = offline.is_fresh();
+                    self_clone.set_response(request.clone(), offline.response, offline.expiry);
+                    restored = self_clone.is_ready();
+
+                    if restored && fresh && !force {
+                        self_clone.queued.set(false);
+                        return;
+                    }
+                }
+
+                if with_loading && !restored {
+                    self_clone.value.set(ApiResponse::new_loading());
+                }
+
+                let aborted = async {
+                    match &signal {
+                        Some(signal) => signal.wait().await,
+                        None => std::future::pending().await,
+                    }
+                    None
                 };
 
-                let expiry = self_clone
-                    .request
-                    .get_ttl()
-                    .map(|ttl| get_driver().now().add_duration(ttl));
+                let sent = async { Some(self_clone.request.send(request.clone()).await) };
+
+                let Some(result) = race(aborted, sent).await else {
+                    self_clone.queued.set(false);
+                    return;
+                };
+
+                offline_cache_store(&self_clone.request, &request, &result);
+
+                let keep_stale =
+                    offline_keep_stale(&self_clone.request, &result) && self_clone.is_ready();
 
-                self_clone.value.set(ApiResponse::new(new_value, expiry));
+                if !keep_stale {
+                    let expiry = self_clone
+                        .request
+                        .get_ttl()
+                        .map(|ttl| get_driver().now().add_duration(ttl));
+
+                    self_clone.set_response(request, result, expiry);
+                }
             }
 
             self_clone.queued.set(false);
         });
     }
 
+    fn is_ready(&self) -> bool {
+        transaction(|context| matches!(self.value.get(context).get_value(), Resource::Ready(_)))
+    }
+
+    fn set_response(
+        &self,
+        request: SsrFetchRequest,
+        response: SsrFetchResponse,
+        expiry: Option<Instant>,
+    ) {
+        let new_value = RequestResponse::new(request, response).into(self.map_response.as_ref());
+
+        let new_value = match new_value {
+            Ok(value) => Resource::Ready(Rc::new(value)),
+            Err(message) => Resource::Error(message),
+        };
+
+        self.value.set(ApiResponse::new(new_value, expiry));
+    }
+
     /// Mirror this cache into a derived, self-updating structure `R`.
     ///
     /// Like [`Value::synchronize`](crate::Value::synchronize), but the source is
@@ -335,9 +404,11 @@ impl<T: PartialEq + JsJsonDeserialize> LazyCache<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::dev::{SsrFetchResponse, SsrFetchResponseContent};
-    use crate::driver_module::api::api_timers;
+    use crate::dev::{OfflineCacheEntry, SsrFetchResponse, SsrFetchResponseContent};
+    use crate::driver_module::api::{api_fetch, api_timers};
+    use crate::fetch::offline_cache::MockOffline;
     use crate::{JsJson, Value};
+    use std::collections::BTreeMap;
 
     #[tokio::test]
     async fn test_lazy_cache_optimistically_set() {
@@ -452,14 +523,7 @@ mod tests {
         let local = tokio::task::LocalSet::new();
         local
             .run_until(async {
-                // Mock timers to execute 0ms timeouts in next tick
-                api_timers().set_mock_handler(|duration, callback_id, _kind| {
-                    if duration == 0 {
-                        tokio::task::spawn_local(async move {
-                            api_timers().callback_timeout(callback_id);
-                        });
-                    }
-                });
+                api_timers().mock_zero_timeouts();
 
                 let token = Value::new(Some("token1".to_string()));
 
@@ -487,6 +551,7 @@ mod tests {
                             .unwrap_or_default();
                         SsrFetchResponse::Ok {
                             status: 200,
+                            headers: BTreeMap::new(),
                             response: SsrFetchResponseContent::Json(JsJson::String(format!(
                                 "response for {}",
                                 auth
@@ -546,14 +611,7 @@ mod tests {
         let local = tokio::task::LocalSet::new();
         local
             .run_until(async {
-                // Mock timers to execute 0ms timeouts in next tick
-                api_timers().set_mock_handler(|duration, callback_id, _kind| {
-                    if duration == 0 {
-                        tokio::task::spawn_local(async move {
-                            api_timers().callback_timeout(callback_id);
-                        });
-                    }
-                });
+                api_timers().mock_zero_timeouts();
 
                 let token_val = Value::new(Some("token1".to_string()));
                 let token_computed = token_val.to_computed();
@@ -573,6 +631,7 @@ mod tests {
                             .unwrap_or_default();
                         SsrFetchResponse::Ok {
                             status: 200,
+                            headers: BTreeMap::new(),
                             response: SsrFetchResponseContent::Json(JsJson::String(format!(
                                 "resp:{}",
                                 auth
@@ -633,4 +692,164 @@ mod tests {
             })
             .await;
     }
+
+    #[tokio::test]
+    async fn test_lazy_cache_drop_aborts_request() {
+        use crate::dev::command::CommandForBrowser;
+        use crate::external_api::safe_wrappers::set_command_mock;
+
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                api_timers().mock_zero_timeouts();
+
+                let started = Rc::new(ValueMut::new(None));
+                let aborted = Rc::new(ValueMut::new(None));
+
+                set_command_mock({
+                    let started = started.clone();
+                    let aborted = aborted.clone();
+                    move |command| {
+                        match command {
+                            CommandForBrowser::FetchExec { callback, .. } => {
+                                started.set(Some(*callback));
+                            }
+                            CommandForBrowser::FetchAbort { callback } => {
+                                aborted.set(Some(*callback));
+                            }
+                            _ => {}
+                        }
+                        None
+                    }
+                });
+
+                let cache =
+                    RequestBuilder::get("https://test.com/api").lazy_cache(|status, body| {
+                        if status == 200 {
+                            Some(body.into::<String>())
+                        } else {
+                            None
+                        }
+                    });
+
+                cache.force_update(true);
+                tokio::task::yield_now().await;
+
+                let Some(callback) = started.get() else {
+                    panic!("Request was not sent");
+                };
+                assert_eq!(aborted.get(), None);
+
+                drop(cache);
+                tokio::task::yield_now().await;
+
+                assert_eq!(aborted.get(), Some(callback));
+            })
+            .await;
+    }
+
+    fn text_response(text: &str) -> SsrFetchResponse {
+        SsrFetchResponse::Ok {
+            status: 200,
+            headers: BTreeMap::new(),
+            response: SsrFetchResponseContent::Json(JsJson::String(text.into())),
+        }
+    }
+
+    fn stored_entry(text: &str) -> Option<OfflineCacheEntry> {
+        Some(OfflineCacheEntry {
+            stored_at: 0,
+            response: text_response(text),
+        })
+    }
+
+    fn text_cache(request: RequestBuilder) -> LazyCache<String> {
+        request.offline_cache().lazy_cache(|status, body| {
+            if status == 200 {
+                Some(body.into::<String>())
+            } else {
+                None
+            }
+        })
+    }
+
+    fn cached_value(cache: &LazyCache<String>) -> Resource<String> {
+        transaction(|context| cache.value.get(context).get_value()).map(|value| (*value).clone())
+    }
+
+    #[tokio::test]
+    async fn test_lazy_cache_offline_restore() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let mock = MockOffline::new(stored_entry("offline"), text_response("network"));
+
+                // Mocked clock returns 0, so the stored response is younger than ttl
+                let cache = text_cache(RequestBuilder::get("https://test.com/api").ttl_seconds(60));
+                cache.update(false, false);
+                MockOffline::settle().await;
+
+                assert_eq!(cached_value(&cache), Resource::Ready("offline".to_string()));
+                assert_eq!(mock.fetches.get(), 0);
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_lazy_cache_offline_revalidate() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let mock = MockOffline::new(stored_entry("offline"), text_response("network"));
+
+                let cache = text_cache(RequestBuilder::get("https://test.com/api"));
+                cache.update(false, false);
+                MockOffline::settle().await;
+
+                assert_eq!(cached_value(&cache), Resource::Ready("network".to_string()));
+                assert_eq!(mock.fetches.get(), 1);
+                assert_eq!(mock.stored.get(), vec![200]);
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_lazy_cache_offline_stale_on_error() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let network = SsrFetchResponse::Err {
+                    message: "Offline".into(),
+                };
+                let mock = MockOffline::new(stored_entry("offline"), network);
+
+                let cache = text_cache(RequestBuilder::get("https://test.com/api"));
+                cache.update(false, false);
+                MockOffline::settle().await;
+
+                assert_eq!(cached_value(&cache), Resource::Ready("offline".to_string()));
+                assert_eq!(mock.fetches.get(), 1);
+                assert_eq!(mock.stored.get(), Vec::<u32>::new());
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_lazy_cache_offline_error_without_stored() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let network = SsrFetchResponse::Err {
+                    message: "Offline".into(),
+                };
+                let _mock = MockOffline::new(None, network);
+
+                let cache = text_cache(RequestBuilder::get("https://test.com/api"));
+                cache.update(false, false);
+                MockOffline::settle().await;
+
+                assert_eq!(cached_value(&cache), Resource::Error("Offline".to_string()));
+            })
+            .await;
+    }
 }
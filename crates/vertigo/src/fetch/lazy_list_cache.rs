@@ -6,8 +6,11 @@ use crate::{
         context::Context,
         struct_mut::{HashMapMut, ValueMut},
     },
-    driver_module::api::api_fetch,
-    fetch::request_builder::{RequestBody, RequestBuilder},
+    dev::{SsrFetchRequest, SsrFetchResponse},
+    fetch::{
+        offline_cache::{offline_cache_load, offline_cache_store, offline_keep_stale},
+        request_builder::{RequestBody, RequestBuilder},
+    },
     get_driver,
     render::collection::CollectionKey,
     transaction,
@@ -249,8 +252,9 @@ impl<T: CollectionKey> LazyListCache<T> {
 
         let self_clone = self.clone();
         get_driver().spawn(async move {
-            let request = transaction(|context| req_fn(&key).to_request_context(context));
-            let result = api_fetch().fetch(request.clone()).await;
+            let builder = req_fn(&key);
+            let request = transaction(|context| builder.clone().to_request_context(context));
+            let result = builder.send(request.clone()).await;
             let new_value = RequestResponse::new(request, result).into(resp_fn.as_ref());
 
             if let Ok(item) = new_value {
@@ -621,8 +625,6 @@ impl<T: CollectionKey> LazyListCache<T> {
             let needs_fetch = force || state == ListCacheState::Uninitialized;
 
             if needs_fetch {
-                self_clone.state.set(ListCacheState::Loading);
-
                 let request = transaction(|context| {
                     self_clone
                         .list_request
@@ -631,25 +633,59 @@ impl<T: CollectionKey> LazyListCache<T> {
                         .to_request_context(context)
                 });
 
-                let result = api_fetch().fetch(request.clone()).await;
-                let new_value = RequestResponse::new(request, result)
-                    .into(self_clone.list_map_response.as_ref());
+                let has_data = state == ListCacheState::Ready;
+                let mut restored = false;
 
-                match new_value {
-                    Ok(items_vec) => {
-                        self_clone.apply_response(items_vec);
-                        self_clone.state.set(ListCacheState::Ready);
-                    }
-                    Err(msg) => {
-                        self_clone.state.set(ListCacheState::Error(msg));
+                if state == ListCacheState::Uninitialized
+                    && let Some(offline) =
+                        offline_cache_load(&self_clone.list_request, &request).await
+                {
+                    let fresh = offline.is_fresh();
+                    self_clone.set_response(request.clone(), offline.response);
+                    restored =
+                        transaction(|ctx| self_clone.state.get(ctx)) == ListCacheState::Ready;
+
+                    if restored && fresh && !force {
+                        self_clone.list_queued.set(false);
+                        return;
                     }
                 }
+
+                if !restored {
+                    self_clone.state.set(ListCacheState::Loading);
+                }
+
+                let result = self_clone.list_request.send(request.clone()).await;
+
+                offline_cache_store(&self_clone.list_request, &request, &result);
+
+                if (has_data || restored) && offline_keep_stale(&self_clone.list_request, &result) {
+                    // Items from the previous response are still in place
+                    self_clone.state.set(ListCacheState::Ready);
+                } else {
+                    self_clone.set_response(request, result);
+                }
             }
 
             self_clone.list_queued.set(false);
         });
     }
 
+    fn set_response(&self, request: SsrFetchRequest, response: SsrFetchResponse) {
+        let new_value =
+            RequestResponse::new(request, response).into(self.list_map_response.as_ref());
+
+        match new_value {
+            Ok(items_vec) => {
+                self.apply_response(items_vec);
+                self.state.set(ListCacheState::Ready);
+            }
+            Err(msg) => {
+                self.state.set(ListCacheState::Error(msg));
+            }
+        }
+    }
+
     fn apply_response(&self, new_items: Vec<T::Value>) {
         let new_keys: Vec<T::Key> = new_items.iter().map(T::get_key).collect();
         let new_key_set: HashSet<T::Key> = new_keys.iter().cloned().collect();
@@ -700,8 +736,15 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use super::*;
-    use crate::{Resource, transaction};
+    use crate::{
+        JsJson, Resource,
+        dev::{OfflineCacheEntry, SsrFetchResponseContent},
+        fetch::offline_cache::MockOffline,
+        transaction,
+    };
 
     #[derive(Clone, PartialEq, Debug)]
     struct Item {
@@ -1438,4 +1481,116 @@ mod tests {
         assert_eq!(cache, cache.clone());
         assert_ne!(cache, make_cache());
     }
+
+    // --- offline cache ---
+
+    struct NameKey;
+
+    impl CollectionKey for NameKey {
+        type Key = String;
+        type Value = String;
+
+        fn get_key(val: &String) -> String {
+            val.clone()
+        }
+    }
+
+    fn names_response(names: &[&str]) -> SsrFetchResponse {
+        let names = names.iter().map(|name| JsJson::String(name.to_string()));
+
+        SsrFetchResponse::Ok {
+            status: 200,
+            headers: BTreeMap::new(),
+            response: SsrFetchResponseContent::Json(JsJson::List(names.collect())),
+        }
+    }
+
+    fn stored_names(names: &[&str]) -> Option<OfflineCacheEntry> {
+        Some(OfflineCacheEntry {
+            stored_at: 0,
+            response: names_response(names),
+        })
+    }
+
+    fn names_cache(request: RequestBuilder) -> LazyListCache<NameKey> {
+        LazyListCache::new(request.offline_cache(), |status, body| {
+            if status == 200 {
+                Some(body.into::<Vec<String>>())
+            } else {
+                None
+            }
+        })
+    }
+
+    fn names(cache: &LazyListCache<NameKey>) -> Vec<String> {
+        transaction(|ctx| match cache.get(ctx) {
+            Resource::Ready(names) => names.as_ref().clone(),
+            other => panic!("expected Ready, got {:?}", other),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_offline_restore() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let mock = MockOffline::new(stored_names(&["Ann"]), names_response(&["Bob"]));
+
+                // Mocked clock returns 0, so the stored response is younger than ttl
+                let cache =
+                    names_cache(RequestBuilder::get("https://test.example/names").ttl_seconds(60));
+                cache.update(false, false);
+                MockOffline::settle().await;
+
+                assert_eq!(names(&cache), vec!["Ann"]);
+                assert_eq!(mock.fetches.get(), 0);
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_offline_revalidate() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let mock = MockOffline::new(stored_names(&["Ann"]), names_response(&["Bob"]));
+
+                let cache = names_cache(RequestBuilder::get("https://test.example/names"));
+                cache.update(false, false);
+                MockOffline::settle().await;
+
+                assert_eq!(names(&cache), vec!["Bob"]);
+                assert_eq!(mock.fetches.get(), 1);
+                assert_eq!(mock.stored.get(), vec![200]);
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_offline_stale_on_error() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let network = SsrFetchResponse::Err {
+                    message: "Offline".into(),
+                };
+                let mock = MockOffline::new(stored_names(&["Ann"]), network);
+
+                let cache = names_cache(RequestBuilder::get("https://test.example/names"));
+                cache.update(false, false);
+                MockOffline::settle().await;
+
+                assert_eq!(names(&cache), vec!["Ann"]);
+                assert_eq!(mock.fetches.get(), 1);
+                assert_eq!(mock.stored.get(), Vec::<u32>::new());
+
+                // Data already shown is kept when refreshing fails
+                cache.force_update(false);
+                MockOffline::settle().await;
+
+                assert_eq!(names(&cache), vec!["Ann"]);
+                assert_eq!(mock.fetches.get(), 2);
+            })
+            .await;
+    }
 }
@@ -1,6 +1,11 @@
+pub mod abort_handle;
 mod api_response;
 pub mod cache_value;
+pub mod fetch_progress;
+pub mod fetch_stream;
 pub mod lazy_cache;
 pub mod lazy_list_cache;
+mod offline_cache;
 pub mod request_builder;
 pub mod resource;
+pub mod retry_policy;
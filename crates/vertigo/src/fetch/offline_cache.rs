@@ -0,0 +1,197 @@
+use crate::{
+    Instant, JsJson, RequestBuilder,
+    dev::{FutureBox, OfflineCacheEntry, SsrFetchRequest, SsrFetchResponse},
+    driver_module::api::{api_browser_command, api_callbacks},
+    from_json, get_driver,
+};
+
+/// Response restored from IndexedDB (see [RequestBuilder::offline_cache])
+pub(crate) struct OfflineResponse {
+    pub response: SsrFetchResponse,
+    /// When the response becomes stale (according to the ttl of the request)
+    pub expiry: Option<Instant>,
+}
+
+impl OfflineResponse {
+    fn new(entry: OfflineCacheEntry, builder: &RequestBuilder) -> Self {
+        let stored_at = Instant {
+            instant: entry.stored_at,
+        };
+
+        Self {
+            response: entry.response,
+            expiry: builder.get_ttl().map(|ttl| stored_at.add_duration(ttl)),
+        }
+    }
+
+    /// Younger than the ttl, so it doesn't need to be revalidated
+    pub fn is_fresh(&self) -> bool {
+        self.expiry
+            .as_ref()
+            .is_some_and(|expiry| !expiry.is_expire())
+    }
+}
+
+/// Read the stored response, if the request uses offline cache
+pub(crate) async fn offline_cache_load(
+    builder: &RequestBuilder,
+    request: &SsrFetchRequest,
+) -> Option<OfflineResponse> {
+    if !builder.get_offline_cache() || !get_driver().is_browser() {
+        return None;
+    }
+
+    let (sender, receiver) = FutureBox::new();
+
+    let (callback, _drop_callback) = api_callbacks().register(move |value| {
+        sender.publish(value);
+        JsJson::Null
+    });
+
+    api_browser_command().offline_cache_get(request.clone(), callback);
+
+    let value = receiver.await;
+
+    if let JsJson::Null = value {
+        return None;
+    }
+
+    from_json::<OfflineCacheEntry>(value)
+        .inspect_err(|err| log::error!("offline_cache_load -> decode error = {err}"))
+        .ok()
+        .map(|entry| OfflineResponse::new(entry, builder))
+}
+
+/// Store the response if it's successful and the request uses offline cache
+pub(crate) fn offline_cache_store(
+    builder: &RequestBuilder,
+    request: &SsrFetchRequest,
+    response: &SsrFetchResponse,
+) {
+    if !builder.get_offline_cache() || !response.is_success() || !get_driver().is_browser() {
+        return;
+    }
+
+    api_browser_command().offline_cache_set(
+        request.clone(),
+        OfflineCacheEntry {
+            stored_at: get_driver().now().instant,
+            response: response.clone(),
+        },
+    );
+}
+
+/// Network error shouldn't replace data already shown if the request uses offline cache
+pub(crate) fn offline_keep_stale(builder: &RequestBuilder, response: &SsrFetchResponse) -> bool {
+    builder.get_offline_cache() && matches!(response, SsrFetchResponse::Err { .. })
+}
+
+/// Browser with IndexedDB holding `stored` response and network answering with `network`
+#[cfg(test)]
+pub(crate) struct MockOffline {
+    /// Number of requests sent to the network
+    pub fetches: std::rc::Rc<crate::dev::ValueMut<u32>>,
+    /// Statuses of responses written to IndexedDB
+    pub stored: std::rc::Rc<crate::dev::ValueMut<Vec<u32>>>,
+}
+
+#[cfg(test)]
+impl MockOffline {
+    /// Must be called inside of a `LocalSet`
+    pub fn new(stored: Option<OfflineCacheEntry>, network: SsrFetchResponse) -> Self {
+        use crate::{
+            JsJsonSerialize,
+            dev::{
+                ValueMut,
+                command::{CommandForBrowser, CommandForWasm, browser_response},
+            },
+            driver_module::api::{api_command_wasm, api_fetch, api_timers},
+            external_api::safe_wrappers::set_command_mock,
+        };
+        use std::rc::Rc;
+
+        api_timers().mock_zero_timeouts();
+
+        let mock = Self {
+            fetches: Rc::new(ValueMut::new(0)),
+            stored: Rc::new(ValueMut::new(Vec::new())),
+        };
+
+        set_command_mock({
+            let stored_list = mock.stored.clone();
+            move |command| match command {
+                CommandForBrowser::IsBrowser => {
+                    Some(browser_response::IsBrowser { value: true }.to_json())
+                }
+                CommandForBrowser::OfflineCacheGet { callback, .. } => {
+                    let callback_id = *callback;
+                    let value = stored.clone().to_json();
+
+                    // IndexedDB responds asynchronously
+                    tokio::task::spawn_local(async move {
+                        api_command_wasm().command_from_js(
+                            CommandForWasm::CallbackCall { callback_id, value }.to_json(),
+                        );
+                    });
+                    Some(JsJson::Null)
+                }
+                CommandForBrowser::OfflineCacheSet { entry, .. } => {
+                    if let SsrFetchResponse::Ok { status, .. } = entry.response {
+                        stored_list.change(|list| list.push(status));
+                    }
+                    Some(JsJson::Null)
+                }
+                _ => None,
+            }
+        });
+
+        api_fetch().set_mock_handler({
+            let fetches = mock.fetches.clone();
+            move |_| {
+                fetches.change(|fetches| *fetches += 1);
+                network.clone()
+            }
+        });
+
+        mock
+    }
+
+    /// Let spawned tasks finish
+    pub async fn settle() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::OfflineResponse;
+    use crate::{
+        JsJson, RequestBuilder,
+        dev::{OfflineCacheEntry, SsrFetchResponse, SsrFetchResponseContent},
+    };
+
+    fn entry(stored_at: u64) -> OfflineCacheEntry {
+        OfflineCacheEntry {
+            stored_at,
+            response: SsrFetchResponse::Ok {
+                status: 200,
+                headers: BTreeMap::new(),
+                response: SsrFetchResponseContent::Json(JsJson::Null),
+            },
+        }
+    }
+
+    #[test]
+    fn test_offline_response_freshness() {
+        // Mocked clock always returns 0
+        let builder = RequestBuilder::get("/api").offline_cache();
+        assert!(!OfflineResponse::new(entry(0), &builder).is_fresh());
+
+        let builder = builder.ttl_seconds(60);
+        assert!(OfflineResponse::new(entry(0), &builder).is_fresh());
+    }
+}
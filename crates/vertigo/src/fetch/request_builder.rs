@@ -1,17 +1,33 @@
 use std::collections::BTreeMap;
+use std::future::{Future, poll_fn};
+use std::pin::pin;
+use std::rc::Rc;
+use std::task::Poll;
 use std::time::Duration;
 
 use crate::{
-    CollectionKey, Computed, Context, FetchMethod, JsJson, JsJsonDeserialize, JsJsonSerialize,
-    LazyCache, LazyListCache, Value,
-    dev::{SsrFetchRequest, SsrFetchRequestBody, SsrFetchResponse, SsrFetchResponseContent},
-    driver_module::api::api_fetch,
+    AbortSignal, CollectionKey, Computed, Context, DropFileItem, FetchMethod, FetchProgress,
+    FetchStreamEvent, JsJson, JsJsonDeserialize, JsJsonSerialize, LazyCache, LazyListCache,
+    RetryPolicy, Value,
+    computed::DropResource,
+    dev::{
+        FutureBox, SsrFetchRequest, SsrFetchRequestBody, SsrFetchResponse, SsrFetchResponseContent,
+        SsrFormField, SsrMultipartPart, SsrMultipartValue,
+    },
+    driver_module::api::{
+        api_browser_command, api_callbacks, api_fetch, api_fetch_interceptors, api_timers,
+    },
     from_json, transaction,
 };
 
 #[derive(Debug, Clone)]
 pub enum RequestBody {
     Json(JsJson),
+    File(DropFileItem),
+    Text(String),
+    Bytes { data: Vec<u8>, content_type: String },
+    FormUrlEncoded(Vec<(String, String)>),
+    Multipart(Multipart),
 }
 
 impl RequestBody {
@@ -21,10 +37,110 @@ impl RequestBody {
                 Ok(data) => Ok(data),
                 Err(err) => Err(err),
             },
+            RequestBody::Text(text) => from_json::<T>(JsJson::String(text)),
+            RequestBody::File(file) => Err(format!("Can't deserialize file {}", file.name)),
+            RequestBody::Bytes { .. } => Err("Can't deserialize binary data".to_string()),
+            RequestBody::FormUrlEncoded(_) | RequestBody::Multipart(_) => {
+                Err("Can't deserialize form data".to_string())
+            }
         }
     }
 }
 
+/// Value of a `multipart/form-data` field
+#[derive(Debug, Clone)]
+pub enum MultipartValue {
+    Text(String),
+    Bytes {
+        data: Vec<u8>,
+        file_name: Option<String>,
+        content_type: Option<String>,
+    },
+    File(DropFileItem),
+}
+
+/// Body of `multipart/form-data` request
+///
+/// ```rust
+/// use vertigo::{Multipart, RequestBuilder};
+///
+/// let body = Multipart::new()
+///     .text("title", "Holidays")
+///     .bytes("thumbnail", vec![0x89, 0x50], Some("thumb.png"), Some("image/png"));
+///
+/// let request = RequestBuilder::post("/api/upload").body_multipart(body);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Multipart {
+    parts: Vec<(String, MultipartValue)>,
+}
+
+impl Multipart {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn part(mut self, name: impl Into<String>, value: MultipartValue) -> Self {
+        self.parts.push((name.into(), value));
+        self
+    }
+
+    #[must_use]
+    pub fn text(self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.part(name, MultipartValue::Text(value.into()))
+    }
+
+    #[must_use]
+    pub fn bytes(
+        self,
+        name: impl Into<String>,
+        data: Vec<u8>,
+        file_name: Option<&str>,
+        content_type: Option<&str>,
+    ) -> Self {
+        self.part(
+            name,
+            MultipartValue::Bytes {
+                data,
+                file_name: file_name.map(ToString::to_string),
+                content_type: content_type.map(ToString::to_string),
+            },
+        )
+    }
+
+    /// Attach a file selected by the user (sent without copying into wasm memory)
+    #[must_use]
+    pub fn file(self, name: impl Into<String>, file: &DropFileItem) -> Self {
+        self.part(name, MultipartValue::File(file.clone()))
+    }
+
+    fn into_parts(self) -> Vec<SsrMultipartPart> {
+        self.parts
+            .into_iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    MultipartValue::Text(text) => SsrMultipartValue::Text { text },
+                    MultipartValue::Bytes {
+                        data,
+                        file_name,
+                        content_type,
+                    } => SsrMultipartValue::Bytes {
+                        data,
+                        file_name,
+                        content_type,
+                    },
+                    MultipartValue::File(file) => SsrMultipartValue::File {
+                        file: file.file_id(),
+                    },
+                };
+
+                SsrMultipartPart { name, value }
+            })
+            .collect()
+    }
+}
+
 /// Builder for typed requests.
 #[derive(Clone)]
 pub struct RequestBuilder {
@@ -34,6 +150,11 @@ pub struct RequestBuilder {
     bearer_auth: Computed<Option<String>>,
     body: Option<RequestBody>,
     ttl: Option<Duration>,
+    timeout: Option<Duration>,
+    retry: Option<RetryPolicy>,
+    abort_signal: Option<AbortSignal>,
+    on_progress: Option<Rc<dyn Fn(FetchProgress)>>,
+    offline_cache: bool,
 }
 
 impl RequestBuilder {
@@ -47,6 +168,11 @@ impl RequestBuilder {
             bearer_auth: init_bearer.to_computed(),
             body: None,
             ttl: None,
+            timeout: None,
+            retry: None,
+            abort_signal: None,
+            on_progress: None,
+            offline_cache: false,
         }
     }
 
@@ -105,6 +231,49 @@ impl RequestBuilder {
         self.body(RequestBody::Json(body))
     }
 
+    /// Send `text/plain` body
+    #[must_use]
+    pub fn body_text(self, text: impl Into<String>) -> Self {
+        self.body(RequestBody::Text(text.into()))
+    }
+
+    /// Send binary body (i.e. protobuf or CBOR) with given Content-Type
+    #[must_use]
+    pub fn body_bytes(self, data: Vec<u8>, content_type: impl Into<String>) -> Self {
+        self.body(RequestBody::Bytes {
+            data,
+            content_type: content_type.into(),
+        })
+    }
+
+    /// Send `application/x-www-form-urlencoded` body, like a classic HTML form
+    #[must_use]
+    pub fn body_form(
+        self,
+        fields: impl IntoIterator<Item = (impl Into<String>, impl Into<String>)>,
+    ) -> Self {
+        let fields = fields
+            .into_iter()
+            .map(|(name, value)| (name.into(), value.into()))
+            .collect();
+        self.body(RequestBody::FormUrlEncoded(fields))
+    }
+
+    /// Send `multipart/form-data` body
+    #[must_use]
+    pub fn body_multipart(self, body: Multipart) -> Self {
+        self.body(RequestBody::Multipart(body))
+    }
+
+    /// Send a file as the request body. The browser streams it from disk,
+    /// so it's not copied into wasm memory.
+    ///
+    /// `Content-Type` is set to file MIME type (if known and not set explicitly).
+    #[must_use]
+    pub fn body_file(self, file: &DropFileItem) -> Self {
+        self.body(RequestBody::File(file.clone()))
+    }
+
     #[must_use]
     pub fn headers(mut self, headers: BTreeMap<String, String>) -> Self {
         self.headers = headers;
@@ -140,32 +309,157 @@ impl RequestBuilder {
         self.ttl
     }
 
+    /// Keep successful responses in the browser's IndexedDB, so [LazyCache] and [LazyListCache]
+    /// using this request can show them after reload or without network connection.
+    ///
+    /// Stored response younger than `ttl_*` is used without fetching, an older one (or any, if no ttl is set)
+    /// is shown as stale data while revalidating. If revalidation fails with a network error, stale data is kept.
+    /// Responses are keyed by the whole request, including the `Authorization` header.
+    /// Only the most recently stored responses are kept, use [Driver::offline_cache_clear](crate::Driver::offline_cache_clear)
+    /// to remove all of them.
+    ///
+    /// ```rust
+    /// use vertigo::RequestBuilder;
+    ///
+    /// let orders = RequestBuilder::get("/api/orders")
+    ///     .ttl_minutes(5)
+    ///     .offline_cache()
+    ///     .lazy_cache(|status, body| {
+    ///         if status == 200 {
+    ///             Some(body.into::<Vec<String>>())
+    ///         } else {
+    ///             None
+    ///         }
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn offline_cache(mut self) -> Self {
+        self.offline_cache = true;
+        self
+    }
+
+    #[must_use]
+    pub fn get_offline_cache(&self) -> bool {
+        self.offline_cache
+    }
+
+    /// Report upload and download progress of the request (in the browser only).
+    ///
+    /// ```rust
+    /// use vertigo::{DropFileItem, RequestBuilder, Value};
+    ///
+    /// fn upload(file: &DropFileItem, percent: &Value<u32>) -> RequestBuilder {
+    ///     let percent = percent.clone();
+    ///
+    ///     RequestBuilder::post("/api/upload")
+    ///         .body_file(file)
+    ///         .on_progress(move |progress| {
+    ///             if let Some(ratio) = progress.upload_ratio() {
+    ///                 percent.set((ratio * 100.0) as u32);
+    ///             }
+    ///         })
+    /// }
+    /// ```
+    #[must_use]
+    pub fn on_progress(mut self, on_progress: impl Fn(FetchProgress) + 'static) -> Self {
+        self.on_progress = Some(Rc::new(on_progress));
+        self
+    }
+
+    /// Keep progress of the request in the [Value] (see [RequestBuilder::on_progress])
+    #[must_use]
+    pub fn progress(self, value: &Value<FetchProgress>) -> Self {
+        let value = value.clone();
+        self.on_progress(move |progress| value.set(progress))
+    }
+
+    /// Fail the request (and abort it) if the response doesn't arrive within given time
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retry failed request (applies only to idempotent methods, see [RetryPolicy])
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use vertigo::{RequestBuilder, RetryPolicy};
+    ///
+    /// let request = RequestBuilder::get("/api/posts")
+    ///     .timeout(Duration::from_secs(10))
+    ///     .retry(RetryPolicy::exponential(3, Duration::from_millis(500)));
+    /// ```
+    #[must_use]
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Abort the request when the [AbortHandle](crate::AbortHandle) is aborted or dropped.
+    ///
+    /// Applies to every request made with this builder, including [LazyCache] refreshes.
+    #[must_use]
+    pub fn abort_signal(mut self, signal: AbortSignal) -> Self {
+        self.abort_signal = Some(signal);
+        self
+    }
+
     pub fn to_request(self, token: Option<String>) -> SsrFetchRequest {
         let mut headers = self.headers;
 
         let body = match self.body {
             None => SsrFetchRequestBody::None,
             Some(RequestBody::Json(data)) => {
-                if !headers.contains_key("Content-Type") {
-                    headers.insert(
-                        "Content-Type".into(),
-                        "application/json;charset=UTF-8".into(),
-                    );
-                }
+                set_default_content_type(&mut headers, "application/json;charset=UTF-8");
                 SsrFetchRequestBody::Data { data }
             }
+            Some(RequestBody::File(file)) => {
+                if !file.mime_type.is_empty() {
+                    set_default_content_type(&mut headers, &file.mime_type);
+                }
+                SsrFetchRequestBody::File {
+                    file: file.file_id(),
+                }
+            }
+            Some(RequestBody::Text(text)) => {
+                set_default_content_type(&mut headers, "text/plain;charset=UTF-8");
+                SsrFetchRequestBody::Text { text }
+            }
+            Some(RequestBody::Bytes { data, content_type }) => {
+                set_default_content_type(&mut headers, &content_type);
+                SsrFetchRequestBody::Bytes { data }
+            }
+            Some(RequestBody::FormUrlEncoded(fields)) => {
+                set_default_content_type(
+                    &mut headers,
+                    "application/x-www-form-urlencoded;charset=UTF-8",
+                );
+                let fields = fields
+                    .into_iter()
+                    .map(|(name, value)| SsrFormField { name, value })
+                    .collect();
+                SsrFetchRequestBody::FormUrlEncoded { fields }
+            }
+            Some(RequestBody::Multipart(multipart)) => SsrFetchRequestBody::Multipart {
+                parts: multipart.into_parts(),
+            },
         };
 
         if let Some(token) = token {
             headers.insert("Authorization".into(), format!("Bearer {token}"));
         }
 
-        SsrFetchRequest {
+        let mut request = SsrFetchRequest {
             method: self.method,
             url: self.url,
             headers,
             body,
-        }
+        };
+
+        api_fetch_interceptors().intercept_request(&mut request);
+
+        request
     }
 
     pub fn to_request_context(self, context: &Context) -> SsrFetchRequest {
@@ -177,13 +471,126 @@ impl RequestBuilder {
     pub async fn call(self) -> RequestResponse {
         let token = transaction(|context| self.get_bearer_auth().get(context));
 
-        let request = self.to_request(token);
+        // The builder keeps the file (if any) available until the request is done
+        let request = self.clone().to_request(token);
 
-        let result = api_fetch().fetch(request.clone()).await;
+        let result = self.send(request.clone()).await;
 
         RequestResponse::new(request, result)
     }
 
+    /// Execute the request respecting timeout, retry policy, abort signal of this builder and response interceptors
+    pub(crate) async fn send(&self, request: SsrFetchRequest) -> SsrFetchResponse {
+        let Some(signal) = &self.abort_signal else {
+            return self.send_intercepted(request).await;
+        };
+
+        let aborted = async {
+            signal.wait().await;
+            SsrFetchResponse::Err {
+                message: "Request aborted".to_string(),
+            }
+        };
+
+        // Abort is polled first, so already aborted signal prevents sending the request
+        race(aborted, self.send_intercepted(request)).await
+    }
+
+    async fn send_intercepted(&self, request: SsrFetchRequest) -> SsrFetchResponse {
+        let response = self.send_with_retry(request.clone()).await;
+
+        api_fetch_interceptors()
+            .intercept_response(request, response, |request| self.send_with_retry(request))
+            .await
+    }
+
+    async fn send_with_retry(&self, request: SsrFetchRequest) -> SsrFetchResponse {
+        let retry = self.retry.filter(|_| self.method.is_idempotent());
+        let mut attempt = 0;
+
+        loop {
+            let response = self.send_with_timeout(request.clone()).await;
+
+            match retry {
+                Some(retry) if retry.should_retry(attempt, &response) => {
+                    sleep(retry.delay(attempt)).await;
+                    attempt += 1;
+                }
+                _ => return response,
+            }
+        }
+    }
+
+    async fn send_with_timeout(&self, request: SsrFetchRequest) -> SsrFetchResponse {
+        let api = api_fetch();
+        let fetch = api.fetch(request, self.on_progress.clone());
+
+        let Some(timeout) = self.timeout else {
+            return fetch.await;
+        };
+
+        let timed_out = async move {
+            sleep(timeout).await;
+            SsrFetchResponse::Err {
+                message: format!("Request timed out after {} ms", timeout.as_millis()),
+            }
+        };
+
+        race(fetch, timed_out).await
+    }
+
+    /// Send the request and receive the response body incrementally, as it arrives
+    /// (i.e. large downloads or NDJSON/SSE-like endpoints).
+    ///
+    /// Dropping returned resource aborts the request. Streaming is available only in the browser,
+    /// during SSR the callback receives [FetchStreamEvent::Error].
+    ///
+    /// The request goes through [request interceptors](crate::Driver::intercept_request),
+    /// but the response is not passed to [response interceptors](crate::Driver::intercept_response).
+    ///
+    /// ```rust
+    /// use vertigo::{FetchStreamEvent, LineDecoder, RequestBuilder, dev::ValueMut};
+    ///
+    /// let decoder = ValueMut::new(LineDecoder::new());
+    ///
+    /// let drop_stream = RequestBuilder::get("/api/events").stream(move |event| match event {
+    ///     FetchStreamEvent::Chunk { data } => {
+    ///         for line in decoder.change(|decoder| decoder.push(&data)) {
+    ///             vertigo::log::info!("event: {line}");
+    ///         }
+    ///     }
+    ///     FetchStreamEvent::Error { message } => vertigo::log::error!("{message}"),
+    ///     _ => {}
+    /// });
+    /// ```
+    #[must_use]
+    pub fn stream(self, on_event: impl Fn(FetchStreamEvent) + 'static) -> DropResource {
+        let token = transaction(|context| self.get_bearer_auth().get(context));
+
+        // Keep the file (if any) available until the request is done
+        let body = self.body.clone();
+
+        let request = self.to_request(token);
+
+        let (callback, drop_callback) = api_callbacks().register(move |value| {
+            let _body = &body;
+
+            match from_json::<FetchStreamEvent>(value) {
+                Ok(event) => on_event(event),
+                Err(err) => log::error!("fetch stream -> decode error = {err}"),
+            }
+
+            JsJson::Null
+        });
+
+        api_browser_command().fetch_stream(request, callback);
+
+        DropResource::new(move || {
+            drop_callback.off();
+            api_browser_command().fetch_stream_abort(callback);
+        })
+    }
+
     /// Create a `LazyCache` that deserializes `T` from the given URL.
     #[must_use]
     pub fn lazy_cache<T: PartialEq>(
@@ -215,30 +622,65 @@ impl RequestResponse {
         RequestResponse { request, response }
     }
 
+    /// The request which was sent (after request interceptors)
+    pub fn request(&self) -> &SsrFetchRequest {
+        &self.request
+    }
+
+    pub fn response(&self) -> &SsrFetchResponse {
+        &self.response
+    }
+
+    pub fn into_parts(self) -> (SsrFetchRequest, SsrFetchResponse) {
+        (self.request, self.response)
+    }
+
     pub fn status(&self) -> Option<u32> {
-        if let SsrFetchResponse::Ok {
-            status,
-            response: _,
-        } = &self.response
-        {
+        if let SsrFetchResponse::Ok { status, .. } = &self.response {
             return Some(*status);
         }
 
         None
     }
 
+    /// Response headers (with lowercase names), `None` in case of network error
+    pub fn headers(&self) -> Option<&BTreeMap<String, String>> {
+        if let SsrFetchResponse::Ok { headers, .. } = &self.response {
+            return Some(headers);
+        }
+
+        None
+    }
+
+    /// Get response header value (name is case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers()?
+            .get(&name.to_lowercase())
+            .map(String::as_str)
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.header("content-type")
+    }
+
     pub fn into<T>(
         self,
         convert: impl Fn(u32, RequestBody) -> Option<Result<T, String>>,
     ) -> Result<T, String> {
         let result: Result<T, String> = match self.response {
-            SsrFetchResponse::Ok { status, response } => {
+            SsrFetchResponse::Ok {
+                status,
+                headers,
+                response,
+            } => {
                 let data = match response {
                     SsrFetchResponseContent::Json(json_response) => {
                         convert(status, RequestBody::Json(json_response))
                     }
-                    SsrFetchResponseContent::Text(_) => {
-                        return Err("Tried to decode text/plain reponse".to_string());
+                    SsrFetchResponseContent::Text(text) => convert(status, RequestBody::Text(text)),
+                    SsrFetchResponseContent::Bytes(data) => {
+                        let content_type = headers.get("content-type").cloned().unwrap_or_default();
+                        convert(status, RequestBody::Bytes { data, content_type })
                     }
                 };
 
@@ -266,9 +708,33 @@ impl RequestResponse {
         self.into(|_, response_body| Some(response_body.into::<T>()))
     }
 
+    /// Get raw body of a binary or text response
+    pub fn into_bytes(self) -> Result<Vec<u8>, String> {
+        self.into(|_, response_body| {
+            Some(match response_body {
+                RequestBody::Bytes { data, .. } => Ok(data),
+                RequestBody::Text(text) => Ok(text.into_bytes()),
+                _ => Err("Expected binary or text response".to_string()),
+            })
+        })
+    }
+
+    /// Get body of a text response (binary response is decoded as lossy UTF-8)
+    pub fn into_text(self) -> Result<String, String> {
+        self.into(|_, response_body| {
+            Some(match response_body {
+                RequestBody::Text(text) => Ok(text),
+                RequestBody::Bytes { data, .. } => Ok(String::from_utf8_lossy(&data).into_owned()),
+                _ => Err("Expected text or binary response".to_string()),
+            })
+        })
+    }
+
     pub fn into_error_message<T>(self) -> Result<T, String> {
         let body = match self.response {
-            SsrFetchResponse::Ok { status, response } => {
+            SsrFetchResponse::Ok {
+                status, response, ..
+            } => {
                 format!("API error {status}: {response:#?}")
             }
             SsrFetchResponse::Err { message } => format!("Network error: {message}"),
@@ -277,3 +743,263 @@ impl RequestResponse {
         Err(body)
     }
 }
+
+/// Resolve with the result of the future which finishes first, the other one is dropped
+pub(crate) async fn race<T>(first: impl Future<Output = T>, second: impl Future<Output = T>) -> T {
+    let mut first = pin!(first);
+    let mut second = pin!(second);
+
+    poll_fn(|cx| {
+        if let Poll::Ready(value) = first.as_mut().poll(cx) {
+            return Poll::Ready(value);
+        }
+
+        second.as_mut().poll(cx)
+    })
+    .await
+}
+
+async fn sleep(duration: Duration) {
+    let (sender, receiver) = FutureBox::new();
+    let time = u32::try_from(duration.as_millis()).unwrap_or(u32::MAX);
+
+    // The timer is cleared if the future is dropped earlier
+    let _timer = api_timers().timeout(time, move || sender.publish(()));
+
+    receiver.await
+}
+
+/// Set `Content-Type` unless it was set by the user (header names are case-insensitive)
+fn set_default_content_type(headers: &mut BTreeMap<String, String>, content_type: &str) {
+    let is_set = headers
+        .keys()
+        .any(|name| name.eq_ignore_ascii_case("content-type"));
+
+    if !is_set {
+        headers.insert("Content-Type".into(), content_type.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, rc::Rc, time::Duration};
+
+    use crate::{
+        AbortHandle, FetchMethod, JsJson, RequestBody, RequestBuilder, RequestResponse,
+        RetryPolicy,
+        dev::{
+            SsrFetchRequest, SsrFetchRequestBody, SsrFetchResponse, SsrFetchResponseContent,
+            ValueMut,
+        },
+        driver_module::api::{api_fetch, api_timers},
+    };
+
+    fn response(content_type: &str, content: SsrFetchResponseContent) -> RequestResponse {
+        let request = SsrFetchRequest {
+            method: FetchMethod::GET,
+            url: "https://test.com/api".into(),
+            headers: BTreeMap::new(),
+            body: SsrFetchRequestBody::None,
+        };
+
+        RequestResponse::new(
+            request,
+            SsrFetchResponse::Ok {
+                status: 200,
+                headers: BTreeMap::from([("content-type".to_string(), content_type.to_string())]),
+                response: content,
+            },
+        )
+    }
+
+    fn mock_fetch(status: impl Fn(u32) -> u32 + 'static) -> Rc<ValueMut<u32>> {
+        let calls = Rc::new(ValueMut::new(0));
+
+        api_fetch().set_mock_handler({
+            let calls = calls.clone();
+            move |_| {
+                let attempt = calls.change(|calls| {
+                    *calls += 1;
+                    *calls
+                });
+
+                SsrFetchResponse::Ok {
+                    status: status(attempt),
+                    headers: BTreeMap::new(),
+                    response: SsrFetchResponseContent::Json(JsJson::Null),
+                }
+            }
+        });
+
+        calls
+    }
+
+    #[tokio::test]
+    async fn test_retry_idempotent_request() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                api_timers().mock_zero_timeouts();
+
+                let calls = mock_fetch(|attempt| if attempt < 3 { 503 } else { 200 });
+                let policy = RetryPolicy::exponential(5, Duration::ZERO);
+
+                let response = RequestBuilder::get("https://test.com/api")
+                    .retry(policy)
+                    .call()
+                    .await;
+                assert_eq!(response.status(), Some(200));
+                assert_eq!(calls.get(), 3);
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_retry_not_idempotent_request() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                api_timers().mock_zero_timeouts();
+
+                let calls = mock_fetch(|_| 503);
+                let policy = RetryPolicy::exponential(5, Duration::ZERO);
+
+                // POST is not idempotent, so it's not retried
+                let response = RequestBuilder::post("https://test.com/api")
+                    .retry(policy)
+                    .call()
+                    .await;
+                assert_eq!(response.status(), Some(503));
+                assert_eq!(calls.get(), 1);
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_retry_limit() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                api_timers().mock_zero_timeouts();
+
+                let calls = mock_fetch(|_| 503);
+                let policy = RetryPolicy::exponential(2, Duration::ZERO);
+
+                // First attempt and 2 retries, the last response is returned
+                let response = RequestBuilder::get("https://test.com/api")
+                    .retry(policy)
+                    .call()
+                    .await;
+                assert_eq!(response.status(), Some(503));
+                assert_eq!(calls.get(), 3);
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_aborted_request() {
+        let calls = mock_fetch(|_| 200);
+
+        let handle = AbortHandle::new();
+        let request = RequestBuilder::get("https://test.com/api").abort_signal(handle.signal());
+        drop(handle);
+
+        let response = request.call().await;
+        assert_eq!(response.status(), None);
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn test_content_type_case_insensitive() {
+        let request = RequestBuilder::post("https://test.com/api")
+            .set_header("content-type", "application/vnd.api+json")
+            .body_json(JsJson::Null)
+            .to_request(None);
+
+        let expected = BTreeMap::from([(
+            "content-type".to_string(),
+            "application/vnd.api+json".to_string(),
+        )]);
+        assert_eq!(request.headers, expected);
+
+        let request = RequestBuilder::post("https://test.com/api")
+            .body_text("Hello")
+            .to_request(None);
+
+        let expected = BTreeMap::from([(
+            "Content-Type".to_string(),
+            "text/plain;charset=UTF-8".to_string(),
+        )]);
+        assert_eq!(request.headers, expected);
+    }
+
+    #[test]
+    fn test_response_headers() {
+        let text = response("text/plain", SsrFetchResponseContent::Text("Hello".into()));
+        assert_eq!(text.header("Content-Type"), Some("text/plain"));
+        assert_eq!(text.content_type(), Some("text/plain"));
+        assert_eq!(text.headers().map(|headers| headers.len()), Some(1));
+
+        let error = RequestResponse::new(
+            text.request().clone(),
+            SsrFetchResponse::Err {
+                message: "Network error".into(),
+            },
+        );
+        assert_eq!(error.headers(), None);
+        assert_eq!(error.content_type(), None);
+    }
+
+    #[test]
+    fn test_response_into_bytes() {
+        let bytes = response(
+            "image/png",
+            SsrFetchResponseContent::Bytes(vec![137, 80, 78, 71]),
+        );
+        assert_eq!(bytes.into_bytes(), Ok(vec![137, 80, 78, 71]));
+
+        let text = response("text/plain", SsrFetchResponseContent::Text("Hi".into()));
+        assert_eq!(text.into_bytes(), Ok(b"Hi".to_vec()));
+
+        let json = response(
+            "application/json",
+            SsrFetchResponseContent::Json(JsJson::Null),
+        );
+        assert!(json.into_bytes().is_err());
+    }
+
+    #[test]
+    fn test_response_into_text() {
+        let text = response("text/csv", SsrFetchResponseContent::Text("a,b".into()));
+        assert_eq!(text.into_text(), Ok("a,b".to_string()));
+
+        let bytes = response(
+            "application/octet-stream",
+            SsrFetchResponseContent::Bytes(vec![b'o', b'k', 0xff]),
+        );
+        assert_eq!(bytes.into_text(), Ok("ok\u{fffd}".to_string()));
+
+        let json = response(
+            "application/json",
+            SsrFetchResponseContent::Json(JsJson::Null),
+        );
+        assert!(json.into_text().is_err());
+    }
+
+    #[test]
+    fn test_response_text_body() {
+        let text = response("text/plain", SsrFetchResponseContent::Text("Hello".into()));
+
+        let body = text.into(|status, body| {
+            Some(match body {
+                RequestBody::Text(text) => Ok((status, text)),
+                _ => Err("Expected text body".to_string()),
+            })
+        });
+        assert_eq!(body, Ok((200, "Hello".to_string())));
+
+        // Text is deserialized as JSON string
+        let text = response("text/plain", SsrFetchResponseContent::Text("Hello".into()));
+        assert_eq!(text.into_data::<String>(), Ok("Hello".to_string()));
+    }
+}
@@ -24,6 +24,15 @@ impl<T> From<ResourceError> for Resource<T> {
     }
 }
 
+impl<T> From<Result<T, String>> for Resource<T> {
+    fn from(result: Result<T, String>) -> Resource<T> {
+        match result {
+            Ok(value) => Resource::Ready(value),
+            Err(message) => Resource::Error(message),
+        }
+    }
+}
+
 impl<T> Resource<T> {
     /// Convert into a `Result` so that `?` can be used inside functions
     /// returning another `Resource`. Use `resource.into_result()?` instead
@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use crate::dev::SsrFetchResponse;
+
+/// Retry policy with exponential backoff (see [RequestBuilder::retry](crate::RequestBuilder::retry)).
+///
+/// Requests are retried after network errors, timeouts and responses with status
+/// 408, 429, 500, 502, 503 or 504. Only idempotent methods (GET, HEAD, PUT, DELETE, OPTIONS, TRACE) are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Retry up to `max_retries` times, waiting `initial_delay` before the first retry
+    /// and doubling the delay before every next one.
+    pub fn exponential(max_retries: u32, initial_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            initial_delay,
+            max_delay: Duration::from_secs(30),
+        }
+    }
+
+    /// Limit the delay between retries (30 seconds by default)
+    #[must_use]
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Delay before the retry number `retry` (counted from 0)
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry);
+        self.initial_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+
+    pub(crate) fn should_retry(&self, retry: u32, response: &SsrFetchResponse) -> bool {
+        retry < self.max_retries && response.is_retriable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RetryPolicy;
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy::exponential(5, Duration::from_millis(100))
+            .max_delay(Duration::from_secs(1));
+
+        assert_eq!(policy.delay(0), Duration::from_millis(100));
+        assert_eq!(policy.delay(1), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(800));
+        assert_eq!(policy.delay(4), Duration::from_secs(1));
+        assert_eq!(policy.delay(40), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,341 @@
+use std::{collections::VecDeque, rc::Rc};
+
+use crate::{
+    Computed, Context, DropResource, Reactive, ToComputed, Value,
+    computed::{get_dependencies, struct_mut::ValueMut},
+};
+
+struct HistoryInner<T> {
+    limit: usize,
+    past: ValueMut<VecDeque<T>>,
+    future: ValueMut<Vec<T>>,
+    /// Number of undo and redo steps, for reactive `can_undo`/`can_redo`
+    depth: Value<(usize, usize)>,
+    /// A snapshot was already taken in the current transaction
+    group_open: Rc<ValueMut<bool>>,
+    _transaction_end: DropResource,
+}
+
+impl<T> HistoryInner<T> {
+    fn update_depth(&self) {
+        let past = self.past.map(|past| past.len());
+        let future = self.future.map(|future| future.len());
+        self.depth.set((past, future));
+    }
+}
+
+/// A [Value] which keeps history of its changes, so they can be undone and redone.
+///
+/// A snapshot of the previous value is taken on the first change in a [transaction](crate::transaction),
+/// so all changes made inside a single transaction are undone as one step.
+/// Setting a new value clears the redo history, at most `limit` undo steps are kept.
+///
+/// ```rust
+/// use vertigo::{HistoryValue, transaction};
+///
+/// let text = HistoryValue::new("".to_string(), 100);
+///
+/// text.set("Hello".to_string());
+///
+/// // Single undo step
+/// transaction(|_| {
+///     text.change(|text| text.push_str(" world"));
+///     text.change(|text| text.push('!'));
+/// });
+///
+/// text.undo();
+///
+/// transaction(|context| {
+///     assert_eq!(text.get(context), "Hello");
+///     assert!(text.can_redo().get(context));
+/// });
+/// ```
+#[derive(Clone)]
+pub struct HistoryValue<T: Clone + PartialEq + 'static> {
+    value: Value<T>,
+    inner: Rc<HistoryInner<T>>,
+}
+
+impl<T: Clone + PartialEq + 'static> HistoryValue<T> {
+    pub fn new(value: T, limit: usize) -> Self {
+        let group_open = Rc::new(ValueMut::new(false));
+
+        let transaction_end = get_dependencies().hooks.on_after_transaction({
+            let group_open = group_open.clone();
+            move || group_open.set(false)
+        });
+
+        Self {
+            value: Value::new(value),
+            inner: Rc::new(HistoryInner {
+                limit,
+                past: ValueMut::new(VecDeque::new()),
+                future: ValueMut::new(Vec::new()),
+                depth: Value::new((0, 0)),
+                group_open,
+                _transaction_end: transaction_end,
+            }),
+        }
+    }
+
+    pub fn get(&self, context: &Context) -> T {
+        self.value.get(context)
+    }
+
+    pub fn set(&self, value: T) {
+        get_dependencies().transaction(|context| {
+            let current = self.value.get(context);
+
+            if current == value {
+                return;
+            }
+
+            if !self.inner.group_open.get() {
+                self.inner.group_open.set(true);
+                self.record(current);
+            }
+
+            self.value.set(value);
+        });
+    }
+
+    pub fn change(&self, change_fn: impl FnOnce(&mut T)) {
+        get_dependencies().transaction(|context| {
+            let mut value = self.value.get(context);
+            change_fn(&mut value);
+            self.set(value);
+        });
+    }
+
+    /// Restore the value from before the last change (or transaction). Returns false if there is nothing to undo.
+    pub fn undo(&self) -> bool {
+        get_dependencies().transaction(|context| {
+            let Some(previous) = self.inner.past.change(|past| past.pop_back()) else {
+                return false;
+            };
+
+            let current = self.value.get(context);
+            self.inner.future.change(|future| future.push(current));
+            self.restore(previous);
+            true
+        })
+    }
+
+    /// Reapply the last undone change. Returns false if there is nothing to redo.
+    pub fn redo(&self) -> bool {
+        get_dependencies().transaction(|context| {
+            let Some(next) = self.inner.future.change(|future| future.pop()) else {
+                return false;
+            };
+
+            let current = self.value.get(context);
+            self.inner.past.change(|past| past.push_back(current));
+            self.restore(next);
+            true
+        })
+    }
+
+    /// Forget the undo and redo history, keeping the current value
+    pub fn clear_history(&self) {
+        self.inner.past.set(VecDeque::new());
+        self.inner.future.set(Vec::new());
+        self.inner.update_depth();
+    }
+
+    /// Whether there is a change to undo (i.e. for enabling a toolbar button)
+    pub fn can_undo(&self) -> Computed<bool> {
+        self.inner.depth.map(|(past, _)| past > 0)
+    }
+
+    /// Whether there is an undone change to redo
+    pub fn can_redo(&self) -> Computed<bool> {
+        self.inner.depth.map(|(_, future)| future > 0)
+    }
+
+    pub fn to_computed(&self) -> Computed<T> {
+        self.value.to_computed()
+    }
+
+    fn record(&self, snapshot: T) {
+        self.inner.past.change(|past| {
+            past.push_back(snapshot);
+
+            while past.len() > self.inner.limit {
+                past.pop_front();
+            }
+        });
+
+        self.inner.future.set(Vec::new());
+        self.inner.update_depth();
+    }
+
+    fn restore(&self, value: T) {
+        // Next change starts a new undo step, even in the same transaction
+        self.inner.group_open.set(false);
+        self.value.set(value);
+        self.inner.update_depth();
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> PartialEq for HistoryValue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Reactive<T> for HistoryValue<T> {
+    fn set(&self, value: T) {
+        HistoryValue::set(self, value)
+    }
+
+    fn get(&self, context: &Context) -> T {
+        HistoryValue::get(self, context)
+    }
+
+    fn change(&self, change_fn: impl FnOnce(&mut T)) {
+        HistoryValue::change(self, change_fn)
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> ToComputed<T> for HistoryValue<T> {
+    fn to_computed(&self) -> Computed<T> {
+        self.to_computed()
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> ToComputed<T> for &HistoryValue<T> {
+    fn to_computed(&self) -> Computed<T> {
+        (*self).to_computed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::{Computed, DropResource, HistoryValue, computed::struct_mut::VecMut, transaction};
+
+    /// Record every change of the computed
+    fn record(computed: Computed<bool>) -> (Rc<VecMut<bool>>, DropResource) {
+        let changes = Rc::new(VecMut::new());
+
+        let subscription = computed.subscribe({
+            let changes = changes.clone();
+            move |value| changes.push(value)
+        });
+
+        (changes, subscription)
+    }
+
+    #[test]
+    fn test_history_value() {
+        let counter = HistoryValue::new(0, 2);
+        let can_undo = counter.can_undo();
+        let can_redo = counter.can_redo();
+
+        counter.set(1);
+        transaction(|_| {
+            counter.change(|value| *value += 1);
+            counter.change(|value| *value += 1);
+        });
+        counter.set(4);
+        transaction(|context| {
+            assert!(can_undo.get(context));
+            assert!(!can_redo.get(context));
+        });
+
+        // Limit of 2 steps dropped the oldest one
+        assert!(counter.undo());
+        assert!(counter.undo());
+        assert!(!counter.undo());
+        transaction(|context| {
+            assert_eq!(counter.get(context), 1);
+            assert!(!can_undo.get(context));
+            assert!(can_redo.get(context));
+        });
+
+        assert!(counter.redo());
+        transaction(|context| assert_eq!(counter.get(context), 3));
+
+        // New change clears redo history
+        counter.set(10);
+        assert!(!counter.redo());
+        assert!(counter.undo());
+        transaction(|context| assert_eq!(counter.get(context), 3));
+    }
+
+    #[test]
+    fn test_undo_groups_transaction() {
+        let counter = HistoryValue::new(0, 10);
+
+        transaction(|_| {
+            counter.set(1);
+
+            // End of a nested transaction doesn't close the group
+            transaction(|_| counter.set(2));
+            counter.set(3);
+        });
+
+        // Next transaction starts a new group
+        counter.set(4);
+        counter.set(5);
+
+        assert!(counter.undo());
+        transaction(|context| assert_eq!(counter.get(context), 4));
+        assert!(counter.undo());
+        transaction(|context| assert_eq!(counter.get(context), 3));
+        assert!(counter.undo());
+        transaction(|context| assert_eq!(counter.get(context), 0));
+        assert!(!counter.undo());
+    }
+
+    #[test]
+    fn test_set_after_undo_clears_redo() {
+        let counter = HistoryValue::new(0, 10);
+        let can_redo = counter.can_redo();
+
+        counter.set(1);
+        counter.set(2);
+        assert!(counter.undo());
+        transaction(|context| assert!(can_redo.get(context)));
+
+        counter.set(5);
+        transaction(|context| assert!(!can_redo.get(context)));
+        assert!(!counter.redo());
+
+        // Undone change is gone from the history, undo goes back to the value it was made on
+        assert!(counter.undo());
+        transaction(|context| assert_eq!(counter.get(context), 1));
+        assert!(counter.undo());
+        transaction(|context| assert_eq!(counter.get(context), 0));
+    }
+
+    #[test]
+    fn test_can_undo_can_redo_changes() {
+        let counter = HistoryValue::new(0, 10);
+        let (can_undo, _undo_subscription) = record(counter.can_undo());
+        let (can_redo, _redo_subscription) = record(counter.can_redo());
+
+        counter.set(1);
+        counter.set(2);
+        assert_eq!(can_undo.map(Vec::clone), vec![false, true]);
+
+        // Reported only when the first and the last step is crossed
+        counter.undo();
+        counter.undo();
+        assert_eq!(can_undo.map(Vec::clone), vec![false, true, false]);
+        assert_eq!(can_redo.map(Vec::clone), vec![false, true]);
+
+        counter.redo();
+        counter.redo();
+        assert_eq!(can_undo.map(Vec::clone), vec![false, true, false, true]);
+        assert_eq!(can_redo.map(Vec::clone), vec![false, true, false]);
+
+        counter.clear_history();
+        assert_eq!(
+            can_undo.map(Vec::clone),
+            vec![false, true, false, true, false]
+        );
+        assert_eq!(can_redo.map(Vec::clone), vec![false, true, false]);
+    }
+}
@@ -14,6 +14,8 @@
 //!   * [Computed] - Read-only (computed) reactive value
 //!   * [LazyCache] - Lazy cache for fetched resources
 //!   * [LazyListCache] - Lazy cache for fetched lists (optimized for CRUD operations)
+//!   * [HistoryValue] - Reactive value with undo/redo history
+//!   * [PersistentValue] - Reactive value kept in `localStorage` or `sessionStorage`
 //!   * [WsCollection] - Reactive collection driven by a server subscription over a WebSocket
 //!   * [store] - Wraps function to be used as a store (singleton) generator
 //! * Others
@@ -41,8 +43,10 @@ mod exports;
 pub mod external_api;
 mod fetch;
 mod future_box;
+mod history_value;
 pub mod html_entities;
 mod instant;
+mod persistent_value;
 pub mod render;
 pub mod router;
 #[cfg(test)]
@@ -84,10 +88,14 @@ pub use dom::{
     dom_id::DomId,
     dom_node::DomNode,
     dom_text::DomText,
-    events::{ClickEvent, DropFileEvent, DropFileItem, IntersectionEvent, KeyDownEvent},
+    events::{
+        ClickEvent, DomEvent, DropFileEvent, DropFileItem, IntersectionEvent, KeyDownEvent,
+        KeyUpEvent, MouseEvent,
+    },
 };
 pub use dom_macro::{AttrGroup, AttrGroupValue, EmbedDom};
 pub use driver_module::{
+    api::{FetchInterception, JsCallback, ServerRequest, ServerResponse},
     driver::{Driver, FetchMethod, FetchResult, get_driver, transaction},
     js_value::{
         JsJson, JsJsonContext, JsJsonDeserialize, JsJsonNumber, JsJsonSerialize, from_json, to_json,
@@ -95,12 +103,18 @@ pub use driver_module::{
 };
 pub use exports::start_app;
 pub use fetch::{
+    abort_handle::{AbortHandle, AbortSignal},
+    fetch_progress::FetchProgress,
+    fetch_stream::{FetchStreamEvent, LineDecoder},
     lazy_cache::{self, LazyCache},
     lazy_list_cache::LazyListCache,
-    request_builder::{RequestBody, RequestBuilder, RequestResponse},
+    request_builder::{Multipart, MultipartValue, RequestBody, RequestBuilder, RequestResponse},
     resource::Resource,
+    retry_policy::RetryPolicy,
 };
+pub use history_value::HistoryValue;
 pub use instant::{Instant, InstantType};
+pub use persistent_value::PersistentValue;
 pub use render::collection::CollectionKey;
 pub use websocket::{WebsocketConnection, WebsocketMessage};
 pub use websocket_collection::{
@@ -392,6 +406,15 @@ pub use vertigo_macro::component;
 ///     )
 /// };
 /// ```
+///
+/// Promises returned by JavaScript can be awaited (resolves to `Err` during SSR):
+///
+/// ```rust
+/// # use vertigo::js;
+/// # async fn example() {
+/// let text = js! { window.navigator.clipboard.readText().await };
+/// # }
+/// ```
 #[macro_export]
 macro_rules! js {
     // Convert `#ref_node.anything` into `#[ref_node] anything` which can be handled by js_inner macro.
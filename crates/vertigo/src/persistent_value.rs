@@ -0,0 +1,300 @@
+use std::rc::Rc;
+
+use crate::{
+    Computed, Context, DropResource, JsJson, JsJsonDeserialize, JsJsonSerialize, Reactive,
+    ToComputed, Value,
+    computed::get_dependencies,
+    dev::command::{StorageKind, browser_response},
+    driver_module::api::{api_browser_command, api_callbacks},
+    from_json, get_driver,
+};
+
+struct PersistentInner<T> {
+    kind: StorageKind,
+    key: String,
+    default: T,
+    _watch: Option<DropResource>,
+}
+
+/// A [Value] persisted in the browser's `localStorage` or `sessionStorage` under the given key.
+///
+/// The value is loaded on creation, written to the storage on every [set](PersistentValue::set)
+/// or [change](PersistentValue::change), and updated when the key is changed in another tab.
+/// Values are stored as JSON, missing or undecodable entries fall back to the default
+/// (stored `null` is a valid value for an `Option`).
+///
+/// During SSR there is no storage, so the default is used and writes only update the value in memory.
+/// Because of that, the first render in the browser (which uses the stored value) can differ from
+/// the server-rendered HTML. Hydration patches the differences (nodes which don't match are replaced),
+/// so the page may flicker on load. Keep persisted values away from the parts of the page which
+/// matter for SSR (i.e. content indexed by search engines).
+///
+/// ```rust
+/// use vertigo::{PersistentValue, transaction};
+///
+/// let theme = PersistentValue::local("theme", "light".to_string());
+///
+/// theme.set("dark".to_string());
+///
+/// transaction(|context| {
+///     assert_eq!(theme.get(context), "dark");
+/// });
+/// ```
+#[derive(Clone)]
+pub struct PersistentValue<T: Clone + PartialEq + 'static> {
+    value: Value<T>,
+    inner: Rc<PersistentInner<T>>,
+}
+
+impl<T> PersistentValue<T>
+where
+    T: JsJsonSerialize + JsJsonDeserialize + Clone + PartialEq + 'static,
+{
+    /// Value kept in `localStorage` (shared between tabs, survives browser restart)
+    pub fn local(key: impl Into<String>, default: T) -> Self {
+        Self::new(StorageKind::Local, key.into(), default)
+    }
+
+    /// Value kept in `sessionStorage` (cleared when the tab is closed)
+    pub fn session(key: impl Into<String>, default: T) -> Self {
+        Self::new(StorageKind::Session, key.into(), default)
+    }
+
+    fn new(kind: StorageKind, key: String, default: T) -> Self {
+        if !get_driver().is_browser() {
+            return Self {
+                value: Value::new(default.clone()),
+                inner: Rc::new(PersistentInner {
+                    kind,
+                    key,
+                    default,
+                    _watch: None,
+                }),
+            };
+        }
+
+        let stored = api_browser_command().storage_get(kind, key.clone());
+        let value = Value::new(decode(&key, stored).unwrap_or_else(|| default.clone()));
+
+        let (callback, drop_callback) = api_callbacks().register({
+            let value = value.clone();
+            let key = key.clone();
+            let default = default.clone();
+
+            move |stored| {
+                let stored = match from_json::<browser_response::StorageGet>(stored) {
+                    Ok(stored) => stored.into_value(),
+                    Err(err) => {
+                        log::error!("PersistentValue {key} -> watch decode error = {err}");
+                        None
+                    }
+                };
+
+                value.set(decode(&key, stored).unwrap_or_else(|| default.clone()));
+                JsJson::Null
+            }
+        });
+
+        api_browser_command().storage_watch(kind, key.clone(), callback);
+
+        let watch = DropResource::new(move || {
+            api_browser_command().storage_unwatch(callback);
+            drop_callback.off();
+        });
+
+        Self {
+            value,
+            inner: Rc::new(PersistentInner {
+                kind,
+                key,
+                default,
+                _watch: Some(watch),
+            }),
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.inner.key
+    }
+
+    pub fn get(&self, context: &Context) -> T {
+        self.value.get(context)
+    }
+
+    pub fn set(&self, value: T) {
+        if get_driver().is_browser() {
+            api_browser_command().storage_set(
+                self.inner.kind,
+                self.inner.key.clone(),
+                value.clone().to_json(),
+            );
+        }
+
+        self.value.set(value);
+    }
+
+    pub fn change(&self, change_fn: impl FnOnce(&mut T)) {
+        get_dependencies().transaction(|context| {
+            let mut value = self.value.get(context);
+            change_fn(&mut value);
+            self.set(value);
+        });
+    }
+
+    /// Remove the key from the storage and reset the value to default
+    pub fn remove(&self) {
+        if get_driver().is_browser() {
+            api_browser_command().storage_remove(self.inner.kind, self.inner.key.clone());
+        }
+
+        self.value.set(self.inner.default.clone());
+    }
+
+    pub fn to_computed(&self) -> Computed<T> {
+        self.value.to_computed()
+    }
+}
+
+fn decode<T: JsJsonDeserialize>(key: &str, stored: Option<JsJson>) -> Option<T> {
+    from_json(stored?)
+        .inspect_err(|err| log::error!("PersistentValue {key} -> decode error = {err}"))
+        .ok()
+}
+
+impl<T: Clone + PartialEq + 'static> PartialEq for PersistentValue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T> Reactive<T> for PersistentValue<T>
+where
+    T: JsJsonSerialize + JsJsonDeserialize + Clone + PartialEq + 'static,
+{
+    fn set(&self, value: T) {
+        PersistentValue::set(self, value)
+    }
+
+    fn get(&self, context: &Context) -> T {
+        PersistentValue::get(self, context)
+    }
+
+    fn change(&self, change_fn: impl FnOnce(&mut T)) {
+        PersistentValue::change(self, change_fn)
+    }
+}
+
+impl<T> ToComputed<T> for PersistentValue<T>
+where
+    T: JsJsonSerialize + JsJsonDeserialize + Clone + PartialEq + 'static,
+{
+    fn to_computed(&self) -> Computed<T> {
+        self.to_computed()
+    }
+}
+
+impl<T> ToComputed<T> for &PersistentValue<T>
+where
+    T: JsJsonSerialize + JsJsonDeserialize + Clone + PartialEq + 'static,
+{
+    fn to_computed(&self) -> Computed<T> {
+        (*self).to_computed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::{
+        JsJson, JsJsonSerialize, PersistentValue,
+        computed::struct_mut::ValueMut,
+        dev::command::{CommandForBrowser, CommandForWasm, StorageKind, browser_response},
+        driver_module::api::api_command_wasm,
+        external_api::safe_wrappers::set_command_mock,
+        transaction,
+    };
+
+    #[test]
+    fn test_persistent_value_on_server() {
+        let counter = PersistentValue::local("counter", 1u32);
+
+        counter.change(|value| *value += 1);
+        transaction(|context| assert_eq!(counter.get(context), 2));
+
+        counter.remove();
+        transaction(|context| assert_eq!(counter.get(context), 1));
+    }
+
+    fn stored(value: Option<JsJson>) -> JsJson {
+        browser_response::StorageGet {
+            stored: value.is_some(),
+            value: value.unwrap_or(JsJson::Null),
+        }
+        .to_json()
+    }
+
+    #[test]
+    fn test_persistent_value_in_browser() {
+        let writes = Rc::new(ValueMut::new(Vec::new()));
+        let watcher = Rc::new(ValueMut::new(None));
+
+        set_command_mock({
+            let writes = writes.clone();
+            let watcher = watcher.clone();
+            move |command| match command {
+                CommandForBrowser::IsBrowser => {
+                    Some(browser_response::IsBrowser { value: true }.to_json())
+                }
+                CommandForBrowser::StorageGet { kind, key } => {
+                    assert_eq!(*kind, StorageKind::Session);
+                    let value = match key.as_str() {
+                        "filter" => Some(JsJson::Null),
+                        "page" => Some(3u32.to_json()),
+                        _ => None,
+                    };
+                    Some(stored(value))
+                }
+                CommandForBrowser::StorageSet { key, value, .. } => {
+                    writes.change(|writes| writes.push((key.clone(), value.clone())));
+                    None
+                }
+                CommandForBrowser::StorageWatch { key, callback, .. } if key == "page" => {
+                    watcher.set(Some(*callback));
+                    None
+                }
+                _ => None,
+            }
+        });
+
+        // Stored null is a value, missing key falls back to default
+        let filter = PersistentValue::session("filter", Some("all".to_string()));
+        let missing = PersistentValue::session("missing", Some("all".to_string()));
+        let page = PersistentValue::session("page", 1u32);
+
+        transaction(|context| {
+            assert_eq!(filter.get(context), None);
+            assert_eq!(missing.get(context), Some("all".to_string()));
+            assert_eq!(page.get(context), 3);
+        });
+
+        page.change(|page| *page += 1);
+        assert_eq!(writes.get(), vec![("page".to_string(), 4u32.to_json())]);
+
+        // Changed in another tab
+        let Some(callback_id) = watcher.get() else {
+            panic!("Storage is not watched");
+        };
+
+        let call = |value| {
+            api_command_wasm()
+                .command_from_js(CommandForWasm::CallbackCall { callback_id, value }.to_json());
+        };
+
+        call(stored(Some(7u32.to_json())));
+        transaction(|context| assert_eq!(page.get(context), 7));
+
+        call(stored(None));
+        transaction(|context| assert_eq!(page.get(context), 1));
+    }
+}
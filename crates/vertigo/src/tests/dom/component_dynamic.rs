@@ -183,11 +183,12 @@ fn test_on_events_grouping_and_spreading() {
             inner:on_dropfile={|_| ()}
             inner:on_input={|_| ()}
             inner:on_key_down={|_| true}
+            inner:on_key_up={|_| true}
             inner:on_load={|| ()}
-            inner:on_mouse_down={|| true}
-            inner:on_mouse_enter={|| ()}
-            inner:on_mouse_leave={|| ()}
-            inner:on_mouse_up={|| true}
+            inner:on_mouse_down={|_| true}
+            inner:on_mouse_enter={|_| ()}
+            inner:on_mouse_leave={|_| ()}
+            inner:on_mouse_up={|_| true}
             inner:on_submit={|| ()}
         />
     };
@@ -196,7 +197,7 @@ fn test_on_events_grouping_and_spreading() {
 
     assert_eq!(
         el_str,
-        "<input name='world' v-component='Everything' blur=2 change=3 click=4 drop=5 hook_keydown=1 input=6 keydown=7 load=8 mousedown=9 mouseenter=10 mouseleave=11 mouseup=12 submit=13 />"
+        "<input name='world' v-component='Everything' blur=2 change=3 click=4 drop=5 hook_keydown=1 input=6 keydown=7 keyup=8 load=9 mousedown=10 mouseenter=11 mouseleave=12 mouseup=13 submit=14 />"
     );
 }
 
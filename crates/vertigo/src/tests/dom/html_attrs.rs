@@ -1,6 +1,6 @@
 use crate as vertigo;
 use crate::dev::inspect::{DomDebugFragment, log_start};
-use crate::{DropFileEvent, dom};
+use crate::{DomEvent, DropFileEvent, dom};
 
 #[test]
 fn button_on_click() {
@@ -30,6 +30,21 @@ fn form_on_submit() {
     assert_eq!(html, "<form submit=1 />");
 }
 
+#[test]
+fn div_dom_events() {
+    let handler = |_: DomEvent| ();
+    let on_focus = || ();
+
+    log_start();
+
+    let _el = dom! {
+        <div on_scroll={handler} on:my-event={handler} {on_focus} />
+    };
+
+    let html = DomDebugFragment::from_log().to_pseudo_html();
+    assert_eq!(html, "<div event:my-event=2 event:scroll=1 focus=3 />");
+}
+
 #[test]
 fn no_keys() {
     let src = "cat.png";
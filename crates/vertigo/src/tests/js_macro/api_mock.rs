@@ -38,6 +38,11 @@ impl ApiMock {
         self
     }
 
+    pub async fn fetch_async(mut self) -> Self {
+        self.0.push(".fetch_async().await".to_string());
+        self
+    }
+
     // Utils:
 
     pub fn new_ref(id: i32) -> ApiMock {
@@ -24,6 +24,27 @@ fn test_method_call() {
     );
 }
 
+#[tokio::test]
+async fn test_await() {
+    use crate::js;
+    use api_mock as vertigo;
+
+    let result = js! {
+        navigator.clipboard.readText().await
+    };
+
+    result.expect(
+        r#"
+            vertigo::get_driver()
+                .api_access()
+                .root("navigator")
+                .get("clipboard")
+                .call("readText", [])
+                .fetch_async().await
+        "#,
+    );
+}
+
 #[test]
 fn test_property() {
     use crate::js;
@@ -60,6 +60,7 @@ impl DropFiles {
 
 fn format_line(file: &DropFileItem) -> String {
     let file_name = &file.name;
-    let size = file.data.len();
-    format!("file name={file_name} size={size}")
+    let size = file.size;
+    let mime_type = &file.mime_type;
+    format!("file name={file_name} size={size} type={mime_type}")
 }
@@ -1,12 +1,12 @@
-use vertigo::{Value, bind, component, css, dom};
+use vertigo::{MouseEvent, Value, bind, component, css, dom};
 
 #[component]
 pub fn MyInput(value: Value<String>) {
-    let mouse_in = || {
+    let mouse_in = |_: MouseEvent| {
         log::info!("enter");
     };
 
-    let mouse_out = || {
+    let mouse_out = |_: MouseEvent| {
         log::info!("out");
     };
 
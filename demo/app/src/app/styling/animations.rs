@@ -1,7 +1,8 @@
 use std::rc::Rc;
 
 use vertigo::{
-    Value, bind_spawn, component, css, dev::ValueMut, dom, get_driver, render::render_list,
+    MouseEvent, Value, bind_spawn, component, css, dev::ValueMut, dom, get_driver,
+    render::render_list,
 };
 
 use super::spinner::Spinner;
@@ -63,12 +64,12 @@ pub fn Animations() {
         state.start_animation().await;
     });
 
-    let on_mouse_enter = || {
-        log::info!("mouse enter");
+    let on_mouse_enter = |event: MouseEvent| {
+        log::info!("mouse enter at {}x{}", event.offset_x, event.offset_y);
     };
 
-    let on_mouse_leave = || {
-        log::info!("mouse leave");
+    let on_mouse_leave = |event: MouseEvent| {
+        log::info!("mouse leave at {}x{}", event.offset_x, event.offset_y);
     };
 
     let css_bg = css! {"
@@ -19,6 +19,23 @@ export default [
             sourcemaps(),
         ],
     },
+    {
+        input: 'crates/vertigo/src/driver_module/src_js/api/command/dom/streamPatches.test.ts',
+        output: [
+            {
+                sourcemap: true,
+                file: 'build/streamPatches.test.js',
+                format: 'cjs',
+            }
+        ],
+        plugins: [
+            typescript({
+                sourceMap: true,
+                inlineSources: true,
+            }),
+            sourcemaps(),
+        ],
+    },
     {
         input: 'crates/vertigo/src/driver_module/src_js/api/command/fetchExec.test.ts',
         output: [
@@ -0,0 +1,143 @@
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    time::{Duration, Instant},
+};
+use vertigo_cli::{BuildOpts, CommonOpts, ServeOpts, build, serve};
+
+const PORT: u16 = 5556;
+const REQUESTS: u32 = 200;
+
+/// Fetch page using plain HTTP/1.0 and return the response without headers
+fn get_page(path: &str) -> String {
+    let mut stream = match TcpStream::connect(("127.0.0.1", PORT)) {
+        Ok(stream) => stream,
+        Err(err) => panic!("connect failed: {err}"),
+    };
+
+    if let Err(err) = write!(stream, "GET {path} HTTP/1.0\r\nHost: 127.0.0.1\r\n\r\n") {
+        panic!("write failed: {err}");
+    }
+
+    let mut response = String::new();
+    if let Err(err) = stream.read_to_string(&mut response) {
+        panic!("read response failed: {err}");
+    }
+
+    assert!(
+        response.starts_with("HTTP/1.0 200") || response.starts_with("HTTP/1.1 200"),
+        "unexpected response: {response}"
+    );
+
+    match response.split_once("\r\n\r\n") {
+        Some((_, body)) => body.to_string(),
+        None => panic!("missing body in response: {response}"),
+    }
+}
+
+async fn bench(wasm_pool_size: Option<u32>) -> Duration {
+    use tokio::sync::oneshot;
+    let (sender, receiver) = oneshot::channel::<i32>();
+
+    let handle = tokio::runtime::Handle::current();
+    std::thread::spawn(move || {
+        let opts = ServeOpts {
+            common: CommonOpts {
+                dest_dir: "./build".to_string(),
+                log_local_time: None,
+            },
+            inner: serve::ServeOptsInner {
+                host: "127.0.0.1".into(),
+                port: PORT,
+                mount_point: "/".to_string(),
+                proxy: vec![],
+                env: vec![],
+                wasm_preload: false,
+                disable_hydration: false,
+                ssr_streaming: false,
+                ssr_timeout: 10_000,
+                ssr_timeout_policy: serve::SsrTimeoutPolicy::Partial,
+                ssr_body_limit: serve::DEFAULT_SSR_BODY_LIMIT,
+                wasm_pool_size,
+                threads: Some(1),
+            },
+        };
+
+        handle.block_on(async {
+            tokio::select! {
+                ret = serve::run(opts, None) => {
+                    if let Err(err) = ret {
+                        println!("Can't spawn vertigo-cli: {err:?}");
+                    }
+                }
+                _ = receiver => {}
+            }
+        });
+    });
+
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let elapsed = match tokio::task::spawn_blocking(|| {
+        // Warm up and remember reference output
+        let first = get_page("/");
+
+        let start = Instant::now();
+
+        for _ in 0..REQUESTS {
+            // Every request should render the same page, no state can leak from previous ones
+            assert_eq!(get_page("/"), first);
+        }
+
+        start.elapsed()
+    })
+    .await
+    {
+        Ok(elapsed) => elapsed,
+        Err(err) => panic!("benchmark failed: {err}"),
+    };
+
+    let _ = sender.send(1);
+
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    elapsed
+}
+
+#[tokio::test]
+#[ignore]
+async fn ssr_bench() {
+    // Go to project root
+    let _ = std::env::set_current_dir("..");
+
+    // Build basic test site
+    let opts = BuildOpts {
+        common: CommonOpts {
+            dest_dir: "./build".to_string(),
+            log_local_time: None,
+        },
+        inner: build::BuildOptsInner {
+            package_name: Some("vertigo-test-basic".to_string()),
+            public_path: None,
+            wasm_opt: Some(true),
+            release_mode: Some(true),
+            wasm_run_source_map: false,
+            cargo_opts: vec![],
+        },
+    };
+
+    println!("Running site build");
+
+    assert!(build::run(opts).is_ok());
+
+    let default_time = bench(None).await;
+    let pooled_time = bench(Some(4)).await;
+
+    println!(
+        "SSR without pooling: {} us per request",
+        default_time.as_micros() / REQUESTS as u128
+    );
+    println!(
+        "SSR with pooling: {} us per request",
+        pooled_time.as_micros() / REQUESTS as u128
+    );
+}
@@ -52,6 +52,11 @@ async fn basic() {
                 env: vec![],
                 wasm_preload: true,
                 disable_hydration: false,
+                ssr_streaming: false,
+                ssr_timeout: 10_000,
+                ssr_timeout_policy: serve::SsrTimeoutPolicy::Partial,
+                ssr_body_limit: serve::DEFAULT_SSR_BODY_LIMIT,
+                wasm_pool_size: None,
                 threads: None,
             },
         };